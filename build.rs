@@ -1,11 +1,29 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     tonic_prost_build::configure()
         .type_attribute(".", "#[derive(serde::Serialize, serde::Deserialize)]")
+        .field_attribute("api.RoleInput.policies", "#[serde(default)]")
+        .field_attribute("api.BookSeatsRequest.seat_ids", "#[serde(default)]")
+        .field_attribute("api.HoldSeatsRequest.seat_ids", "#[serde(default)]")
+        .field_attribute("api.QuotePriceRequest.seat_ids", "#[serde(default)]")
+        .field_attribute("api.CreatePaymentRequest.ticket_ids", "#[serde(default)]")
+        .field_attribute("api.RefundPolicyInput.tiers", "#[serde(default)]")
+        .field_attribute("api.SeatsInput.seats", "#[serde(default)]")
+        .field_attribute("api.RouteStopsInput.stops", "#[serde(default)]")
+        .field_attribute("api.LocationInput.aliases", "#[serde(default)]")
+        .field_attribute("api.PromoCodeInput.route_ids", "#[serde(default)]")
+        .field_attribute("api.ScheduleInput.days_of_week", "#[serde(default)]")
+        .field_attribute("api.ScheduleInput.exceptions", "#[serde(default)]")
         .compile_protos(
             &[
                 "proto/heath.proto",
                 "proto/auth.proto",
                 "proto/protected.proto",
+                "proto/admin.proto",
+                "proto/account.proto",
+                "proto/booking.proto",
+                "proto/trip.proto",
+                "proto/ticket.proto",
+                "proto/payment.proto",
             ],
             &["proto"],
         )?;
@@ -13,5 +31,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:rerun-if-changed=proto/heath.proto");
     println!("cargo:rerun-if-changed=proto/auth.proto");
     println!("cargo:rerun-if-changed=proto/protected.proto");
+    println!("cargo:rerun-if-changed=proto/admin.proto");
+    println!("cargo:rerun-if-changed=proto/account.proto");
+    println!("cargo:rerun-if-changed=proto/booking.proto");
+    println!("cargo:rerun-if-changed=proto/trip.proto");
+    println!("cargo:rerun-if-changed=proto/ticket.proto");
+    println!("cargo:rerun-if-changed=proto/payment.proto");
     Ok(())
 }
@@ -1,55 +1,235 @@
+CREATE TABLE role (
+    role_id SERIAL NOT NULL,
+    name VARCHAR(64) NOT NULL,
+    mfa_required BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (role_id),
+    UNIQUE (name)
+);
+
+CREATE TABLE policy (
+    policy_id SERIAL NOT NULL,
+    name VARCHAR(128) NOT NULL,
+    description VARCHAR(255) NOT NULL DEFAULT '',
+    PRIMARY KEY (policy_id),
+    UNIQUE (name)
+);
+
+CREATE TABLE role_policy (
+    role_id INT NOT NULL,
+    policy_id INT NOT NULL,
+    PRIMARY KEY (role_id, policy_id),
+    FOREIGN KEY (role_id) REFERENCES role (role_id) ON DELETE CASCADE,
+    FOREIGN KEY (policy_id) REFERENCES policy (policy_id) ON DELETE CASCADE
+);
+
 CREATE TABLE "user" (
-    user_id INT NOT NULL,
+    user_id SERIAL NOT NULL,
     email VARCHAR(128) NOT NULL,
     fullname VARCHAR(128) NOT NULL DEFAULT '',
     phone VARCHAR(16) NOT NULL DEFAULT '',
     address VARCHAR(128) NOT NULL DEFAULT '',
     role_id INT NOT NULL DEFAULT 0,
-    password VARCHAR(128) NOT NULL DEFAULT '',
-    PRIMARY KEY (user_id)
+    password VARCHAR(255) NOT NULL DEFAULT '',
+    email_verified BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (user_id),
+    UNIQUE (email),
+    FOREIGN KEY (role_id) REFERENCES role (role_id)
 );
 
 CREATE TABLE bus (
-    bus_id INT NOT NULL,
+    bus_id SERIAL NOT NULL,
     name VARCHAR(128),
     license_plate VARCHAR(16) NOT NULL,
     deleted BOOLEAN NOT NULL DEFAULT FALSE,
     PRIMARY KEY (bus_id)
 );
 
+-- Case- and accent-insensitive form of a place name for trip search ('Cần Thơ' -> 'can tho').
+-- The dictionary is named so the function can be IMMUTABLE and back indexes
+CREATE EXTENSION IF NOT EXISTS unaccent;
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+CREATE OR REPLACE FUNCTION fold_location(p_text TEXT)
+RETURNS TEXT
+LANGUAGE sql
+IMMUTABLE
+AS $$
+    SELECT lower(public.unaccent('public.unaccent'::regdictionary, p_text));
+$$;
+
+-- Catalogue of places routes run between. Names are unique once folded, and
+-- coordinates are WGS 84 degrees
+CREATE TABLE location (
+    location_id SERIAL NOT NULL,
+    name VARCHAR(128) NOT NULL,
+    province VARCHAR(128),
+    latitude DOUBLE PRECISION CHECK (latitude BETWEEN -90 AND 90),
+    longitude DOUBLE PRECISION CHECK (longitude BETWEEN -180 AND 180),
+    PRIMARY KEY (location_id)
+);
+
+CREATE UNIQUE INDEX location_name_idx ON location (fold_location(name));
+CREATE INDEX location_name_trgm_idx ON location USING gin (fold_location(name) gin_trgm_ops);
+
+-- Other names a location is searched by ('Sai Gon' for Ho Chi Minh), unique
+-- across the catalogue once folded
+CREATE TABLE location_alias (
+    location_id INT NOT NULL,
+    alias VARCHAR(128) NOT NULL,
+    PRIMARY KEY (location_id, alias),
+    FOREIGN KEY (location_id) REFERENCES location (location_id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX location_alias_idx ON location_alias (fold_location(alias));
+CREATE INDEX location_alias_trgm_idx ON location_alias USING gin (fold_location(alias) gin_trgm_ops);
+
+-- from_location and to_location copy the canonical names of the route's
+-- locations and follow them when a location is renamed
 CREATE TABLE route (
-    route_id INT NOT NULL,
+    route_id SERIAL NOT NULL,
     from_location VARCHAR(128) NOT NULL DEFAULT '',
     to_location VARCHAR(128) NOT NULL DEFAULT '',
+    from_location_id INT NOT NULL,
+    to_location_id INT NOT NULL,
     base_price INT NOT NULL,
     deleted BOOLEAN NOT NULL DEFAULT FALSE,
-    PRIMARY KEY (route_id)
+    PRIMARY KEY (route_id),
+    FOREIGN KEY (from_location_id) REFERENCES location (location_id),
+    FOREIGN KEY (to_location_id) REFERENCES location (location_id)
+);
+
+-- Stops a route makes between its ends, numbered from 1 in travel order. fare is
+-- the leg from the previous stop and offset_minutes the time since departure;
+-- the last leg to to_location costs what the stops leave of base_price
+CREATE TABLE route_stop (
+    route_id INT NOT NULL,
+    stop_order INT NOT NULL CHECK (stop_order >= 1),
+    location VARCHAR(128) NOT NULL,
+    location_id INT NOT NULL,
+    offset_minutes INT NOT NULL CHECK (offset_minutes >= 1),
+    fare INT NOT NULL CHECK (fare >= 1),
+    PRIMARY KEY (route_id, stop_order),
+    FOREIGN KEY (route_id) REFERENCES route (route_id) ON DELETE CASCADE,
+    FOREIGN KEY (location_id) REFERENCES location (location_id)
+);
+
+-- Recurring timetable the trip generator expands into trips. days_of_week is a
+-- bit mask from Monday (1) to Sunday (64); valid_until NULL runs indefinitely
+CREATE TABLE schedule (
+    schedule_id SERIAL NOT NULL,
+    route_id INT NOT NULL,
+    bus_id INT NOT NULL,
+    departure_time TIME NOT NULL,
+    duration_minutes INT NOT NULL CHECK (duration_minutes BETWEEN 1 AND 1440),
+    days_of_week INT NOT NULL CHECK (days_of_week BETWEEN 1 AND 127),
+    valid_from DATE NOT NULL,
+    valid_until DATE,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (schedule_id),
+    FOREIGN KEY (route_id) REFERENCES route (route_id),
+    FOREIGN KEY (bus_id) REFERENCES bus (bus_id),
+    CHECK (valid_until IS NULL OR valid_until >= valid_from)
+);
+
+-- Days a schedule does not run
+CREATE TABLE schedule_exception (
+    schedule_id INT NOT NULL,
+    service_date DATE NOT NULL,
+    PRIMARY KEY (schedule_id, service_date),
+    FOREIGN KEY (schedule_id) REFERENCES schedule (schedule_id) ON DELETE CASCADE
 );
 
+-- A bus runs one trip at a time: trips that are not cancelled may not
+-- overlap on the same bus
+CREATE EXTENSION IF NOT EXISTS btree_gist;
 CREATE TABLE trip (
-    trip_id INT NOT NULL,
+    trip_id SERIAL NOT NULL,
     route_id INT NOT NULL,
     start_date TIMESTAMP NOT NULL,
     end_date TIMESTAMP NOT NULL,
     bus_id INT NOT NULL,
+    cancelled BOOLEAN NOT NULL DEFAULT FALSE,
+    schedule_id INT,
     PRIMARY KEY (trip_id),
     FOREIGN KEY (route_id) REFERENCES route (route_id),
-    FOREIGN KEY (bus_id) REFERENCES bus (bus_id)
+    FOREIGN KEY (bus_id) REFERENCES bus (bus_id),
+    FOREIGN KEY (schedule_id) REFERENCES schedule (schedule_id),
+    -- A schedule departs once per start time, which keeps generation idempotent
+    UNIQUE (schedule_id, start_date),
+    CHECK (end_date > start_date),
+    CONSTRAINT trip_bus_no_overlap EXCLUDE USING gist (
+        bus_id WITH =,
+        tsrange(start_date, end_date) WITH &&
+    ) WHERE (NOT cancelled)
 );
 
+-- seat_row and seat_column place the seat on its deck; seeded seats have no layout
 CREATE TABLE seat (
-    seat_id INT NOT NULL,
+    seat_id SERIAL NOT NULL,
     bus_id INT NOT NULL,
     price INT NOT NULL DEFAULT 0,
     deleted BOOLEAN NOT NULL DEFAULT FALSE,
     name VARCHAR(128) NOT NULL,
+    seat_row INT CHECK (seat_row >= 1),
+    seat_column INT CHECK (seat_column >= 1),
+    deck INT NOT NULL DEFAULT 1 CHECK (deck BETWEEN 1 AND 2),
     PRIMARY KEY (seat_id),
     FOREIGN KEY (bus_id) REFERENCES bus (bus_id)
 );
 
+-- Seat names are unique among a bus's active seats
+CREATE UNIQUE INDEX seat_bus_name_idx ON seat (bus_id, name) WHERE NOT deleted;
+
+-- Discount codes applied when booking. promo_code.kind: 0 percentage, 1 fixed amount.
+-- value is the percentage (1-100) or the amount; max_discount caps a percentage discount.
+-- NULL limits and validity bounds are unlimited and open.
+CREATE TABLE promo_code (
+    promo_code_id SERIAL NOT NULL,
+    code VARCHAR(32) NOT NULL,
+    kind INT NOT NULL CHECK (kind BETWEEN 0 AND 1),
+    value INT NOT NULL CHECK (value > 0),
+    max_discount INT CHECK (max_discount > 0),
+    min_spend INT NOT NULL DEFAULT 0 CHECK (min_spend >= 0),
+    valid_from TIMESTAMP,
+    valid_until TIMESTAMP,
+    max_uses INT CHECK (max_uses > 0),
+    max_uses_per_user INT CHECK (max_uses_per_user > 0),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (promo_code_id),
+    UNIQUE (code),
+    CHECK (kind <> 0 OR value <= 100),
+    CHECK (valid_until > valid_from)
+);
+
+-- Routes a promo code is limited to; a code without rows applies to every route
+CREATE TABLE promo_code_route (
+    promo_code_id INT NOT NULL,
+    route_id INT NOT NULL,
+    PRIMARY KEY (promo_code_id, route_id),
+    FOREIGN KEY (promo_code_id) REFERENCES promo_code (promo_code_id) ON DELETE CASCADE,
+    FOREIGN KEY (route_id) REFERENCES route (route_id) ON DELETE CASCADE
+);
+
+-- One booking that used a promo code; it stops counting towards the usage limits once
+-- all of its tickets are cancelled or refunded
+CREATE TABLE promo_redemption (
+    promo_redemption_id SERIAL NOT NULL,
+    promo_code_id INT NOT NULL,
+    user_id INT NOT NULL,
+    discount INT NOT NULL CHECK (discount >= 0),
+    redeemed_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (promo_redemption_id),
+    FOREIGN KEY (promo_code_id) REFERENCES promo_code (promo_code_id),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id)
+);
+
+CREATE INDEX promo_redemption_code_idx ON promo_redemption (promo_code_id, user_id);
+
+-- ticket.status: 0 held, 1 booked, 2 paid, 3 checked in, 4 cancelled, 5 refunded, 6 no-show
 CREATE TABLE ticket (
-    ticket_id INT NOT NULL,
-    status INT NOT NULL DEFAULT 0,
+    ticket_id SERIAL NOT NULL,
+    status INT NOT NULL DEFAULT 0 CHECK (status BETWEEN 0 AND 6),
     price INT NOT NULL DEFAULT 0,
     trip_id INT NOT NULL,
     seat_id INT NOT NULL,
@@ -57,27 +237,277 @@ CREATE TABLE ticket (
     user_id INT NOT NULL,
     from_location VARCHAR(128),
     to_location VARCHAR(128),
+    refund_amount INT,
+    -- The ticket rides from stop from_stop to stop to_stop of route_itinerary
+    from_stop INT NOT NULL CHECK (from_stop >= 0),
+    to_stop INT NOT NULL,
+    -- price is what the ticket costs after discount, its share of the promo code discount
+    discount INT NOT NULL DEFAULT 0 CHECK (discount >= 0),
+    promo_redemption_id INT,
     PRIMARY KEY (ticket_id),
     FOREIGN KEY (trip_id) REFERENCES trip (trip_id),
+    FOREIGN KEY (promo_redemption_id) REFERENCES promo_redemption (promo_redemption_id),
     FOREIGN KEY (seat_id) REFERENCES seat (seat_id),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id),
+    CHECK (to_stop > from_stop),
+    -- A seat is sold at most once per trip and leg; cancelled (4) and refunded (5) tickets free it
+    CONSTRAINT ticket_seat_no_overlap EXCLUDE USING gist (
+        trip_id WITH =,
+        seat_id WITH =,
+        int4range(from_stop, to_stop) WITH &&
+    ) WHERE (status NOT IN (4, 5))
+);
+
+CREATE TABLE refresh_token (
+    refresh_token_id SERIAL NOT NULL,
+    family_id VARCHAR(64) NOT NULL,
+    user_id INT NOT NULL,
+    token_hash VARCHAR(64) NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    rotated_at TIMESTAMP,
+    revoked BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (refresh_token_id),
+    UNIQUE (token_hash),
     FOREIGN KEY (user_id) REFERENCES "user" (user_id)
 );
 
+CREATE INDEX refresh_token_family_idx ON refresh_token (family_id);
+CREATE INDEX refresh_token_user_idx ON refresh_token (user_id);
+
+CREATE TABLE revoked_token (
+    jti VARCHAR(64) NOT NULL,
+    sub VARCHAR(128) NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (jti)
+);
+
+-- Access tokens for `sub` issued before `revoked_before` are rejected
+CREATE TABLE subject_revocation (
+    sub VARCHAR(128) NOT NULL,
+    revoked_before TIMESTAMP NOT NULL,
+    PRIMARY KEY (sub)
+);
+
+-- Single-use links mailed on registration; only the SHA-256 of the token is stored
+CREATE TABLE email_verification (
+    token_hash VARCHAR(64) NOT NULL,
+    user_id INT NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (token_hash),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX email_verification_user_idx ON email_verification (user_id);
+
+-- Single-use password reset links; only the SHA-256 of the token is stored
+CREATE TABLE password_reset (
+    token_hash VARCHAR(64) NOT NULL,
+    user_id INT NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (token_hash),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX password_reset_user_idx ON password_reset (user_id);
+
+-- Failed login counters shared by all nodes when LOGIN_THROTTLE_STORE=postgres.
+-- attempt_key is `user:<email>` or `ip:<address>`.
+CREATE TABLE login_attempt (
+    attempt_key VARCHAR(160) NOT NULL,
+    failures INT NOT NULL DEFAULT 0,
+    window_started TIMESTAMP NOT NULL,
+    locked_until TIMESTAMP,
+    PRIMARY KEY (attempt_key)
+);
+
+-- TOTP second factor; confirmed_at stays NULL until the first code is accepted
+CREATE TABLE user_totp (
+    user_id INT NOT NULL,
+    secret VARCHAR(64) NOT NULL,
+    confirmed_at TIMESTAMP,
+    last_used_step BIGINT,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (user_id),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id) ON DELETE CASCADE
+);
+
+-- Single-use MFA recovery codes; only the SHA-256 of the code is stored
+CREATE TABLE mfa_recovery_code (
+    mfa_recovery_code_id SERIAL NOT NULL,
+    user_id INT NOT NULL,
+    code_hash VARCHAR(64) NOT NULL,
+    used_at TIMESTAMP,
+    PRIMARY KEY (mfa_recovery_code_id),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX mfa_recovery_code_user_idx ON mfa_recovery_code (user_id);
+
+-- Logins waiting for their second factor; only the SHA-256 of the token is stored
+CREATE TABLE mfa_challenge (
+    token_hash VARCHAR(64) NOT NULL,
+    user_id INT NOT NULL,
+    cookie BOOLEAN NOT NULL DEFAULT FALSE,
+    attempts INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (token_hash),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX mfa_challenge_user_idx ON mfa_challenge (user_id);
+
+-- Refund tiers per route: a cancellation at least min_hours_before hours before departure
+-- refunds refund_percent of the fare, taking the tier with the highest min_hours_before that
+-- applies; routes without a matching tier refund nothing
+CREATE TABLE route_refund_tier (
+    route_id INT NOT NULL,
+    min_hours_before INT NOT NULL CHECK (min_hours_before >= 0),
+    refund_percent INT NOT NULL CHECK (refund_percent BETWEEN 0 AND 100),
+    PRIMARY KEY (route_id, min_hours_before),
+    FOREIGN KEY (route_id) REFERENCES route (route_id) ON DELETE CASCADE
+);
+
+-- Every ticket status change; from_status is NULL when the ticket is created
+-- and actor_id NULL when the system made the change
+CREATE TABLE ticket_status_history (
+    ticket_status_history_id SERIAL NOT NULL,
+    ticket_id INT NOT NULL,
+    from_status INT,
+    to_status INT NOT NULL,
+    actor_id INT,
+    changed_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (ticket_status_history_id),
+    FOREIGN KEY (ticket_id) REFERENCES ticket (ticket_id) ON DELETE CASCADE,
+    FOREIGN KEY (actor_id) REFERENCES "user" (user_id) ON DELETE SET NULL
+);
+
+CREATE INDEX ticket_status_history_ticket_idx ON ticket_status_history (ticket_id);
+
+-- Seats reserved during checkout for the legs from_stop..to_stop; a hold past
+-- expires_at no longer blocks the seat
+CREATE TABLE seat_hold (
+    trip_id INT NOT NULL,
+    seat_id INT NOT NULL,
+    user_id INT NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    from_stop INT NOT NULL CHECK (from_stop >= 0),
+    to_stop INT NOT NULL,
+    PRIMARY KEY (trip_id, seat_id, from_stop),
+    FOREIGN KEY (trip_id) REFERENCES trip (trip_id),
+    FOREIGN KEY (seat_id) REFERENCES seat (seat_id),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id) ON DELETE CASCADE,
+    CHECK (to_stop > from_stop),
+    CONSTRAINT seat_hold_no_overlap EXCLUDE USING gist (
+        trip_id WITH =,
+        seat_id WITH =,
+        int4range(from_stop, to_stop) WITH &&
+    )
+);
+
+CREATE INDEX seat_hold_expires_idx ON seat_hold (expires_at);
+
+-- A payment for booked tickets through a provider; provider_ref is the provider's id for it.
+-- payment_intent.status: 0 pending, 1 succeeded, 2 failed, 3 expired
+-- expires_at is when its tickets are released unpaid, NULL when they never are
+CREATE TABLE payment_intent (
+    payment_intent_id SERIAL NOT NULL,
+    user_id INT NOT NULL,
+    provider VARCHAR(32) NOT NULL,
+    provider_ref VARCHAR(128) NOT NULL,
+    amount INT NOT NULL CHECK (amount >= 0),
+    currency VARCHAR(3) NOT NULL,
+    status INT NOT NULL DEFAULT 0 CHECK (status BETWEEN 0 AND 3),
+    checkout_url TEXT,
+    -- Paid for tickets that were no longer payable and given back
+    refunded_amount INT NOT NULL DEFAULT 0 CHECK (refunded_amount >= 0),
+    created_at TIMESTAMP NOT NULL,
+    expires_at TIMESTAMP,
+    completed_at TIMESTAMP,
+    PRIMARY KEY (payment_intent_id),
+    UNIQUE (provider, provider_ref),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id)
+);
+
+CREATE INDEX payment_intent_pending_idx ON payment_intent (expires_at) WHERE status = 0;
+
+CREATE TABLE payment_intent_ticket (
+    payment_intent_id INT NOT NULL,
+    ticket_id INT NOT NULL,
+    PRIMARY KEY (payment_intent_id, ticket_id),
+    FOREIGN KEY (payment_intent_id) REFERENCES payment_intent (payment_intent_id) ON DELETE CASCADE,
+    FOREIGN KEY (ticket_id) REFERENCES ticket (ticket_id)
+);
+
+CREATE INDEX payment_intent_ticket_ticket_idx ON payment_intent_ticket (ticket_id);
+
+-- Webhook events already applied, so a redelivered event changes nothing
+CREATE TABLE payment_event (
+    provider VARCHAR(32) NOT NULL,
+    event_id VARCHAR(128) NOT NULL,
+    payment_intent_id INT NOT NULL,
+    received_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (provider, event_id),
+    FOREIGN KEY (payment_intent_id) REFERENCES payment_intent (payment_intent_id) ON DELETE CASCADE
+);
+
 -- Computed seat count: use a view or query instead of a computed column
 CREATE OR REPLACE FUNCTION get_seat_count_for_bus(p_bus_id INT)
 RETURNS INT
 LANGUAGE sql
 STABLE
 AS $$
-    SELECT COUNT(*)::INT FROM seat WHERE bus_id = p_bus_id;
+    SELECT COUNT(*)::INT FROM seat WHERE bus_id = p_bus_id AND NOT deleted;
 $$;
 
 CREATE VIEW bus_with_seat_count AS
 SELECT b.*, get_seat_count_for_bus(b.bus_id) AS seat_count
 FROM bus b;
 
+-- Every stop of a route in travel order: from_location as stop 0, the rows of
+-- route_stop, then to_location. offset_minutes is NULL at the destination, which
+-- is reached at the trip's end_date; a leg from stop a to stop b costs
+-- b.fare_from_origin - a.fare_from_origin
+CREATE VIEW route_itinerary AS
+SELECT route_id, stop_order, location, offset_minutes, fare,
+       SUM(fare) OVER (PARTITION BY route_id ORDER BY stop_order)::INT AS fare_from_origin,
+       stop_order = MAX(stop_order) OVER (PARTITION BY route_id) AS terminal
+FROM (
+    SELECT route_id, 0 AS stop_order, from_location AS location, 0 AS offset_minutes, 0 AS fare
+    FROM route
+    UNION ALL
+    SELECT route_id, stop_order, location, offset_minutes, fare
+    FROM route_stop
+    UNION ALL
+    SELECT r.route_id, COALESCE(MAX(rs.stop_order), 0) + 1, r.to_location, NULL,
+           (r.base_price - COALESCE(SUM(rs.fare), 0))::INT
+    FROM route r
+    LEFT JOIN route_stop rs ON rs.route_id = r.route_id
+    GROUP BY r.route_id
+) stops;
+
 -- Seed data
 
+INSERT INTO role (role_id, name) VALUES
+(0, 'user'),
+(2, 'staff'),
+(3, 'admin');
+
+SELECT setval('role_role_id_seq', (SELECT MAX(role_id) FROM role));
+
+INSERT INTO policy (name, description) VALUES
+('protected.read', 'Read the caller''s own protected resources');
+
+INSERT INTO role_policy (role_id, policy_id)
+SELECT r.role_id, p.policy_id FROM role r CROSS JOIN policy p WHERE p.name = 'protected.read';
+
+-- Seed passwords are legacy plaintext; they are rehashed with Argon2id on the first successful login.
+
 INSERT INTO "user" (user_id, email, fullname, phone, address, role_id, password) VALUES
 (0, 'ngodinhkhoinguyen69@gmail.com', 'Ngo Dinh Khoi Nguyen', '349-548-8233', '69 Asplie Lica', 0, '123456'),
 (1, 'akenewel0@gravatar.com', 'Ainsley Kenewel', '521-396-4437', '8 Alpine Junction', 3, '123456'),
@@ -91,6 +521,11 @@ INSERT INTO "user" (user_id, email, fullname, phone, address, role_id, password)
 (9, 'fgonnel8@wiley.com', 'Frederigo Gonnel', '750-973-2099', '74 Karstens Trail', 3, '123456'),
 (10, 'jpeirazzi9@yahoo.co.jp', 'Juliana Peirazzi', '391-480-0322', '50 Lyons Park', 3, '123456');
 
+-- Seed accounts predate email verification
+UPDATE "user" SET email_verified = TRUE;
+
+SELECT setval('user_user_id_seq', (SELECT MAX(user_id) FROM "user"));
+
 INSERT INTO bus (bus_id, name, license_plate, deleted) VALUES
 (1, 'Sdasad', '41A23435', FALSE),
 (2, 'Sdasad', '43278322', FALSE),
@@ -104,30 +539,65 @@ INSERT INTO bus (bus_id, name, license_plate, deleted) VALUES
 (13, 'Sdasad', '43543434', FALSE),
 (14, 'From the nam', '47A82783', FALSE);
 
-INSERT INTO route (route_id, from_location, to_location, base_price, deleted) VALUES
-(1, 'Can Tho', 'Ca Mau', 10000, FALSE),
-(2, 'Can Tho', 'Singapore', 10000, FALSE),
-(3, 'Can Tho', 'Can Tho', 10000, FALSE),
-(4, 'Can Tho', 'Can Tho', 10000, FALSE),
-(5, 'Can Tho', 'Can Tho', 10000, FALSE),
-(6, 'Can Tho', 'Can Tho', 10000, FALSE),
-(7, 'Can Tho', 'Can Tho 2', 10000, FALSE),
-(8, 'Can Tho', 'Can Tho', 10000, TRUE),
-(9, 'Can Tho', 'Can Tho', 10000, TRUE),
-(10, 'Can Tho', 'Can Tho', 10000, TRUE),
-(11, 'Can Tho', 'Can Tho', 10000, FALSE),
-(12, 'Can Tho', 'Can Tho 3', 10000, FALSE),
-(13, 'Can Tho', 'Can Tho', 10000, TRUE),
-(14, 'Can Tho', 'Can Tho', 10000, FALSE),
-(15, 'Can Tho', 'Can Tho', 10000, FALSE),
-(16, 'Can Tho', 'Can Tho', 10000, FALSE),
-(17, 'Can Tho', 'Can Tho', 10000, FALSE),
-(18, 'Can Tho', 'Ca Mau', 500000, FALSE),
-(19, 'Can Tho', 'Ca Mau', 200000, FALSE),
-(20, 'Can Tho', 'Can Tho', 10000, TRUE),
-(21, 'Long An', 'Ho Chi Minh', 2000, TRUE),
-(22, 'Long An', 'Ha Noi', 500000, TRUE),
-(23, 'Long An', 'Ho Chi Minh', 500000, TRUE);
+SELECT setval('bus_bus_id_seq', (SELECT MAX(bus_id) FROM bus));
+
+INSERT INTO location (location_id, name, province, latitude, longitude) VALUES
+(1, 'Can Tho', 'Can Tho', 10.0452, 105.7469),
+(2, 'Ca Mau', 'Ca Mau', 9.1769, 105.1524),
+(3, 'Singapore', NULL, 1.3521, 103.8198),
+(4, 'Can Tho 2', 'Can Tho', NULL, NULL),
+(5, 'Can Tho 3', 'Can Tho', NULL, NULL),
+(6, 'Long An', 'Long An', 10.5360, 106.4130),
+(7, 'Ho Chi Minh', 'Ho Chi Minh', 10.7769, 106.7009),
+(8, 'Ha Noi', 'Ha Noi', 21.0285, 105.8542),
+(9, 'Soc Trang', 'Soc Trang', 9.6025, 105.9739),
+(10, 'Bac Lieu', 'Bac Lieu', 9.2941, 105.7278);
+
+SELECT setval('location_location_id_seq', (SELECT MAX(location_id) FROM location));
+
+INSERT INTO location_alias (location_id, alias) VALUES
+(7, 'Sai Gon'),
+(7, 'Saigon'),
+(7, 'TP HCM'),
+(8, 'Hanoi');
+
+INSERT INTO route (route_id, from_location, to_location, from_location_id, to_location_id, base_price, deleted) VALUES
+(1, 'Can Tho', 'Ca Mau', 1, 2, 10000, FALSE),
+(2, 'Can Tho', 'Singapore', 1, 3, 10000, FALSE),
+(3, 'Can Tho', 'Can Tho', 1, 1, 10000, FALSE),
+(4, 'Can Tho', 'Can Tho', 1, 1, 10000, FALSE),
+(5, 'Can Tho', 'Can Tho', 1, 1, 10000, FALSE),
+(6, 'Can Tho', 'Can Tho', 1, 1, 10000, FALSE),
+(7, 'Can Tho', 'Can Tho 2', 1, 4, 10000, FALSE),
+(8, 'Can Tho', 'Can Tho', 1, 1, 10000, TRUE),
+(9, 'Can Tho', 'Can Tho', 1, 1, 10000, TRUE),
+(10, 'Can Tho', 'Can Tho', 1, 1, 10000, TRUE),
+(11, 'Can Tho', 'Can Tho', 1, 1, 10000, FALSE),
+(12, 'Can Tho', 'Can Tho 3', 1, 5, 10000, FALSE),
+(13, 'Can Tho', 'Can Tho', 1, 1, 10000, TRUE),
+(14, 'Can Tho', 'Can Tho', 1, 1, 10000, FALSE),
+(15, 'Can Tho', 'Can Tho', 1, 1, 10000, FALSE),
+(16, 'Can Tho', 'Can Tho', 1, 1, 10000, FALSE),
+(17, 'Can Tho', 'Can Tho', 1, 1, 10000, FALSE),
+(18, 'Can Tho', 'Ca Mau', 1, 2, 500000, FALSE),
+(19, 'Can Tho', 'Ca Mau', 1, 2, 200000, FALSE),
+(20, 'Can Tho', 'Can Tho', 1, 1, 10000, TRUE),
+(21, 'Long An', 'Ho Chi Minh', 6, 7, 2000, TRUE),
+(22, 'Long An', 'Ha Noi', 6, 8, 500000, TRUE),
+(23, 'Long An', 'Ho Chi Minh', 6, 7, 500000, TRUE);
+
+SELECT setval('route_route_id_seq', (SELECT MAX(route_id) FROM route));
+
+INSERT INTO route_stop (route_id, stop_order, location, location_id, offset_minutes, fare) VALUES
+(1, 1, 'Soc Trang', 9, 90, 3000),
+(1, 2, 'Bac Lieu', 10, 180, 3000);
+
+INSERT INTO route_refund_tier (route_id, min_hours_before, refund_percent) VALUES
+(1, 48, 100),
+(1, 24, 50),
+(1, 2, 20),
+(2, 72, 90),
+(2, 24, 50);
 
 INSERT INTO seat (seat_id, bus_id, price, deleted, name) VALUES
 (21, 1, 250000, FALSE, 'A1'),
@@ -141,37 +611,37 @@ INSERT INTO seat (seat_id, bus_id, price, deleted, name) VALUES
 (29, 1, 250000, FALSE, 'A9'),
 (30, 1, 250000, FALSE, 'A10'),
 (31, 1, 250000, FALSE, 'A11'),
-(32, 2, 26000, FALSE, 'A11'),
-(33, 2, 250000, FALSE, 'A11'),
-(34, 2, 250000, FALSE, 'A11'),
-(35, 2, 250000, FALSE, 'A11'),
-(56, 4, 26000, FALSE, 'A11'),
-(57, 4, 26000, FALSE, 'A11'),
-(58, 4, 26000, FALSE, 'A11'),
-(71, 1, 250000, FALSE, 'B11'),
-(72, 1, 250000, FALSE, 'B11'),
-(73, 1, 250000, FALSE, 'B11'),
-(74, 1, 250000, FALSE, 'B11'),
-(75, 1, 250000, FALSE, 'B11'),
-(76, 1, 250000, FALSE, 'B11'),
-(77, 1, 250000, FALSE, 'B11'),
-(97, 2, 250000, FALSE, 'B11'),
-(98, 2, 250000, FALSE, 'B11'),
-(99, 2, 250000, FALSE, 'B11'),
-(100, 2, 250000, FALSE, 'B11'),
-(101, 2, 250000, FALSE, 'B11'),
-(102, 2, 250000, FALSE, 'B11'),
-(103, 2, 250000, FALSE, 'B11'),
-(104, 2, 250000, FALSE, 'B11'),
-(105, 2, 250000, FALSE, 'B11'),
-(106, 2, 250000, FALSE, 'B11'),
+(32, 2, 26000, FALSE, 'A1'),
+(33, 2, 250000, FALSE, 'A2'),
+(34, 2, 250000, FALSE, 'A3'),
+(35, 2, 250000, FALSE, 'A4'),
+(56, 4, 26000, FALSE, 'A1'),
+(57, 4, 26000, FALSE, 'A2'),
+(58, 4, 26000, FALSE, 'A3'),
+(71, 1, 250000, FALSE, 'B1'),
+(72, 1, 250000, FALSE, 'B2'),
+(73, 1, 250000, FALSE, 'B3'),
+(74, 1, 250000, FALSE, 'B4'),
+(75, 1, 250000, FALSE, 'B5'),
+(76, 1, 250000, FALSE, 'B6'),
+(77, 1, 250000, FALSE, 'B7'),
+(97, 2, 250000, FALSE, 'B1'),
+(98, 2, 250000, FALSE, 'B2'),
+(99, 2, 250000, FALSE, 'B3'),
+(100, 2, 250000, FALSE, 'B4'),
+(101, 2, 250000, FALSE, 'B5'),
+(102, 2, 250000, FALSE, 'B6'),
+(103, 2, 250000, FALSE, 'B7'),
+(104, 2, 250000, FALSE, 'B8'),
+(105, 2, 250000, FALSE, 'B9'),
+(106, 2, 250000, FALSE, 'B10'),
 (107, 2, 250000, FALSE, 'B11'),
-(128, 2, 250000, FALSE, 'B11'),
-(129, 2, 250000, FALSE, 'B11'),
-(130, 2, 250000, FALSE, 'B11'),
-(131, 2, 250000, FALSE, 'B11'),
-(132, 2, 250000, FALSE, 'B11'),
-(133, 2, 250000, FALSE, 'B11'),
+(128, 2, 250000, FALSE, 'B12'),
+(129, 2, 250000, FALSE, 'B13'),
+(130, 2, 250000, FALSE, 'B14'),
+(131, 2, 250000, FALSE, 'B15'),
+(132, 2, 250000, FALSE, 'B16'),
+(133, 2, 250000, FALSE, 'B17'),
 (134, 14, 300000, FALSE, 'A1'),
 (135, 14, 300000, FALSE, 'A2'),
 (136, 14, 300000, FALSE, 'A3'),
@@ -203,13 +673,27 @@ INSERT INTO seat (seat_id, bus_id, price, deleted, name) VALUES
 (162, 14, 300000, FALSE, 'A29'),
 (163, 14, 300000, FALSE, 'A30');
 
-INSERT INTO trip (trip_id, route_id, start_date, end_date, bus_id) VALUES
-(2, 2, '2022-11-13 08:14:27', '2022-11-15 08:14:27', 2),
-(3, 2, '2022-11-15 21:00:00', '2022-11-15 22:00:00', 2),
-(4, 2, '2022-11-15 19:00:00', '2022-11-15 22:00:00', 3),
-(5, 2, '2022-11-15 18:00:00', '2022-11-15 22:00:00', 4),
-(6, 2, '2022-11-15 17:00:00', '2022-11-15 22:00:00', 5),
-(35, 2, '2022-11-15 20:00:00', '2022-11-15 22:00:00', 3),
-(36, 2, '2022-11-22 06:49:02', '2022-11-22 11:49:02', 14),
-(37, 17, '2022-10-30 15:35:47', '2022-11-08 15:35:47', 14);
+SELECT setval('seat_seat_id_seq', (SELECT MAX(seat_id) FROM seat));
+
+-- Trip 35 put bus 3 on top of trip 4, so it is kept as a cancelled trip
+INSERT INTO trip (trip_id, route_id, start_date, end_date, bus_id, cancelled) VALUES
+(2, 2, '2022-11-13 08:14:27', '2022-11-15 08:14:27', 2, FALSE),
+(3, 2, '2022-11-15 21:00:00', '2022-11-15 22:00:00', 2, FALSE),
+(4, 2, '2022-11-15 19:00:00', '2022-11-15 22:00:00', 3, FALSE),
+(5, 2, '2022-11-15 18:00:00', '2022-11-15 22:00:00', 4, FALSE),
+(6, 2, '2022-11-15 17:00:00', '2022-11-15 22:00:00', 5, FALSE),
+(35, 2, '2022-11-15 20:00:00', '2022-11-15 22:00:00', 3, TRUE),
+(36, 2, '2022-11-22 06:49:02', '2022-11-22 11:49:02', 14, FALSE),
+(37, 17, '2022-10-30 15:35:47', '2022-11-08 15:35:47', 14, FALSE);
+
+SELECT setval('trip_trip_id_seq', (SELECT MAX(trip_id) FROM trip));
+
+
+INSERT INTO promo_code (promo_code_id, code, kind, value, max_discount, min_spend, max_uses_per_user, created_at) VALUES
+(1, 'WELCOME10', 0, 10, 50000, 0, 1, '2022-01-01 00:00:00'),
+(2, 'MEKONG30K', 1, 30000, NULL, 200000, NULL, '2022-01-01 00:00:00');
+
+INSERT INTO promo_code_route (promo_code_id, route_id) VALUES
+(2, 1);
 
+SELECT setval('promo_code_promo_code_id_seq', (SELECT MAX(promo_code_id) FROM promo_code));
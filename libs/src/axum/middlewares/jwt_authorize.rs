@@ -5,9 +5,12 @@ use axum::{
     response::Response,
 };
 
-use crate::jwt::{Claims, decode_token};
+use std::sync::Arc;
 
-fn extract_token(headers: &HeaderMap) -> Option<String> {
+use crate::jwt::{Claims, KeySet};
+use crate::revocation::RevocationStore;
+
+pub fn extract_token(headers: &HeaderMap) -> Option<String> {
     if let Some(auth) = headers.get(header::AUTHORIZATION)
         && let Ok(auth_str) = auth.to_str()
         && let Some(token) = auth_str.strip_prefix("Bearer ")
@@ -31,43 +34,54 @@ fn extract_token(headers: &HeaderMap) -> Option<String> {
 
 #[derive(Clone)]
 pub struct JwtAuth {
-    secret: String,
+    keys: Arc<KeySet>,
     roles: Option<Vec<String>>,
     policies: Option<Vec<String>>,
+    revocation: Option<Arc<dyn RevocationStore>>,
 }
 
 impl JwtAuth {
-    pub fn new(secret: impl Into<String>) -> Self {
+    pub fn new(keys: Arc<KeySet>) -> Self {
         Self {
-            secret: secret.into(),
+            keys,
             roles: None,
             policies: None,
+            revocation: None,
         }
     }
 
-    pub fn with_roles(secret: impl Into<String>, roles: Vec<&str>) -> Self {
+    pub fn with_roles(keys: Arc<KeySet>, roles: Vec<&str>) -> Self {
         Self {
-            secret: secret.into(),
+            keys,
             roles: Some(roles.into_iter().map(String::from).collect()),
             policies: None,
+            revocation: None,
         }
     }
 
-    pub fn with_policies(secret: impl Into<String>, policies: Vec<&str>) -> Self {
+    pub fn with_policies(keys: Arc<KeySet>, policies: Vec<&str>) -> Self {
         Self {
-            secret: secret.into(),
+            keys,
             roles: None,
             policies: Some(policies.into_iter().map(String::from).collect()),
+            revocation: None,
         }
     }
 
-    pub fn with_rules(secret: impl Into<String>, roles: Vec<&str>, policies: Vec<&str>) -> Self {
+    pub fn with_rules(keys: Arc<KeySet>, roles: Vec<&str>, policies: Vec<&str>) -> Self {
         Self {
-            secret: secret.into(),
+            keys,
             roles: Some(roles.into_iter().map(String::from).collect()),
             policies: Some(policies.into_iter().map(String::from).collect()),
+            revocation: None,
         }
     }
+
+    /// Rejects tokens the store reports as revoked.
+    pub fn with_revocation(mut self, store: Arc<dyn RevocationStore>) -> Self {
+        self.revocation = Some(store);
+        self
+    }
 }
 
 pub async fn jwt_authorize(
@@ -77,8 +91,13 @@ pub async fn jwt_authorize(
 ) -> Result<Response, StatusCode> {
     let token = extract_token(req.headers()).ok_or(StatusCode::UNAUTHORIZED)?;
 
-    let claims =
-        decode_token(&token, auth.secret.as_str()).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let claims = auth.keys.decode(&token).map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    if let Some(store) = &auth.revocation
+        && store.is_revoked(&claims)
+    {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
 
     if let Some(required_roles) = auth.roles {
         if !claims.roles.iter().any(|r| required_roles.contains(r)) {
@@ -1,3 +1,4 @@
 pub mod axum;
 pub mod jwt;
+pub mod revocation;
 pub mod tonic;
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::jwt::Claims;
+
+/// Checked synchronously by the middlewares, so implementations must answer
+/// from memory.
+pub trait RevocationStore: Send + Sync {
+    fn is_revoked(&self, claims: &Claims) -> bool;
+}
+
+#[derive(Default)]
+pub struct MemoryRevocationStore {
+    // jti -> exp
+    tokens: RwLock<HashMap<String, usize>>,
+    // sub -> tokens issued before this second are revoked
+    subjects: RwLock<HashMap<String, usize>>,
+}
+
+impl MemoryRevocationStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn revoke_token(&self, jti: impl Into<String>, exp: usize) {
+        if let Ok(mut tokens) = self.tokens.write() {
+            tokens.insert(jti.into(), exp);
+        }
+    }
+
+    pub fn revoke_subject(&self, sub: impl Into<String>, revoked_at: usize) {
+        if let Ok(mut subjects) = self.subjects.write() {
+            let entry = subjects.entry(sub.into()).or_default();
+            *entry = (*entry).max(revoked_at);
+        }
+    }
+
+    /// Drops token entries whose `exp` has passed; they fail validation anyway.
+    pub fn purge_expired(&self, now: usize) {
+        if let Ok(mut tokens) = self.tokens.write() {
+            tokens.retain(|_, exp| *exp > now);
+        }
+    }
+}
+
+impl RevocationStore for MemoryRevocationStore {
+    fn is_revoked(&self, claims: &Claims) -> bool {
+        let token_revoked = self
+            .tokens
+            .read()
+            .map(|tokens| tokens.contains_key(&claims.jti))
+            .unwrap_or(true);
+
+        let subject_revoked = self
+            .subjects
+            .read()
+            .map(|subjects| subjects.get(&claims.sub).is_some_and(|at| claims.iat < *at))
+            .unwrap_or(true);
+
+        token_revoked || subject_revoked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(sub: &str, jti: &str, iat: usize) -> Claims {
+        Claims {
+            sub: sub.to_string(),
+            exp: iat + 900,
+            iat,
+            jti: jti.to_string(),
+            roles: Vec::new(),
+            policies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn revoked_token_is_rejected_by_jti() {
+        let store = MemoryRevocationStore::new();
+        store.revoke_token("a", 2_000);
+
+        assert!(store.is_revoked(&claims("alice", "a", 1_000)));
+        assert!(!store.is_revoked(&claims("alice", "b", 1_000)));
+    }
+
+    #[test]
+    fn subject_revocation_rejects_only_earlier_tokens() {
+        let store = MemoryRevocationStore::new();
+        store.revoke_subject("alice", 1_000);
+
+        assert!(store.is_revoked(&claims("alice", "a", 999)));
+        assert!(!store.is_revoked(&claims("alice", "b", 1_000)));
+        assert!(!store.is_revoked(&claims("alice", "c", 1_001)));
+        assert!(!store.is_revoked(&claims("bob", "d", 999)));
+    }
+
+    #[test]
+    fn subject_revocation_keeps_latest_instant() {
+        let store = MemoryRevocationStore::new();
+        store.revoke_subject("alice", 1_000);
+        store.revoke_subject("alice", 500);
+
+        assert!(store.is_revoked(&claims("alice", "a", 999)));
+    }
+
+    #[test]
+    fn legacy_token_without_iat_is_rejected_after_revoke_all() {
+        let store = MemoryRevocationStore::new();
+        store.revoke_subject("alice", 1_000);
+
+        assert!(store.is_revoked(&claims("alice", "", 0)));
+    }
+
+    #[test]
+    fn purge_drops_only_expired_tokens() {
+        let store = MemoryRevocationStore::new();
+        store.revoke_token("old", 1_000);
+        store.revoke_token("live", 3_000);
+        store.purge_expired(2_000);
+
+        assert!(!store.is_revoked(&claims("alice", "old", 500)));
+        assert!(store.is_revoked(&claims("alice", "live", 500)));
+    }
+}
@@ -1,9 +1,12 @@
 use tonic::metadata::MetadataMap;
 use tonic::{Request, Status};
 
-use crate::jwt::{Claims, decode_token};
+use std::sync::Arc;
 
-fn extract_token(metadata: &MetadataMap) -> Option<String> {
+use crate::jwt::{Claims, KeySet};
+use crate::revocation::RevocationStore;
+
+pub fn extract_token(metadata: &MetadataMap) -> Option<String> {
     if let Some(auth) = metadata.get("authorization")
         && let Ok(auth_str) = auth.to_str()
         && let Some(token) = auth_str.strip_prefix("Bearer ")
@@ -27,51 +30,70 @@ fn extract_token(metadata: &MetadataMap) -> Option<String> {
 
 #[derive(Clone)]
 pub struct JwtAuthInterceptor {
-    secret: String,
+    keys: Arc<KeySet>,
     roles: Option<Vec<String>>,
     policies: Option<Vec<String>>,
+    revocation: Option<Arc<dyn RevocationStore>>,
 }
 
 impl JwtAuthInterceptor {
-    pub fn new(secret: impl Into<String>) -> Self {
+    pub fn new(keys: Arc<KeySet>) -> Self {
         Self {
-            secret: secret.into(),
+            keys,
             roles: None,
             policies: None,
+            revocation: None,
         }
     }
 
-    pub fn with_roles(secret: impl Into<String>, roles: Vec<&str>) -> Self {
+    pub fn with_roles(keys: Arc<KeySet>, roles: Vec<&str>) -> Self {
         Self {
-            secret: secret.into(),
+            keys,
             roles: Some(roles.into_iter().map(String::from).collect()),
             policies: None,
+            revocation: None,
         }
     }
 
-    pub fn with_policies(secret: impl Into<String>, policies: Vec<&str>) -> Self {
+    pub fn with_policies(keys: Arc<KeySet>, policies: Vec<&str>) -> Self {
         Self {
-            secret: secret.into(),
+            keys,
             roles: None,
             policies: Some(policies.into_iter().map(String::from).collect()),
+            revocation: None,
         }
     }
 
-    pub fn with_rules(secret: impl Into<String>, roles: Vec<&str>, policies: Vec<&str>) -> Self {
+    pub fn with_rules(keys: Arc<KeySet>, roles: Vec<&str>, policies: Vec<&str>) -> Self {
         Self {
-            secret: secret.into(),
+            keys,
             roles: Some(roles.into_iter().map(String::from).collect()),
             policies: Some(policies.into_iter().map(String::from).collect()),
+            revocation: None,
         }
     }
 
+    /// Rejects tokens the store reports as revoked.
+    pub fn with_revocation(mut self, store: Arc<dyn RevocationStore>) -> Self {
+        self.revocation = Some(store);
+        self
+    }
+
     pub fn intercept(&self, mut req: Request<()>) -> Result<Request<()>, Status> {
         let token = extract_token(req.metadata())
             .ok_or_else(|| Status::unauthenticated("missing token"))?;
 
-        let claims = decode_token(&token, self.secret.as_str())
+        let claims = self
+            .keys
+            .decode(&token)
             .map_err(|_| Status::unauthenticated("invalid token"))?;
 
+        if let Some(store) = &self.revocation
+            && store.is_revoked(&claims)
+        {
+            return Err(Status::unauthenticated("token revoked"));
+        }
+
         if let Some(required_roles) = &self.roles
             && !claims.roles.iter().any(|r| required_roles.contains(r))
         {
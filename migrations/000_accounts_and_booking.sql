@@ -0,0 +1,402 @@
+-- Upgrades a database created from the original schema, before accounts,
+-- tokens, MFA, seat holds, ticket states, scheduling and multi-stop routes, to
+-- the schema `001_location_catalogue.sql` starts from. Run it first, once, e.g.
+-- `psql -v ON_ERROR_STOP=1 -1 -f migrations/000_accounts_and_booking.sql`.
+--
+-- Existing rows are kept. Ids become SERIAL and continue after the highest
+-- one, accounts count as verified, every role_id in use gets a role, tickets
+-- ride their route end to end, and of trips that overlap on a bus all but the
+-- lowest trip_id are cancelled. It stops without changing anything when the
+-- data breaks a new constraint that cannot be fixed automatically: emails used
+-- twice, ticket statuses outside 0-6, trips ending before they start, a seat
+-- sold twice on a trip, or a trip to be cancelled for overlapping another that
+-- still has held, booked or paid tickets.
+
+-- Roles and policies, with a role for every role_id already in use
+
+CREATE TABLE role (
+    role_id SERIAL NOT NULL,
+    name VARCHAR(64) NOT NULL,
+    mfa_required BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (role_id),
+    UNIQUE (name)
+);
+
+CREATE TABLE policy (
+    policy_id SERIAL NOT NULL,
+    name VARCHAR(128) NOT NULL,
+    description VARCHAR(255) NOT NULL DEFAULT '',
+    PRIMARY KEY (policy_id),
+    UNIQUE (name)
+);
+
+CREATE TABLE role_policy (
+    role_id INT NOT NULL,
+    policy_id INT NOT NULL,
+    PRIMARY KEY (role_id, policy_id),
+    FOREIGN KEY (role_id) REFERENCES role (role_id) ON DELETE CASCADE,
+    FOREIGN KEY (policy_id) REFERENCES policy (policy_id) ON DELETE CASCADE
+);
+
+INSERT INTO role (role_id, name) VALUES
+(0, 'user'),
+(2, 'staff'),
+(3, 'admin');
+
+INSERT INTO role (role_id, name)
+SELECT DISTINCT role_id, 'role_' || role_id FROM "user" WHERE role_id NOT IN (0, 2, 3);
+
+SELECT setval('role_role_id_seq', (SELECT MAX(role_id) FROM role));
+
+INSERT INTO policy (name, description) VALUES
+('protected.read', 'Read the caller''s own protected resources');
+
+INSERT INTO role_policy (role_id, policy_id)
+SELECT r.role_id, p.policy_id FROM role r CROSS JOIN policy p WHERE p.name = 'protected.read';
+
+-- SERIAL ids continuing after the existing rows
+
+CREATE SEQUENCE user_user_id_seq AS INTEGER OWNED BY "user".user_id;
+ALTER TABLE "user" ALTER COLUMN user_id SET DEFAULT nextval('user_user_id_seq');
+SELECT setval('user_user_id_seq', COALESCE((SELECT MAX(user_id) + 1 FROM "user"), 1), FALSE);
+
+CREATE SEQUENCE bus_bus_id_seq AS INTEGER OWNED BY bus.bus_id;
+ALTER TABLE bus ALTER COLUMN bus_id SET DEFAULT nextval('bus_bus_id_seq');
+SELECT setval('bus_bus_id_seq', COALESCE((SELECT MAX(bus_id) + 1 FROM bus), 1), FALSE);
+
+CREATE SEQUENCE route_route_id_seq AS INTEGER OWNED BY route.route_id;
+ALTER TABLE route ALTER COLUMN route_id SET DEFAULT nextval('route_route_id_seq');
+SELECT setval('route_route_id_seq', COALESCE((SELECT MAX(route_id) + 1 FROM route), 1), FALSE);
+
+CREATE SEQUENCE trip_trip_id_seq AS INTEGER OWNED BY trip.trip_id;
+ALTER TABLE trip ALTER COLUMN trip_id SET DEFAULT nextval('trip_trip_id_seq');
+SELECT setval('trip_trip_id_seq', COALESCE((SELECT MAX(trip_id) + 1 FROM trip), 1), FALSE);
+
+CREATE SEQUENCE seat_seat_id_seq AS INTEGER OWNED BY seat.seat_id;
+ALTER TABLE seat ALTER COLUMN seat_id SET DEFAULT nextval('seat_seat_id_seq');
+SELECT setval('seat_seat_id_seq', COALESCE((SELECT MAX(seat_id) + 1 FROM seat), 1), FALSE);
+
+CREATE SEQUENCE ticket_ticket_id_seq AS INTEGER OWNED BY ticket.ticket_id;
+ALTER TABLE ticket ALTER COLUMN ticket_id SET DEFAULT nextval('ticket_ticket_id_seq');
+SELECT setval('ticket_ticket_id_seq', COALESCE((SELECT MAX(ticket_id) + 1 FROM ticket), 1), FALSE);
+
+-- Accounts
+
+ALTER TABLE "user"
+    ALTER COLUMN password TYPE VARCHAR(255),
+    ADD COLUMN email_verified BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD UNIQUE (email),
+    ADD FOREIGN KEY (role_id) REFERENCES role (role_id);
+
+-- Existing accounts predate email verification
+UPDATE "user" SET email_verified = TRUE;
+
+CREATE TABLE refresh_token (
+    refresh_token_id SERIAL NOT NULL,
+    family_id VARCHAR(64) NOT NULL,
+    user_id INT NOT NULL,
+    token_hash VARCHAR(64) NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    rotated_at TIMESTAMP,
+    revoked BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (refresh_token_id),
+    UNIQUE (token_hash),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id)
+);
+
+CREATE INDEX refresh_token_family_idx ON refresh_token (family_id);
+CREATE INDEX refresh_token_user_idx ON refresh_token (user_id);
+
+CREATE TABLE revoked_token (
+    jti VARCHAR(64) NOT NULL,
+    sub VARCHAR(128) NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (jti)
+);
+
+-- Access tokens for `sub` issued before `revoked_before` are rejected
+CREATE TABLE subject_revocation (
+    sub VARCHAR(128) NOT NULL,
+    revoked_before TIMESTAMP NOT NULL,
+    PRIMARY KEY (sub)
+);
+
+-- Single-use links mailed on registration; only the SHA-256 of the token is stored
+CREATE TABLE email_verification (
+    token_hash VARCHAR(64) NOT NULL,
+    user_id INT NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (token_hash),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX email_verification_user_idx ON email_verification (user_id);
+
+-- Single-use password reset links; only the SHA-256 of the token is stored
+CREATE TABLE password_reset (
+    token_hash VARCHAR(64) NOT NULL,
+    user_id INT NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (token_hash),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX password_reset_user_idx ON password_reset (user_id);
+
+-- Failed login counters shared by all nodes when LOGIN_THROTTLE_STORE=postgres.
+-- attempt_key is `user:<email>` or `ip:<address>`.
+CREATE TABLE login_attempt (
+    attempt_key VARCHAR(160) NOT NULL,
+    failures INT NOT NULL DEFAULT 0,
+    window_started TIMESTAMP NOT NULL,
+    locked_until TIMESTAMP,
+    PRIMARY KEY (attempt_key)
+);
+
+-- TOTP second factor; confirmed_at stays NULL until the first code is accepted
+CREATE TABLE user_totp (
+    user_id INT NOT NULL,
+    secret VARCHAR(64) NOT NULL,
+    confirmed_at TIMESTAMP,
+    last_used_step BIGINT,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (user_id),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id) ON DELETE CASCADE
+);
+
+-- Single-use MFA recovery codes; only the SHA-256 of the code is stored
+CREATE TABLE mfa_recovery_code (
+    mfa_recovery_code_id SERIAL NOT NULL,
+    user_id INT NOT NULL,
+    code_hash VARCHAR(64) NOT NULL,
+    used_at TIMESTAMP,
+    PRIMARY KEY (mfa_recovery_code_id),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX mfa_recovery_code_user_idx ON mfa_recovery_code (user_id);
+
+-- Logins waiting for their second factor; only the SHA-256 of the token is stored
+CREATE TABLE mfa_challenge (
+    token_hash VARCHAR(64) NOT NULL,
+    user_id INT NOT NULL,
+    cookie BOOLEAN NOT NULL DEFAULT FALSE,
+    attempts INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (token_hash),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX mfa_challenge_user_idx ON mfa_challenge (user_id);
+
+-- Routes, schedules and trips
+
+-- Stops a route makes between its ends, numbered from 1 in travel order. fare is
+-- the leg from the previous stop and offset_minutes the time since departure;
+-- the last leg to to_location costs what the stops leave of base_price
+CREATE TABLE route_stop (
+    route_id INT NOT NULL,
+    stop_order INT NOT NULL CHECK (stop_order >= 1),
+    location VARCHAR(128) NOT NULL,
+    offset_minutes INT NOT NULL CHECK (offset_minutes >= 1),
+    fare INT NOT NULL CHECK (fare >= 1),
+    PRIMARY KEY (route_id, stop_order),
+    FOREIGN KEY (route_id) REFERENCES route (route_id) ON DELETE CASCADE
+);
+
+-- Recurring timetable the trip generator expands into trips. days_of_week is a
+-- bit mask from Monday (1) to Sunday (64); valid_until NULL runs indefinitely
+CREATE TABLE schedule (
+    schedule_id SERIAL NOT NULL,
+    route_id INT NOT NULL,
+    bus_id INT NOT NULL,
+    departure_time TIME NOT NULL,
+    duration_minutes INT NOT NULL CHECK (duration_minutes BETWEEN 1 AND 1440),
+    days_of_week INT NOT NULL CHECK (days_of_week BETWEEN 1 AND 127),
+    valid_from DATE NOT NULL,
+    valid_until DATE,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (schedule_id),
+    FOREIGN KEY (route_id) REFERENCES route (route_id),
+    FOREIGN KEY (bus_id) REFERENCES bus (bus_id),
+    CHECK (valid_until IS NULL OR valid_until >= valid_from)
+);
+
+-- Days a schedule does not run
+CREATE TABLE schedule_exception (
+    schedule_id INT NOT NULL,
+    service_date DATE NOT NULL,
+    PRIMARY KEY (schedule_id, service_date),
+    FOREIGN KEY (schedule_id) REFERENCES schedule (schedule_id) ON DELETE CASCADE
+);
+
+ALTER TABLE trip
+    ADD COLUMN cancelled BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN schedule_id INT,
+    ADD FOREIGN KEY (schedule_id) REFERENCES schedule (schedule_id),
+    ADD UNIQUE (schedule_id, start_date),
+    ADD CHECK (end_date > start_date);
+
+-- A bus runs one trip at a time; of trips already overlapping, the lowest
+-- trip_id keeps running. Live tickets on the others would outlive their trip,
+-- so an operator moves or cancels those first.
+DO $$
+DECLARE
+    conflicts TEXT;
+BEGIN
+    SELECT string_agg(DISTINCT t.trip_id::TEXT, ', ') INTO conflicts
+    FROM trip t
+    JOIN ticket k ON k.trip_id = t.trip_id AND k.status IN (0, 1, 2)
+    WHERE EXISTS (
+        SELECT 1 FROM trip o
+        WHERE o.bus_id = t.bus_id
+          AND o.trip_id < t.trip_id
+          AND tsrange(o.start_date, o.end_date) && tsrange(t.start_date, t.end_date)
+    );
+
+    IF conflicts IS NOT NULL THEN
+        RAISE EXCEPTION 'trips % overlap an earlier trip on their bus and still have held, booked or paid tickets', conflicts;
+    END IF;
+END $$;
+
+UPDATE trip t
+SET cancelled = TRUE
+WHERE EXISTS (
+    SELECT 1 FROM trip o
+    WHERE o.bus_id = t.bus_id
+      AND o.trip_id < t.trip_id
+      AND tsrange(o.start_date, o.end_date) && tsrange(t.start_date, t.end_date)
+);
+
+CREATE EXTENSION IF NOT EXISTS btree_gist;
+ALTER TABLE trip ADD CONSTRAINT trip_bus_no_overlap EXCLUDE USING gist (
+    bus_id WITH =,
+    tsrange(start_date, end_date) WITH &&
+) WHERE (NOT cancelled);
+
+-- Seats and tickets
+
+-- seat_row and seat_column place the seat on its deck; existing seats have no layout
+ALTER TABLE seat
+    ADD COLUMN seat_row INT CHECK (seat_row >= 1),
+    ADD COLUMN seat_column INT CHECK (seat_column >= 1),
+    ADD COLUMN deck INT NOT NULL DEFAULT 1 CHECK (deck BETWEEN 1 AND 2);
+
+-- ticket.status: 0 held, 1 booked, 2 paid, 3 checked in, 4 cancelled, 5 refunded, 6 no-show.
+-- Routes have no stops yet, so existing tickets ride from stop 0 to stop 1.
+ALTER TABLE ticket
+    ADD CHECK (status BETWEEN 0 AND 6),
+    ADD COLUMN refund_amount INT,
+    ADD COLUMN from_stop INT NOT NULL DEFAULT 0 CHECK (from_stop >= 0),
+    ADD COLUMN to_stop INT NOT NULL DEFAULT 1,
+    ADD CHECK (to_stop > from_stop);
+
+ALTER TABLE ticket
+    ALTER COLUMN from_stop DROP DEFAULT,
+    ALTER COLUMN to_stop DROP DEFAULT;
+
+-- A seat is sold at most once per trip and leg; cancelled (4) and refunded (5) tickets free it
+ALTER TABLE ticket ADD CONSTRAINT ticket_seat_no_overlap EXCLUDE USING gist (
+    trip_id WITH =,
+    seat_id WITH =,
+    int4range(from_stop, to_stop) WITH &&
+) WHERE (status NOT IN (4, 5));
+
+-- Refund tiers per route: a cancellation at least min_hours_before hours before departure
+-- refunds refund_percent of the fare, taking the tier with the highest min_hours_before that
+-- applies; routes without a matching tier refund nothing
+CREATE TABLE route_refund_tier (
+    route_id INT NOT NULL,
+    min_hours_before INT NOT NULL CHECK (min_hours_before >= 0),
+    refund_percent INT NOT NULL CHECK (refund_percent BETWEEN 0 AND 100),
+    PRIMARY KEY (route_id, min_hours_before),
+    FOREIGN KEY (route_id) REFERENCES route (route_id) ON DELETE CASCADE
+);
+
+-- Every ticket status change; from_status is NULL when the ticket is created
+-- and actor_id NULL when the system made the change
+CREATE TABLE ticket_status_history (
+    ticket_status_history_id SERIAL NOT NULL,
+    ticket_id INT NOT NULL,
+    from_status INT,
+    to_status INT NOT NULL,
+    actor_id INT,
+    changed_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (ticket_status_history_id),
+    FOREIGN KEY (ticket_id) REFERENCES ticket (ticket_id) ON DELETE CASCADE,
+    FOREIGN KEY (actor_id) REFERENCES "user" (user_id) ON DELETE SET NULL
+);
+
+CREATE INDEX ticket_status_history_ticket_idx ON ticket_status_history (ticket_id);
+
+-- Seats reserved during checkout for the legs from_stop..to_stop; a hold past
+-- expires_at no longer blocks the seat
+CREATE TABLE seat_hold (
+    trip_id INT NOT NULL,
+    seat_id INT NOT NULL,
+    user_id INT NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    from_stop INT NOT NULL CHECK (from_stop >= 0),
+    to_stop INT NOT NULL,
+    PRIMARY KEY (trip_id, seat_id, from_stop),
+    FOREIGN KEY (trip_id) REFERENCES trip (trip_id),
+    FOREIGN KEY (seat_id) REFERENCES seat (seat_id),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id) ON DELETE CASCADE,
+    CHECK (to_stop > from_stop),
+    CONSTRAINT seat_hold_no_overlap EXCLUDE USING gist (
+        trip_id WITH =,
+        seat_id WITH =,
+        int4range(from_stop, to_stop) WITH &&
+    )
+);
+
+CREATE INDEX seat_hold_expires_idx ON seat_hold (expires_at);
+
+-- Functions and views
+
+-- Case- and accent-insensitive form of a place name for trip search ('Cần Thơ' -> 'can tho')
+CREATE EXTENSION IF NOT EXISTS unaccent;
+CREATE OR REPLACE FUNCTION fold_location(p_text TEXT)
+RETURNS TEXT
+LANGUAGE sql
+STABLE
+AS $$
+    SELECT lower(unaccent(p_text));
+$$;
+
+CREATE OR REPLACE FUNCTION get_seat_count_for_bus(p_bus_id INT)
+RETURNS INT
+LANGUAGE sql
+STABLE
+AS $$
+    SELECT COUNT(*)::INT FROM seat WHERE bus_id = p_bus_id AND NOT deleted;
+$$;
+
+-- Every stop of a route in travel order: from_location as stop 0, the rows of
+-- route_stop, then to_location. offset_minutes is NULL at the destination, which
+-- is reached at the trip's end_date; a leg from stop a to stop b costs
+-- b.fare_from_origin - a.fare_from_origin
+CREATE VIEW route_itinerary AS
+SELECT route_id, stop_order, location, offset_minutes, fare,
+       SUM(fare) OVER (PARTITION BY route_id ORDER BY stop_order)::INT AS fare_from_origin,
+       stop_order = MAX(stop_order) OVER (PARTITION BY route_id) AS terminal
+FROM (
+    SELECT route_id, 0 AS stop_order, from_location AS location, 0 AS offset_minutes, 0 AS fare
+    FROM route
+    UNION ALL
+    SELECT route_id, stop_order, location, offset_minutes, fare
+    FROM route_stop
+    UNION ALL
+    SELECT r.route_id, COALESCE(MAX(rs.stop_order), 0) + 1, r.to_location, NULL,
+           (r.base_price - COALESCE(SUM(rs.fare), 0))::INT
+    FROM route r
+    LEFT JOIN route_stop rs ON rs.route_id = r.route_id
+    GROUP BY r.route_id
+) stops;
@@ -0,0 +1,73 @@
+-- Upgrades a database created before the location catalogue: builds
+-- `location` from the place names already used by routes, route stops and
+-- tickets, then points routes and route stops at it. Spellings that only
+-- differ in case or accents become one location, named after the most used
+-- one. Run once, e.g. `psql -v ON_ERROR_STOP=1 -1 -f migrations/001_location_catalogue.sql`.
+
+CREATE EXTENSION IF NOT EXISTS unaccent;
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+CREATE OR REPLACE FUNCTION fold_location(p_text TEXT)
+RETURNS TEXT
+LANGUAGE sql
+IMMUTABLE
+AS $$
+    SELECT lower(public.unaccent('public.unaccent'::regdictionary, p_text));
+$$;
+
+CREATE TABLE location (
+    location_id SERIAL NOT NULL,
+    name VARCHAR(128) NOT NULL,
+    province VARCHAR(128),
+    latitude DOUBLE PRECISION CHECK (latitude BETWEEN -90 AND 90),
+    longitude DOUBLE PRECISION CHECK (longitude BETWEEN -180 AND 180),
+    PRIMARY KEY (location_id)
+);
+
+CREATE UNIQUE INDEX location_name_idx ON location (fold_location(name));
+CREATE INDEX location_name_trgm_idx ON location USING gin (fold_location(name) gin_trgm_ops);
+
+CREATE TABLE location_alias (
+    location_id INT NOT NULL,
+    alias VARCHAR(128) NOT NULL,
+    PRIMARY KEY (location_id, alias),
+    FOREIGN KEY (location_id) REFERENCES location (location_id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX location_alias_idx ON location_alias (fold_location(alias));
+CREATE INDEX location_alias_trgm_idx ON location_alias USING gin (fold_location(alias) gin_trgm_ops);
+
+INSERT INTO location (name)
+SELECT DISTINCT ON (fold_location(name)) name
+FROM (
+    SELECT btrim(from_location) AS name FROM route
+    UNION ALL SELECT btrim(to_location) FROM route
+    UNION ALL SELECT btrim(location) FROM route_stop
+    UNION ALL SELECT btrim(from_location) FROM ticket WHERE from_location IS NOT NULL
+    UNION ALL SELECT btrim(to_location) FROM ticket WHERE to_location IS NOT NULL
+) names
+GROUP BY name
+ORDER BY fold_location(name), COUNT(*) DESC, name;
+
+ALTER TABLE route
+    ADD COLUMN from_location_id INT REFERENCES location (location_id),
+    ADD COLUMN to_location_id INT REFERENCES location (location_id);
+
+UPDATE route r
+SET from_location_id = f.location_id, from_location = f.name,
+    to_location_id = t.location_id, to_location = t.name
+FROM location f, location t
+WHERE fold_location(f.name) = fold_location(btrim(r.from_location))
+  AND fold_location(t.name) = fold_location(btrim(r.to_location));
+
+ALTER TABLE route
+    ALTER COLUMN from_location_id SET NOT NULL,
+    ALTER COLUMN to_location_id SET NOT NULL;
+
+ALTER TABLE route_stop ADD COLUMN location_id INT REFERENCES location (location_id);
+
+UPDATE route_stop s
+SET location_id = l.location_id, location = l.name
+FROM location l
+WHERE fold_location(l.name) = fold_location(btrim(s.location));
+
+ALTER TABLE route_stop ALTER COLUMN location_id SET NOT NULL;
@@ -0,0 +1,46 @@
+-- Adds the payment tables to a database created before payments. Run once,
+-- e.g. `psql -v ON_ERROR_STOP=1 -1 -f migrations/002_payments.sql`.
+
+-- A payment for booked tickets through a provider; provider_ref is the provider's id for it.
+-- payment_intent.status: 0 pending, 1 succeeded, 2 failed, 3 expired
+-- expires_at is when its tickets are released unpaid, NULL when they never are
+CREATE TABLE payment_intent (
+    payment_intent_id SERIAL NOT NULL,
+    user_id INT NOT NULL,
+    provider VARCHAR(32) NOT NULL,
+    provider_ref VARCHAR(128) NOT NULL,
+    amount INT NOT NULL CHECK (amount >= 0),
+    currency VARCHAR(3) NOT NULL,
+    status INT NOT NULL DEFAULT 0 CHECK (status BETWEEN 0 AND 3),
+    checkout_url TEXT,
+    -- Paid for tickets that were no longer payable and given back
+    refunded_amount INT NOT NULL DEFAULT 0 CHECK (refunded_amount >= 0),
+    created_at TIMESTAMP NOT NULL,
+    expires_at TIMESTAMP,
+    completed_at TIMESTAMP,
+    PRIMARY KEY (payment_intent_id),
+    UNIQUE (provider, provider_ref),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id)
+);
+
+CREATE INDEX payment_intent_pending_idx ON payment_intent (expires_at) WHERE status = 0;
+
+CREATE TABLE payment_intent_ticket (
+    payment_intent_id INT NOT NULL,
+    ticket_id INT NOT NULL,
+    PRIMARY KEY (payment_intent_id, ticket_id),
+    FOREIGN KEY (payment_intent_id) REFERENCES payment_intent (payment_intent_id) ON DELETE CASCADE,
+    FOREIGN KEY (ticket_id) REFERENCES ticket (ticket_id)
+);
+
+CREATE INDEX payment_intent_ticket_ticket_idx ON payment_intent_ticket (ticket_id);
+
+-- Webhook events already applied, so a redelivered event changes nothing
+CREATE TABLE payment_event (
+    provider VARCHAR(32) NOT NULL,
+    event_id VARCHAR(128) NOT NULL,
+    payment_intent_id INT NOT NULL,
+    received_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (provider, event_id),
+    FOREIGN KEY (payment_intent_id) REFERENCES payment_intent (payment_intent_id) ON DELETE CASCADE
+);
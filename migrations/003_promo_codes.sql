@@ -0,0 +1,52 @@
+-- Adds promo codes to a database created before them: the promo tables and each
+-- ticket's discount. Run once, e.g. `psql -v ON_ERROR_STOP=1 -1 -f migrations/003_promo_codes.sql`.
+
+-- Discount codes applied when booking. promo_code.kind: 0 percentage, 1 fixed amount.
+-- value is the percentage (1-100) or the amount; max_discount caps a percentage discount.
+-- NULL limits and validity bounds are unlimited and open.
+CREATE TABLE promo_code (
+    promo_code_id SERIAL NOT NULL,
+    code VARCHAR(32) NOT NULL,
+    kind INT NOT NULL CHECK (kind BETWEEN 0 AND 1),
+    value INT NOT NULL CHECK (value > 0),
+    max_discount INT CHECK (max_discount > 0),
+    min_spend INT NOT NULL DEFAULT 0 CHECK (min_spend >= 0),
+    valid_from TIMESTAMP,
+    valid_until TIMESTAMP,
+    max_uses INT CHECK (max_uses > 0),
+    max_uses_per_user INT CHECK (max_uses_per_user > 0),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (promo_code_id),
+    UNIQUE (code),
+    CHECK (kind <> 0 OR value <= 100),
+    CHECK (valid_until > valid_from)
+);
+
+-- Routes a promo code is limited to; a code without rows applies to every route
+CREATE TABLE promo_code_route (
+    promo_code_id INT NOT NULL,
+    route_id INT NOT NULL,
+    PRIMARY KEY (promo_code_id, route_id),
+    FOREIGN KEY (promo_code_id) REFERENCES promo_code (promo_code_id) ON DELETE CASCADE,
+    FOREIGN KEY (route_id) REFERENCES route (route_id) ON DELETE CASCADE
+);
+
+-- One booking that used a promo code; it stops counting towards the usage limits once
+-- all of its tickets are cancelled or refunded
+CREATE TABLE promo_redemption (
+    promo_redemption_id SERIAL NOT NULL,
+    promo_code_id INT NOT NULL,
+    user_id INT NOT NULL,
+    discount INT NOT NULL CHECK (discount >= 0),
+    redeemed_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (promo_redemption_id),
+    FOREIGN KEY (promo_code_id) REFERENCES promo_code (promo_code_id),
+    FOREIGN KEY (user_id) REFERENCES "user" (user_id)
+);
+
+CREATE INDEX promo_redemption_code_idx ON promo_redemption (promo_code_id, user_id);
+
+ALTER TABLE ticket
+    ADD COLUMN discount INT NOT NULL DEFAULT 0 CHECK (discount >= 0),
+    ADD COLUMN promo_redemption_id INT REFERENCES promo_redemption (promo_redemption_id);
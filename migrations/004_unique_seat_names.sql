@@ -0,0 +1,17 @@
+-- Makes seat names unique among a bus's active seats in a database created
+-- before the constraint. Of seats sharing a name on a bus the lowest seat_id
+-- keeps it and the others are renamed `<name>-<seat_id>`, so their tickets stay
+-- valid; list them first with `GET /api/v1/admin/buses/layout-report`. Run once,
+-- e.g. `psql -v ON_ERROR_STOP=1 -1 -f migrations/004_unique_seat_names.sql`.
+
+UPDATE seat s
+SET name = LEFT(s.name, 116) || '-' || s.seat_id
+FROM (
+    SELECT seat_id, ROW_NUMBER() OVER (PARTITION BY bus_id, name ORDER BY seat_id) AS copy
+    FROM seat
+    WHERE NOT deleted
+) d
+WHERE d.seat_id = s.seat_id AND d.copy > 1;
+
+-- Seat names are unique among a bus's active seats
+CREATE UNIQUE INDEX seat_bus_name_idx ON seat (bus_id, name) WHERE NOT deleted;
@@ -0,0 +1,137 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::extract::ConnectInfo;
+use axum::http::HeaderMap;
+use tonic::Request;
+
+/// Picks the client address for a request. `X-Forwarded-For` is only
+/// honoured when the socket peer is a trusted proxy; the rightmost entry that
+/// is not itself a trusted proxy is the client, since anything left of it can
+/// be forged by the caller.
+pub fn resolve(peer: Option<IpAddr>, forwarded_for: Option<&str>, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let peer = peer?;
+    if !trusted_proxies.contains(&peer) {
+        return Some(peer);
+    }
+
+    let forwarded = forwarded_for
+        .into_iter()
+        .flat_map(|value| value.split(','))
+        .filter_map(|entry| entry.trim().parse::<IpAddr>().ok())
+        .collect::<Vec<_>>();
+
+    Some(
+        forwarded
+            .iter()
+            .rev()
+            .find(|ip| !trusted_proxies.contains(ip))
+            .or(forwarded.first())
+            .copied()
+            .unwrap_or(peer),
+    )
+}
+
+pub fn from_http(peer: SocketAddr, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let forwarded_for = forwarded_for(headers);
+    resolve(Some(peer.ip()), forwarded_for.as_deref(), trusted_proxies)
+}
+
+pub fn from_grpc<T>(request: &Request<T>, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0.ip())
+        .or_else(|| request.remote_addr().map(|addr| addr.ip()));
+    let forwarded_for = request
+        .metadata()
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    resolve(peer, Some(forwarded_for.as_str()).filter(|v| !v.is_empty()), trusted_proxies)
+}
+
+/// Joins repeated `X-Forwarded-For` headers in the order they were sent.
+fn forwarded_for(headers: &HeaderMap) -> Option<String> {
+    let values = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>();
+
+    (!values.is_empty()).then(|| values.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn untrusted_peer_is_the_client_whatever_it_forwards() {
+        let trusted = [ip("10.0.0.1")];
+
+        assert_eq!(resolve(Some(ip("203.0.113.7")), Some("198.51.100.1"), &trusted), Some(ip("203.0.113.7")));
+        assert_eq!(resolve(Some(ip("203.0.113.7")), None, &[]), Some(ip("203.0.113.7")));
+    }
+
+    #[test]
+    fn trusted_peer_uses_rightmost_untrusted_forwarded_address() {
+        let trusted = [ip("10.0.0.1"), ip("10.0.0.2")];
+
+        assert_eq!(
+            resolve(Some(ip("10.0.0.1")), Some("1.2.3.4, 198.51.100.9, 10.0.0.2"), &trusted),
+            Some(ip("198.51.100.9"))
+        );
+    }
+
+    #[test]
+    fn forged_entries_left_of_the_client_are_ignored() {
+        let trusted = [ip("10.0.0.1")];
+
+        assert_eq!(
+            resolve(Some(ip("10.0.0.1")), Some("127.0.0.1, 198.51.100.9"), &trusted),
+            Some(ip("198.51.100.9"))
+        );
+    }
+
+    #[test]
+    fn trusted_peer_without_usable_header_is_the_client() {
+        let trusted = [ip("10.0.0.1")];
+
+        assert_eq!(resolve(Some(ip("10.0.0.1")), None, &trusted), Some(ip("10.0.0.1")));
+        assert_eq!(resolve(Some(ip("10.0.0.1")), Some("unknown, "), &trusted), Some(ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn chain_of_only_trusted_proxies_falls_back_to_leftmost() {
+        let trusted = [ip("10.0.0.1"), ip("10.0.0.2")];
+
+        assert_eq!(resolve(Some(ip("10.0.0.1")), Some("10.0.0.2, 10.0.0.1"), &trusted), Some(ip("10.0.0.2")));
+    }
+
+    #[test]
+    fn missing_peer_is_unknown() {
+        assert_eq!(resolve(None, Some("198.51.100.9"), &[]), None);
+    }
+
+    #[test]
+    fn repeated_http_headers_are_read_in_order() {
+        let mut headers = HeaderMap::new();
+        headers.append("x-forwarded-for", HeaderValue::from_static("198.51.100.9"));
+        headers.append("x-forwarded-for", HeaderValue::from_static("10.0.0.2"));
+        let trusted = [ip("10.0.0.1"), ip("10.0.0.2")];
+
+        assert_eq!(
+            from_http("10.0.0.1:443".parse().unwrap(), &headers, &trusted),
+            Some(ip("198.51.100.9"))
+        );
+    }
+}
@@ -1 +1,3 @@
+pub mod client_ip;
 pub mod v1;
+pub mod well_known;
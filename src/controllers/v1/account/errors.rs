@@ -0,0 +1,110 @@
+use axum::{
+    Json,
+    http::{HeaderValue, StatusCode, header::RETRY_AFTER},
+    response::IntoResponse,
+};
+use serde::Serialize;
+use thiserror::Error;
+use tonic::Status;
+
+use crate::services::auth::AuthError;
+use crate::services::mfa::MfaError;
+use crate::services::refresh_token::RefreshTokenError;
+use crate::services::revocation::RevocationError;
+
+#[derive(Debug, Error)]
+pub enum AccountControllerError {
+    #[error("auth error: {0}")]
+    Auth(#[from] AuthError),
+
+    #[error("refresh token error: {0}")]
+    RefreshToken(#[from] RefreshTokenError),
+
+    #[error("revocation error: {0}")]
+    Revocation(#[from] RevocationError),
+
+    #[error("mfa error: {0}")]
+    Mfa(#[from] MfaError),
+
+    #[error("missing claims")]
+    MissingClaims,
+
+    #[error("validation error: {0:?}")]
+    Validation(Vec<String>),
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    code: u16,
+    messages: Vec<String>,
+}
+
+// gRPC
+impl From<AccountControllerError> for Status {
+    fn from(err: AccountControllerError) -> Self {
+        match err {
+            AccountControllerError::Auth(e) => match e {
+                AuthError::InvalidCredentials => Status::permission_denied("current password is incorrect"),
+                AuthError::UserNotFound => Status::not_found("user not found"),
+                _ => Status::internal("database error"),
+            },
+            AccountControllerError::RefreshToken(_) => Status::internal("failed to revoke refresh tokens"),
+            AccountControllerError::Revocation(_) => Status::internal("failed to revoke tokens"),
+            AccountControllerError::Mfa(e) => match e {
+                MfaError::InvalidCode => Status::permission_denied(e.to_string()),
+                MfaError::InvalidChallenge => Status::unauthenticated(e.to_string()),
+                MfaError::NotEnrolled | MfaError::AlreadyEnabled | MfaError::Required => {
+                    Status::failed_precondition(e.to_string())
+                }
+                MfaError::Locked { retry_after } => {
+                    let mut status = Status::resource_exhausted(e.to_string());
+                    status.metadata_mut().insert("retry-after", retry_after.into());
+                    status
+                }
+                MfaError::Database(_) | MfaError::Throttle(_) => Status::internal("database error"),
+            },
+            AccountControllerError::MissingClaims => Status::unauthenticated("missing claims"),
+            AccountControllerError::Validation(msgs) => Status::invalid_argument(msgs.join(", ")),
+        }
+    }
+}
+
+// HTTP
+impl IntoResponse for AccountControllerError {
+    fn into_response(self) -> axum::response::Response {
+        let retry_after = match &self {
+            AccountControllerError::Mfa(MfaError::Locked { retry_after }) => Some(*retry_after),
+            _ => None,
+        };
+
+        let (status, messages) = match self {
+            AccountControllerError::Auth(e) => match e {
+                AuthError::InvalidCredentials => (StatusCode::FORBIDDEN, vec!["current password is incorrect".to_string()]),
+                AuthError::UserNotFound => (StatusCode::NOT_FOUND, vec!["user not found".to_string()]),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            AccountControllerError::RefreshToken(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["failed to revoke refresh tokens".to_string()]),
+            AccountControllerError::Revocation(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["failed to revoke tokens".to_string()]),
+            AccountControllerError::Mfa(e) => match e {
+                MfaError::InvalidCode => (StatusCode::FORBIDDEN, vec![e.to_string()]),
+                MfaError::InvalidChallenge => (StatusCode::UNAUTHORIZED, vec![e.to_string()]),
+                MfaError::NotEnrolled | MfaError::AlreadyEnabled | MfaError::Required => {
+                    (StatusCode::CONFLICT, vec![e.to_string()])
+                }
+                MfaError::Locked { .. } => (StatusCode::TOO_MANY_REQUESTS, vec![e.to_string()]),
+                MfaError::Database(_) | MfaError::Throttle(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()])
+                }
+            },
+            AccountControllerError::MissingClaims => (StatusCode::UNAUTHORIZED, vec!["missing claims".to_string()]),
+            AccountControllerError::Validation(msgs) => (StatusCode::BAD_REQUEST, msgs),
+        };
+
+        let mut response = (status, Json(ErrorResponse { code: status.as_u16(), messages })).into_response();
+        if let Some(retry_after) = retry_after {
+            response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+
+        response
+    }
+}
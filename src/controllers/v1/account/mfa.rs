@@ -0,0 +1,135 @@
+use axum::{
+    extract::{Extension, State},
+    Json,
+};
+use libs::jwt::Claims;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::pb;
+
+use super::{AccountControllerError, AccountState};
+
+// HTTP
+
+pub async fn enroll_totp(
+    State(state): State<Arc<AccountState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<pb::TotpEnrollment>, AccountControllerError> {
+    Ok(Json(do_enroll_totp(&state, &claims).await?))
+}
+
+pub async fn confirm_totp(
+    State(state): State<Arc<AccountState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<pb::TotpCodeRequest>,
+) -> Result<Json<pb::RecoveryCodes>, AccountControllerError> {
+    Ok(Json(do_confirm_totp(&state, &claims, &input).await?))
+}
+
+pub async fn disable_totp(
+    State(state): State<Arc<AccountState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<pb::TotpCodeRequest>,
+) -> Result<Json<pb::DisableTotpResponse>, AccountControllerError> {
+    do_disable_totp(&state, &claims, &input).await?;
+
+    Ok(Json(pb::DisableTotpResponse { status: "ok".to_string() }))
+}
+
+pub async fn regenerate_recovery_codes(
+    State(state): State<Arc<AccountState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<pb::TotpCodeRequest>,
+) -> Result<Json<pb::RecoveryCodes>, AccountControllerError> {
+    Ok(Json(do_regenerate_recovery_codes(&state, &claims, &input).await?))
+}
+
+// gRPC handlers
+
+pub async fn grpc_enroll_totp(
+    service: &AccountState,
+    request: Request<pb::EnrollTotpRequest>,
+) -> Result<Response<pb::TotpEnrollment>, Status> {
+    let claims = claims(&request)?;
+
+    Ok(Response::new(do_enroll_totp(service, &claims).await?))
+}
+
+pub async fn grpc_confirm_totp(
+    service: &AccountState,
+    request: Request<pb::TotpCodeRequest>,
+) -> Result<Response<pb::RecoveryCodes>, Status> {
+    let claims = claims(&request)?;
+
+    Ok(Response::new(do_confirm_totp(service, &claims, request.get_ref()).await?))
+}
+
+pub async fn grpc_disable_totp(
+    service: &AccountState,
+    request: Request<pb::TotpCodeRequest>,
+) -> Result<Response<pb::DisableTotpResponse>, Status> {
+    let claims = claims(&request)?;
+    do_disable_totp(service, &claims, request.get_ref()).await?;
+
+    Ok(Response::new(pb::DisableTotpResponse { status: "ok".to_string() }))
+}
+
+pub async fn grpc_regenerate_recovery_codes(
+    service: &AccountState,
+    request: Request<pb::TotpCodeRequest>,
+) -> Result<Response<pb::RecoveryCodes>, Status> {
+    let claims = claims(&request)?;
+
+    Ok(Response::new(do_regenerate_recovery_codes(service, &claims, request.get_ref()).await?))
+}
+
+fn claims<T>(request: &Request<T>) -> Result<Claims, AccountControllerError> {
+    request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AccountControllerError::MissingClaims)
+}
+
+async fn do_enroll_totp(state: &AccountState, claims: &Claims) -> Result<pb::TotpEnrollment, AccountControllerError> {
+    let user = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+    let enrollment = state.mfa_service.enroll(&state.db, &user).await?;
+
+    Ok(pb::TotpEnrollment {
+        secret: enrollment.secret,
+        otpauth_uri: enrollment.otpauth_uri,
+    })
+}
+
+async fn do_confirm_totp(
+    state: &AccountState,
+    claims: &Claims,
+    input: &pb::TotpCodeRequest,
+) -> Result<pb::RecoveryCodes, AccountControllerError> {
+    let user = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+    let codes = state.mfa_service.confirm(&state.db, user.user_id, &input.code).await?;
+
+    Ok(pb::RecoveryCodes { codes })
+}
+
+async fn do_disable_totp(
+    state: &AccountState,
+    claims: &Claims,
+    input: &pb::TotpCodeRequest,
+) -> Result<(), AccountControllerError> {
+    let user = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+
+    Ok(state.mfa_service.disable(&state.db, &user, &input.code).await?)
+}
+
+async fn do_regenerate_recovery_codes(
+    state: &AccountState,
+    claims: &Claims,
+    input: &pb::TotpCodeRequest,
+) -> Result<pb::RecoveryCodes, AccountControllerError> {
+    let user = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+    let codes = state.mfa_service.regenerate_recovery_codes(&state.db, user.user_id, &input.code).await?;
+
+    Ok(pb::RecoveryCodes { codes })
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use sea_orm::DatabaseConnection;
+use tonic::{Request, Response, Status};
+
+pub mod errors;
+pub mod mfa;
+pub mod password;
+
+use crate::inject::{InjectError, InjectFactory};
+use crate::pb;
+use crate::services::auth::Auth;
+use crate::services::mfa::MfaService;
+use crate::services::refresh_token::RefreshTokenService;
+use crate::services::revocation::RevocationService;
+
+pub use errors::AccountControllerError;
+pub use mfa::{confirm_totp, disable_totp, enroll_totp, regenerate_recovery_codes};
+pub use password::change_password;
+
+pub struct AccountState {
+    pub auth_service: Arc<dyn Auth>,
+    pub refresh_token_service: Arc<dyn RefreshTokenService>,
+    pub revocation_service: Arc<dyn RevocationService>,
+    pub mfa_service: Arc<dyn MfaService>,
+    pub db: Arc<DatabaseConnection>,
+}
+
+impl AccountState {
+    pub fn new(injector: &dyn InjectFactory) -> Result<Self, InjectError> {
+        Ok(Self {
+            auth_service: injector.auth_service()?,
+            refresh_token_service: injector.refresh_token_service()?,
+            revocation_service: injector.revocation_service()?,
+            mfa_service: injector.mfa_service()?,
+            db: injector.database()?,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl pb::account_service_server::AccountService for AccountState {
+    async fn change_password(
+        &self,
+        request: Request<pb::ChangePasswordRequest>,
+    ) -> Result<Response<pb::ChangePasswordResponse>, Status> {
+        password::grpc_change_password(self, request).await
+    }
+
+    async fn enroll_totp(
+        &self,
+        request: Request<pb::EnrollTotpRequest>,
+    ) -> Result<Response<pb::TotpEnrollment>, Status> {
+        mfa::grpc_enroll_totp(self, request).await
+    }
+
+    async fn confirm_totp(
+        &self,
+        request: Request<pb::TotpCodeRequest>,
+    ) -> Result<Response<pb::RecoveryCodes>, Status> {
+        mfa::grpc_confirm_totp(self, request).await
+    }
+
+    async fn disable_totp(
+        &self,
+        request: Request<pb::TotpCodeRequest>,
+    ) -> Result<Response<pb::DisableTotpResponse>, Status> {
+        mfa::grpc_disable_totp(self, request).await
+    }
+
+    async fn regenerate_recovery_codes(
+        &self,
+        request: Request<pb::TotpCodeRequest>,
+    ) -> Result<Response<pb::RecoveryCodes>, Status> {
+        mfa::grpc_regenerate_recovery_codes(self, request).await
+    }
+}
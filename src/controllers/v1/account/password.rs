@@ -0,0 +1,60 @@
+use axum::{
+    extract::{Extension, State},
+    Json,
+};
+use libs::jwt::Claims;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::controllers::v1::auth::password::NewPasswordInput;
+use crate::pb;
+
+use super::{AccountControllerError, AccountState};
+
+// HTTP
+
+pub async fn change_password(
+    State(state): State<Arc<AccountState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<pb::ChangePasswordRequest>,
+) -> Result<Json<pb::ChangePasswordResponse>, AccountControllerError> {
+    do_change_password(&state, &claims, &input).await?;
+
+    Ok(Json(pb::ChangePasswordResponse { status: "ok".to_string() }))
+}
+
+// gRPC handler
+
+pub async fn grpc_change_password(
+    service: &AccountState,
+    request: Request<pb::ChangePasswordRequest>,
+) -> Result<Response<pb::ChangePasswordResponse>, Status> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AccountControllerError::MissingClaims)?;
+
+    do_change_password(service, &claims, request.get_ref()).await?;
+
+    Ok(Response::new(pb::ChangePasswordResponse { status: "ok".to_string() }))
+}
+
+/// Every session of the account is revoked afterwards, the caller's included,
+/// so a stolen token does not outlive the password change.
+async fn do_change_password(
+    state: &AccountState,
+    claims: &Claims,
+    input: &pb::ChangePasswordRequest,
+) -> Result<(), AccountControllerError> {
+    NewPasswordInput::check(&input.new_password).map_err(AccountControllerError::Validation)?;
+
+    let user = state.auth_service
+        .change_password(&state.db, &claims.sub, &input.current_password, &input.new_password)
+        .await?;
+
+    state.revocation_service.revoke_subject(&state.db, &user.email).await?;
+    state.refresh_token_service.revoke_all_for_user(&state.db, user.user_id).await?;
+
+    Ok(())
+}
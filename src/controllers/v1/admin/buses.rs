@@ -0,0 +1,295 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::errors::extract_validation_messages;
+use crate::entities::seat::Model as SeatModel;
+use crate::pb;
+use crate::services::bus::{BusSummary, DuplicateSeatName, SeatInput};
+
+use super::{AdminControllerError, AdminState};
+
+/// Upper bound on seats added by one request.
+const MAX_SEATS_PER_REQUEST: usize = 200;
+
+#[derive(Validate)]
+pub struct BusValidation {
+    #[validate(length(max = 128, message = "bus name must be at most 128 characters"))]
+    pub name: Option<String>,
+
+    #[validate(length(min = 1, max = 16, message = "license_plate must be 1-16 characters"))]
+    pub license_plate: String,
+}
+
+impl From<BusSummary> for pb::Bus {
+    fn from(bus: BusSummary) -> Self {
+        pb::Bus {
+            bus_id: bus.bus_id,
+            name: bus.name,
+            license_plate: bus.license_plate,
+            deleted: bus.deleted,
+            seat_count: bus.seat_count,
+        }
+    }
+}
+
+impl From<SeatModel> for pb::BusSeat {
+    fn from(seat: SeatModel) -> Self {
+        pb::BusSeat {
+            seat_id: seat.seat_id,
+            name: seat.name,
+            price: seat.price,
+            row: seat.seat_row,
+            column: seat.seat_column,
+            deck: seat.deck,
+        }
+    }
+}
+
+impl From<DuplicateSeatName> for pb::DuplicateSeatName {
+    fn from(duplicate: DuplicateSeatName) -> Self {
+        pb::DuplicateSeatName {
+            bus_id: duplicate.bus_id,
+            name: duplicate.name,
+            seat_ids: duplicate.seat_ids,
+        }
+    }
+}
+
+fn to_layout(bus_id: i32, seats: Vec<SeatModel>) -> pb::SeatLayout {
+    pb::SeatLayout {
+        bus_id,
+        seats: seats.into_iter().map(pb::BusSeat::from).collect(),
+    }
+}
+
+// HTTP
+
+pub async fn list_buses(
+    State(state): State<Arc<AdminState>>,
+    Query(input): Query<pb::ListBusesRequest>,
+) -> Result<Json<pb::BusList>, AdminControllerError> {
+    Ok(Json(do_list_buses(&state, input).await?))
+}
+
+pub async fn create_bus(
+    State(state): State<Arc<AdminState>>,
+    Json(input): Json<pb::BusInput>,
+) -> Result<Json<pb::Bus>, AdminControllerError> {
+    Ok(Json(do_create_bus(&state, input).await?))
+}
+
+pub async fn get_seat_layout(
+    State(state): State<Arc<AdminState>>,
+    Path(bus_id): Path<i32>,
+) -> Result<Json<pb::SeatLayout>, AdminControllerError> {
+    let seats = state.bus_service.seat_layout(&state.db, bus_id).await?;
+
+    Ok(Json(to_layout(bus_id, seats)))
+}
+
+pub async fn add_seats(
+    State(state): State<Arc<AdminState>>,
+    Path(bus_id): Path<i32>,
+    Json(input): Json<pb::SeatsInput>,
+) -> Result<Json<pb::SeatLayout>, AdminControllerError> {
+    Ok(Json(do_add_seats(&state, bus_id, input.seats).await?))
+}
+
+pub async fn clone_seat_layout(
+    State(state): State<Arc<AdminState>>,
+    Path(bus_id): Path<i32>,
+    Json(input): Json<pb::CloneSeatLayoutInput>,
+) -> Result<Json<pb::SeatLayout>, AdminControllerError> {
+    let seats = state.bus_service
+        .clone_layout(&state.db, bus_id, input.source_bus_id)
+        .await?;
+
+    Ok(Json(to_layout(bus_id, seats)))
+}
+
+pub async fn delete_seat(
+    State(state): State<Arc<AdminState>>,
+    Path((bus_id, seat_id)): Path<(i32, i32)>,
+) -> Result<Json<pb::AdminResponse>, AdminControllerError> {
+    state.bus_service.delete_seat(&state.db, bus_id, seat_id).await?;
+
+    Ok(Json(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+pub async fn get_seat_layout_report(
+    State(state): State<Arc<AdminState>>,
+) -> Result<Json<pb::SeatLayoutReport>, AdminControllerError> {
+    Ok(Json(do_seat_layout_report(&state).await?))
+}
+
+// gRPC handlers
+
+pub async fn grpc_list_buses(
+    service: &AdminState,
+    request: Request<pb::ListBusesRequest>,
+) -> Result<Response<pb::BusList>, Status> {
+    Ok(Response::new(do_list_buses(service, request.into_inner()).await?))
+}
+
+pub async fn grpc_create_bus(
+    service: &AdminState,
+    request: Request<pb::BusInput>,
+) -> Result<Response<pb::Bus>, Status> {
+    Ok(Response::new(do_create_bus(service, request.into_inner()).await?))
+}
+
+pub async fn grpc_get_seat_layout(
+    service: &AdminState,
+    request: Request<pb::GetSeatLayoutRequest>,
+) -> Result<Response<pb::SeatLayout>, Status> {
+    let bus_id = request.into_inner().bus_id;
+    let seats = service.bus_service
+        .seat_layout(&service.db, bus_id)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(to_layout(bus_id, seats)))
+}
+
+pub async fn grpc_add_seats(
+    service: &AdminState,
+    request: Request<pb::AddSeatsRequest>,
+) -> Result<Response<pb::SeatLayout>, Status> {
+    let input = request.into_inner();
+
+    Ok(Response::new(do_add_seats(service, input.bus_id, input.seats).await?))
+}
+
+pub async fn grpc_clone_seat_layout(
+    service: &AdminState,
+    request: Request<pb::CloneSeatLayoutRequest>,
+) -> Result<Response<pb::SeatLayout>, Status> {
+    let input = request.into_inner();
+    let seats = service.bus_service
+        .clone_layout(&service.db, input.bus_id, input.source_bus_id)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(to_layout(input.bus_id, seats)))
+}
+
+pub async fn grpc_delete_seat(
+    service: &AdminState,
+    request: Request<pb::DeleteSeatRequest>,
+) -> Result<Response<pb::AdminResponse>, Status> {
+    let input = request.into_inner();
+
+    service.bus_service
+        .delete_seat(&service.db, input.bus_id, input.seat_id)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+pub async fn grpc_get_seat_layout_report(
+    service: &AdminState,
+    _request: Request<pb::GetSeatLayoutReportRequest>,
+) -> Result<Response<pb::SeatLayoutReport>, Status> {
+    Ok(Response::new(do_seat_layout_report(service).await?))
+}
+
+async fn do_list_buses(state: &AdminState, input: pb::ListBusesRequest) -> Result<pb::BusList, AdminControllerError> {
+    let buses = state.bus_service
+        .list_buses(&state.db, input.include_deleted.unwrap_or(false))
+        .await?;
+
+    Ok(pb::BusList {
+        buses: buses.into_iter().map(pb::Bus::from).collect(),
+    })
+}
+
+async fn do_create_bus(state: &AdminState, input: pb::BusInput) -> Result<pb::Bus, AdminControllerError> {
+    let validation = BusValidation {
+        name: input.name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty()),
+        license_plate: input.license_plate.trim().to_string(),
+    };
+    validation
+        .validate()
+        .map_err(|e| AdminControllerError::Validation(extract_validation_messages(e)))?;
+
+    let bus = state.bus_service
+        .create_bus(&state.db, validation.name, validation.license_plate)
+        .await?;
+
+    Ok(bus.into())
+}
+
+async fn do_add_seats(
+    state: &AdminState,
+    bus_id: i32,
+    seats: Vec<pb::SeatInput>,
+) -> Result<pb::SeatLayout, AdminControllerError> {
+    let seats = validate_seats(seats)?;
+    let seats = state.bus_service.add_seats(&state.db, bus_id, seats).await?;
+
+    Ok(to_layout(bus_id, seats))
+}
+
+async fn do_seat_layout_report(state: &AdminState) -> Result<pb::SeatLayoutReport, AdminControllerError> {
+    let duplicates = state.bus_service.duplicate_seat_names(&state.db).await?;
+
+    Ok(pb::SeatLayoutReport {
+        duplicates: duplicates.into_iter().map(pb::DuplicateSeatName::from).collect(),
+    })
+}
+
+fn validate_seats(seats: Vec<pb::SeatInput>) -> Result<Vec<SeatInput>, AdminControllerError> {
+    let mut messages: Vec<String> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut report = |message: String| {
+        if !messages.contains(&message) {
+            messages.push(message);
+        }
+    };
+
+    if seats.is_empty() || seats.len() > MAX_SEATS_PER_REQUEST {
+        report(format!("between 1 and {MAX_SEATS_PER_REQUEST} seats are required"));
+    }
+
+    let seats: Vec<SeatInput> = seats
+        .into_iter()
+        .map(|seat| SeatInput {
+            name: seat.name.trim().to_string(),
+            price: seat.price.unwrap_or(0),
+            row: seat.row,
+            column: seat.column,
+            deck: seat.deck.unwrap_or(1),
+        })
+        .collect();
+
+    for seat in &seats {
+        if seat.name.is_empty() || seat.name.chars().count() > 128 {
+            report("seat name must be 1-128 characters".to_string());
+        }
+        if seat.price < 0 {
+            report("seat price must not be negative".to_string());
+        }
+        if seat.row.is_some_and(|row| row < 1) || seat.column.is_some_and(|column| column < 1) {
+            report("row and column start at 1".to_string());
+        }
+        if !(1..=2).contains(&seat.deck) {
+            report("deck must be 1 or 2".to_string());
+        }
+        if !seen.insert(seat.name.as_str()) {
+            report(format!("seat name {} is listed more than once", seat.name));
+        }
+    }
+
+    if !messages.is_empty() {
+        return Err(AdminControllerError::Validation(messages));
+    }
+
+    Ok(seats)
+}
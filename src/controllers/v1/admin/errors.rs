@@ -0,0 +1,232 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use thiserror::Error;
+use tonic::Status;
+
+use crate::services::auth::AuthError;
+use crate::services::bus::BusError;
+use crate::services::location::LocationError;
+use crate::services::login_throttle::LoginThrottleError;
+use crate::services::payment::PaymentError;
+use crate::services::pricing::PricingError;
+use crate::services::refresh_token::RefreshTokenError;
+use crate::services::revocation::RevocationError;
+use crate::services::role::RoleError;
+use crate::services::route::RouteError;
+use crate::services::schedule::ScheduleError;
+use crate::services::ticket::TicketError;
+use crate::services::trip::TripError;
+
+#[derive(Debug, Error)]
+pub enum AdminControllerError {
+    #[error("auth error: {0}")]
+    Auth(#[from] AuthError),
+
+    #[error("refresh token error: {0}")]
+    RefreshToken(#[from] RefreshTokenError),
+
+    #[error("revocation error: {0}")]
+    Revocation(#[from] RevocationError),
+
+    #[error("role error: {0}")]
+    Role(#[from] RoleError),
+
+    #[error("login throttle error: {0}")]
+    LoginThrottle(#[from] LoginThrottleError),
+
+    #[error("ticket error: {0}")]
+    Ticket(#[from] TicketError),
+
+    #[error("route error: {0}")]
+    Route(#[from] RouteError),
+
+    #[error("bus error: {0}")]
+    Bus(#[from] BusError),
+
+    #[error("trip error: {0}")]
+    Trip(#[from] TripError),
+
+    #[error("schedule error: {0}")]
+    Schedule(#[from] ScheduleError),
+
+    #[error("location error: {0}")]
+    Location(#[from] LocationError),
+
+    #[error("pricing error: {0}")]
+    Pricing(#[from] PricingError),
+
+    #[error("missing claims")]
+    MissingClaims,
+
+    #[error("validation error: {0:?}")]
+    Validation(Vec<String>),
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    code: u16,
+    messages: Vec<String>,
+}
+
+// gRPC
+impl From<AdminControllerError> for Status {
+    fn from(err: AdminControllerError) -> Self {
+        match err {
+            AdminControllerError::Auth(e) => match e {
+                AuthError::UserNotFound => Status::not_found("user not found"),
+                _ => Status::internal("database error"),
+            },
+            AdminControllerError::RefreshToken(_) => Status::internal("failed to revoke refresh tokens"),
+            AdminControllerError::Revocation(_) => Status::internal("failed to revoke tokens"),
+            AdminControllerError::LoginThrottle(_) => Status::internal("failed to unlock user"),
+            AdminControllerError::Role(e) => match e {
+                RoleError::RoleNotFound => Status::not_found("role not found"),
+                RoleError::PolicyNotFound => Status::not_found("policy not found"),
+                RoleError::UserNotFound => Status::not_found("user not found"),
+                RoleError::UnknownPolicies(_) => Status::invalid_argument(e.to_string()),
+                RoleError::NameTaken(_) => Status::already_exists(e.to_string()),
+                RoleError::RoleInUse => Status::failed_precondition("role is still assigned to users"),
+                RoleError::Database(_) => Status::internal("database error"),
+            },
+            AdminControllerError::Ticket(e) => match e {
+                TicketError::RouteNotFound => Status::not_found("route not found"),
+                _ => Status::internal("database error"),
+            },
+            AdminControllerError::Route(e) => match e {
+                RouteError::RouteNotFound => Status::not_found("route not found"),
+                RouteError::HasUpcomingTrips(_) | RouteError::StopsInUse => Status::failed_precondition(e.to_string()),
+                RouteError::StopFaresExceedPrice(_)
+                | RouteError::StopFaresTooLarge
+                | RouteError::DuplicateStop(_)
+                | RouteError::UnknownLocation(_) => Status::invalid_argument(e.to_string()),
+                RouteError::Database(_) => Status::internal("database error"),
+            },
+            AdminControllerError::Bus(e) => match e {
+                BusError::BusNotFound => Status::not_found("bus not found"),
+                BusError::SeatNotFound => Status::not_found("seat not found"),
+                BusError::LicensePlateTaken(_) | BusError::DuplicateSeatNames(_) => Status::already_exists(e.to_string()),
+                BusError::SeatInUse => Status::failed_precondition(e.to_string()),
+                BusError::Database(_) => Status::internal("database error"),
+            },
+            AdminControllerError::Trip(e) => match e {
+                TripError::TripNotFound | TripError::RouteNotFound | TripError::BusNotFound => {
+                    Status::not_found(e.to_string())
+                }
+                TripError::Payment(PaymentError::Provider(_)) => Status::unavailable("payment provider error"),
+                TripError::Payment(_) | TripError::Database(_) => Status::internal("database error"),
+                _ => Status::failed_precondition(e.to_string()),
+            },
+            AdminControllerError::Schedule(e) => match e {
+                ScheduleError::ScheduleNotFound | ScheduleError::RouteNotFound | ScheduleError::BusNotFound => {
+                    Status::not_found(e.to_string())
+                }
+                ScheduleError::RouteDeleted | ScheduleError::BusDeleted => Status::failed_precondition(e.to_string()),
+                ScheduleError::Database(_) => Status::internal("database error"),
+            },
+            AdminControllerError::Location(e) => match e {
+                LocationError::LocationNotFound => Status::not_found(e.to_string()),
+                LocationError::NameTaken(_) => Status::already_exists(e.to_string()),
+                LocationError::DuplicateName(_) | LocationError::MergeIntoItself => {
+                    Status::invalid_argument(e.to_string())
+                }
+                LocationError::Database(_) => Status::internal("database error"),
+            },
+            AdminControllerError::Pricing(e) => match e {
+                PricingError::PromoNotFound => Status::not_found(e.to_string()),
+                PricingError::CodeTaken(_) => Status::already_exists(e.to_string()),
+                PricingError::UnknownRoutes(_) => Status::invalid_argument(e.to_string()),
+                PricingError::Database(_) => Status::internal("database error"),
+                _ => Status::failed_precondition(e.to_string()),
+            },
+            AdminControllerError::MissingClaims => Status::unauthenticated("missing claims"),
+            AdminControllerError::Validation(msgs) => Status::invalid_argument(msgs.join(", ")),
+        }
+    }
+}
+
+// HTTP
+impl IntoResponse for AdminControllerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, messages) = match self {
+            AdminControllerError::Auth(e) => match e {
+                AuthError::UserNotFound => (StatusCode::NOT_FOUND, vec!["user not found".to_string()]),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            AdminControllerError::RefreshToken(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["failed to revoke refresh tokens".to_string()]),
+            AdminControllerError::Revocation(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["failed to revoke tokens".to_string()]),
+            AdminControllerError::LoginThrottle(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["failed to unlock user".to_string()]),
+            AdminControllerError::Role(e) => match e {
+                RoleError::RoleNotFound => (StatusCode::NOT_FOUND, vec!["role not found".to_string()]),
+                RoleError::PolicyNotFound => (StatusCode::NOT_FOUND, vec!["policy not found".to_string()]),
+                RoleError::UserNotFound => (StatusCode::NOT_FOUND, vec!["user not found".to_string()]),
+                RoleError::UnknownPolicies(_) => (StatusCode::BAD_REQUEST, vec![e.to_string()]),
+                RoleError::NameTaken(_) => (StatusCode::CONFLICT, vec![e.to_string()]),
+                RoleError::RoleInUse => (StatusCode::CONFLICT, vec!["role is still assigned to users".to_string()]),
+                RoleError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            AdminControllerError::Ticket(e) => match e {
+                TicketError::RouteNotFound => (StatusCode::NOT_FOUND, vec!["route not found".to_string()]),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            AdminControllerError::Route(e) => match e {
+                RouteError::RouteNotFound => (StatusCode::NOT_FOUND, vec!["route not found".to_string()]),
+                RouteError::HasUpcomingTrips(_) | RouteError::StopsInUse => (StatusCode::CONFLICT, vec![e.to_string()]),
+                RouteError::StopFaresExceedPrice(_)
+                | RouteError::StopFaresTooLarge
+                | RouteError::DuplicateStop(_)
+                | RouteError::UnknownLocation(_) => (StatusCode::BAD_REQUEST, vec![e.to_string()]),
+                RouteError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            AdminControllerError::Bus(e) => match e {
+                BusError::BusNotFound => (StatusCode::NOT_FOUND, vec!["bus not found".to_string()]),
+                BusError::SeatNotFound => (StatusCode::NOT_FOUND, vec!["seat not found".to_string()]),
+                BusError::LicensePlateTaken(_) | BusError::DuplicateSeatNames(_) | BusError::SeatInUse => {
+                    (StatusCode::CONFLICT, vec![e.to_string()])
+                }
+                BusError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            AdminControllerError::Trip(e) => match e {
+                TripError::TripNotFound | TripError::RouteNotFound | TripError::BusNotFound => {
+                    (StatusCode::NOT_FOUND, vec![e.to_string()])
+                }
+                TripError::Payment(PaymentError::Provider(_)) => {
+                    (StatusCode::BAD_GATEWAY, vec!["payment provider error".to_string()])
+                }
+                TripError::Payment(_) | TripError::Database(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()])
+                }
+                _ => (StatusCode::CONFLICT, vec![e.to_string()]),
+            },
+            AdminControllerError::Schedule(e) => match e {
+                ScheduleError::ScheduleNotFound | ScheduleError::RouteNotFound | ScheduleError::BusNotFound => {
+                    (StatusCode::NOT_FOUND, vec![e.to_string()])
+                }
+                ScheduleError::RouteDeleted | ScheduleError::BusDeleted => (StatusCode::CONFLICT, vec![e.to_string()]),
+                ScheduleError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            AdminControllerError::Location(e) => match e {
+                LocationError::LocationNotFound => (StatusCode::NOT_FOUND, vec![e.to_string()]),
+                LocationError::NameTaken(_) => (StatusCode::CONFLICT, vec![e.to_string()]),
+                LocationError::DuplicateName(_) | LocationError::MergeIntoItself => {
+                    (StatusCode::BAD_REQUEST, vec![e.to_string()])
+                }
+                LocationError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            AdminControllerError::Pricing(e) => match e {
+                PricingError::PromoNotFound => (StatusCode::NOT_FOUND, vec![e.to_string()]),
+                PricingError::CodeTaken(_) => (StatusCode::CONFLICT, vec![e.to_string()]),
+                PricingError::UnknownRoutes(_) => (StatusCode::BAD_REQUEST, vec![e.to_string()]),
+                PricingError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+                _ => (StatusCode::CONFLICT, vec![e.to_string()]),
+            },
+            AdminControllerError::MissingClaims => (StatusCode::UNAUTHORIZED, vec!["missing claims".to_string()]),
+            AdminControllerError::Validation(msgs) => (StatusCode::BAD_REQUEST, msgs),
+        };
+
+        (status, Json(ErrorResponse { code: status.as_u16(), messages })).into_response()
+    }
+}
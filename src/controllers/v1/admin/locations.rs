@@ -0,0 +1,184 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::pb;
+use crate::services::location::{LocationDetails, LocationInput};
+
+use super::{AdminControllerError, AdminState};
+
+const MAX_ALIASES: usize = 20;
+
+impl From<LocationDetails> for pb::Location {
+    fn from(details: LocationDetails) -> Self {
+        pb::Location {
+            location_id: details.location.location_id,
+            name: details.location.name,
+            province: details.location.province,
+            latitude: details.location.latitude,
+            longitude: details.location.longitude,
+            aliases: details.aliases,
+        }
+    }
+}
+
+// HTTP
+
+pub async fn list_locations(
+    State(state): State<Arc<AdminState>>,
+) -> Result<Json<pb::LocationList>, AdminControllerError> {
+    Ok(Json(do_list_locations(&state).await?))
+}
+
+pub async fn create_location(
+    State(state): State<Arc<AdminState>>,
+    Json(input): Json<pb::LocationInput>,
+) -> Result<Json<pb::Location>, AdminControllerError> {
+    let input = validate_location(input.name, input.province, input.latitude, input.longitude, input.aliases)?;
+
+    Ok(Json(state.location_service.create_location(&state.db, input).await?.into()))
+}
+
+pub async fn update_location(
+    State(state): State<Arc<AdminState>>,
+    Path(location_id): Path<i32>,
+    Json(input): Json<pb::LocationInput>,
+) -> Result<Json<pb::Location>, AdminControllerError> {
+    let input = validate_location(input.name, input.province, input.latitude, input.longitude, input.aliases)?;
+
+    Ok(Json(state.location_service.update_location(&state.db, location_id, input).await?.into()))
+}
+
+pub async fn merge_location(
+    State(state): State<Arc<AdminState>>,
+    Path(location_id): Path<i32>,
+    Json(input): Json<pb::MergeLocationInput>,
+) -> Result<Json<pb::Location>, AdminControllerError> {
+    let location = state.location_service
+        .merge_location(&state.db, location_id, input.into_location_id)
+        .await?;
+
+    Ok(Json(location.into()))
+}
+
+// gRPC handlers
+
+pub async fn grpc_list_locations(
+    service: &AdminState,
+    _request: Request<pb::ListLocationsRequest>,
+) -> Result<Response<pb::LocationList>, Status> {
+    Ok(Response::new(do_list_locations(service).await?))
+}
+
+pub async fn grpc_create_location(
+    service: &AdminState,
+    request: Request<pb::LocationInput>,
+) -> Result<Response<pb::Location>, Status> {
+    let input = request.into_inner();
+    let input = validate_location(input.name, input.province, input.latitude, input.longitude, input.aliases)?;
+
+    let location = service.location_service
+        .create_location(&service.db, input)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(location.into()))
+}
+
+pub async fn grpc_update_location(
+    service: &AdminState,
+    request: Request<pb::UpdateLocationRequest>,
+) -> Result<Response<pb::Location>, Status> {
+    let input = request.into_inner();
+    let location_id = input.location_id;
+    let input = validate_location(input.name, input.province, input.latitude, input.longitude, input.aliases)?;
+
+    let location = service.location_service
+        .update_location(&service.db, location_id, input)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(location.into()))
+}
+
+pub async fn grpc_merge_location(
+    service: &AdminState,
+    request: Request<pb::MergeLocationRequest>,
+) -> Result<Response<pb::Location>, Status> {
+    let input = request.into_inner();
+
+    let location = service.location_service
+        .merge_location(&service.db, input.location_id, input.into_location_id)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(location.into()))
+}
+
+async fn do_list_locations(state: &AdminState) -> Result<pb::LocationList, AdminControllerError> {
+    let locations = state.location_service.list_locations(&state.db).await?;
+
+    Ok(pb::LocationList {
+        locations: locations.into_iter().map(pb::Location::from).collect(),
+    })
+}
+
+fn validate_location(
+    name: String,
+    province: Option<String>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    aliases: Vec<String>,
+) -> Result<LocationInput, AdminControllerError> {
+    let mut messages: Vec<String> = Vec::new();
+    let mut report = |message: String| {
+        if !messages.contains(&message) {
+            messages.push(message);
+        }
+    };
+
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().count() > 128 {
+        report("name must be 1-128 characters".to_string());
+    }
+
+    let province = province.map(|province| province.trim().to_string()).filter(|province| !province.is_empty());
+    if province.as_ref().is_some_and(|province| province.chars().count() > 128) {
+        report("province must be at most 128 characters".to_string());
+    }
+
+    if latitude.is_some() != longitude.is_some() {
+        report("latitude and longitude must be given together".to_string());
+    }
+    if latitude.is_some_and(|latitude| !(-90.0..=90.0).contains(&latitude)) {
+        report("latitude must be between -90 and 90".to_string());
+    }
+    if longitude.is_some_and(|longitude| !(-180.0..=180.0).contains(&longitude)) {
+        report("longitude must be between -180 and 180".to_string());
+    }
+
+    if aliases.len() > MAX_ALIASES {
+        report(format!("a location has at most {MAX_ALIASES} aliases"));
+    }
+    let aliases: Vec<String> = aliases.into_iter().map(|alias| alias.trim().to_string()).collect();
+    for alias in &aliases {
+        if alias.is_empty() || alias.chars().count() > 128 {
+            report("aliases must be 1-128 characters".to_string());
+        }
+    }
+
+    if !messages.is_empty() {
+        return Err(AdminControllerError::Validation(messages));
+    }
+
+    Ok(LocationInput {
+        name,
+        province,
+        latitude,
+        longitude,
+        aliases,
+    })
+}
@@ -0,0 +1,389 @@
+use std::sync::Arc;
+
+use sea_orm::DatabaseConnection;
+use tonic::{Request, Response, Status};
+
+pub mod buses;
+pub mod errors;
+pub mod locations;
+pub mod policies;
+pub mod promo_codes;
+pub mod refunds;
+pub mod roles;
+pub mod routes;
+pub mod schedules;
+pub mod tokens;
+pub mod trips;
+pub mod users;
+
+use crate::inject::{InjectError, InjectFactory};
+use crate::pb;
+use crate::services::auth::Auth;
+use crate::services::bus::BusService;
+use crate::services::location::LocationService;
+use crate::services::login_throttle::LoginThrottleService;
+use crate::services::pricing::PricingService;
+use crate::services::refresh_token::RefreshTokenService;
+use crate::services::revocation::RevocationService;
+use crate::services::role::RoleService;
+use crate::services::route::RouteService;
+use crate::services::schedule::ScheduleService;
+use crate::services::ticket::TicketService;
+use crate::services::trip::TripService;
+
+pub use buses::{
+    add_seats, clone_seat_layout, create_bus, delete_seat, get_seat_layout, get_seat_layout_report, list_buses,
+};
+pub use errors::AdminControllerError;
+pub use locations::{create_location, list_locations, merge_location, update_location};
+pub use policies::{create_policy, delete_policy, list_policies, update_policy};
+pub use promo_codes::{create_promo_code, list_promo_codes, update_promo_code};
+pub use refunds::{get_refund_policy, set_refund_policy};
+pub use roles::{create_role, delete_role, list_roles, update_role};
+pub use routes::{
+    create_route, delete_route, get_route, get_route_stops, list_routes, restore_route, set_route_stops, update_route,
+};
+pub use schedules::{create_schedule, deactivate_schedule, generate_trips, list_schedules, preview_schedule};
+pub use tokens::revoke_user_tokens;
+pub use trips::{cancel_trip, create_trip, update_trip};
+pub use users::{set_user_role, unlock_user};
+
+pub struct AdminState {
+    pub auth_service: Arc<dyn Auth>,
+    pub refresh_token_service: Arc<dyn RefreshTokenService>,
+    pub revocation_service: Arc<dyn RevocationService>,
+    pub role_service: Arc<dyn RoleService>,
+    pub login_throttle_service: Arc<dyn LoginThrottleService>,
+    pub ticket_service: Arc<dyn TicketService>,
+    pub route_service: Arc<dyn RouteService>,
+    pub bus_service: Arc<dyn BusService>,
+    pub trip_service: Arc<dyn TripService>,
+    pub schedule_service: Arc<dyn ScheduleService>,
+    pub location_service: Arc<dyn LocationService>,
+    pub pricing_service: Arc<dyn PricingService>,
+    pub db: Arc<DatabaseConnection>,
+}
+
+impl AdminState {
+    pub fn new(injector: &dyn InjectFactory) -> Result<Self, InjectError> {
+        Ok(Self {
+            auth_service: injector.auth_service()?,
+            refresh_token_service: injector.refresh_token_service()?,
+            revocation_service: injector.revocation_service()?,
+            role_service: injector.role_service()?,
+            login_throttle_service: injector.login_throttle_service()?,
+            ticket_service: injector.ticket_service()?,
+            route_service: injector.route_service()?,
+            bus_service: injector.bus_service()?,
+            trip_service: injector.trip_service()?,
+            schedule_service: injector.schedule_service()?,
+            location_service: injector.location_service()?,
+            pricing_service: injector.pricing_service()?,
+            db: injector.database()?,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl pb::admin_service_server::AdminService for AdminState {
+    async fn revoke_user_tokens(
+        &self,
+        request: Request<pb::RevokeUserTokensRequest>,
+    ) -> Result<Response<pb::AdminResponse>, Status> {
+        tokens::grpc_revoke_user_tokens(self, request).await
+    }
+
+    async fn set_user_role(
+        &self,
+        request: Request<pb::SetUserRoleRequest>,
+    ) -> Result<Response<pb::AdminResponse>, Status> {
+        users::grpc_set_user_role(self, request).await
+    }
+
+    async fn unlock_user(
+        &self,
+        request: Request<pb::UnlockUserRequest>,
+    ) -> Result<Response<pb::AdminResponse>, Status> {
+        users::grpc_unlock_user(self, request).await
+    }
+
+    async fn list_roles(
+        &self,
+        request: Request<pb::ListRolesRequest>,
+    ) -> Result<Response<pb::RoleList>, Status> {
+        roles::grpc_list_roles(self, request).await
+    }
+
+    async fn create_role(
+        &self,
+        request: Request<pb::RoleInput>,
+    ) -> Result<Response<pb::Role>, Status> {
+        roles::grpc_create_role(self, request).await
+    }
+
+    async fn update_role(
+        &self,
+        request: Request<pb::UpdateRoleRequest>,
+    ) -> Result<Response<pb::Role>, Status> {
+        roles::grpc_update_role(self, request).await
+    }
+
+    async fn delete_role(
+        &self,
+        request: Request<pb::DeleteRoleRequest>,
+    ) -> Result<Response<pb::AdminResponse>, Status> {
+        roles::grpc_delete_role(self, request).await
+    }
+
+    async fn list_policies(
+        &self,
+        request: Request<pb::ListPoliciesRequest>,
+    ) -> Result<Response<pb::PolicyList>, Status> {
+        policies::grpc_list_policies(self, request).await
+    }
+
+    async fn create_policy(
+        &self,
+        request: Request<pb::PolicyInput>,
+    ) -> Result<Response<pb::Policy>, Status> {
+        policies::grpc_create_policy(self, request).await
+    }
+
+    async fn update_policy(
+        &self,
+        request: Request<pb::UpdatePolicyRequest>,
+    ) -> Result<Response<pb::Policy>, Status> {
+        policies::grpc_update_policy(self, request).await
+    }
+
+    async fn delete_policy(
+        &self,
+        request: Request<pb::DeletePolicyRequest>,
+    ) -> Result<Response<pb::AdminResponse>, Status> {
+        policies::grpc_delete_policy(self, request).await
+    }
+
+    async fn get_refund_policy(
+        &self,
+        request: Request<pb::GetRefundPolicyRequest>,
+    ) -> Result<Response<pb::RefundPolicy>, Status> {
+        refunds::grpc_get_refund_policy(self, request).await
+    }
+
+    async fn set_refund_policy(
+        &self,
+        request: Request<pb::RefundPolicy>,
+    ) -> Result<Response<pb::RefundPolicy>, Status> {
+        refunds::grpc_set_refund_policy(self, request).await
+    }
+
+    async fn list_routes(
+        &self,
+        request: Request<pb::ListRoutesRequest>,
+    ) -> Result<Response<pb::RouteList>, Status> {
+        routes::grpc_list_routes(self, request).await
+    }
+
+    async fn get_route(
+        &self,
+        request: Request<pb::GetRouteRequest>,
+    ) -> Result<Response<pb::Route>, Status> {
+        routes::grpc_get_route(self, request).await
+    }
+
+    async fn create_route(
+        &self,
+        request: Request<pb::RouteInput>,
+    ) -> Result<Response<pb::Route>, Status> {
+        routes::grpc_create_route(self, request).await
+    }
+
+    async fn update_route(
+        &self,
+        request: Request<pb::UpdateRouteRequest>,
+    ) -> Result<Response<pb::Route>, Status> {
+        routes::grpc_update_route(self, request).await
+    }
+
+    async fn delete_route(
+        &self,
+        request: Request<pb::DeleteRouteRequest>,
+    ) -> Result<Response<pb::AdminResponse>, Status> {
+        routes::grpc_delete_route(self, request).await
+    }
+
+    async fn restore_route(
+        &self,
+        request: Request<pb::RestoreRouteRequest>,
+    ) -> Result<Response<pb::Route>, Status> {
+        routes::grpc_restore_route(self, request).await
+    }
+
+    async fn get_route_stops(
+        &self,
+        request: Request<pb::GetRouteStopsRequest>,
+    ) -> Result<Response<pb::RouteStops>, Status> {
+        routes::grpc_get_route_stops(self, request).await
+    }
+
+    async fn set_route_stops(
+        &self,
+        request: Request<pb::SetRouteStopsRequest>,
+    ) -> Result<Response<pb::RouteStops>, Status> {
+        routes::grpc_set_route_stops(self, request).await
+    }
+
+    async fn list_buses(
+        &self,
+        request: Request<pb::ListBusesRequest>,
+    ) -> Result<Response<pb::BusList>, Status> {
+        buses::grpc_list_buses(self, request).await
+    }
+
+    async fn create_bus(
+        &self,
+        request: Request<pb::BusInput>,
+    ) -> Result<Response<pb::Bus>, Status> {
+        buses::grpc_create_bus(self, request).await
+    }
+
+    async fn get_seat_layout(
+        &self,
+        request: Request<pb::GetSeatLayoutRequest>,
+    ) -> Result<Response<pb::SeatLayout>, Status> {
+        buses::grpc_get_seat_layout(self, request).await
+    }
+
+    async fn add_seats(
+        &self,
+        request: Request<pb::AddSeatsRequest>,
+    ) -> Result<Response<pb::SeatLayout>, Status> {
+        buses::grpc_add_seats(self, request).await
+    }
+
+    async fn clone_seat_layout(
+        &self,
+        request: Request<pb::CloneSeatLayoutRequest>,
+    ) -> Result<Response<pb::SeatLayout>, Status> {
+        buses::grpc_clone_seat_layout(self, request).await
+    }
+
+    async fn delete_seat(
+        &self,
+        request: Request<pb::DeleteSeatRequest>,
+    ) -> Result<Response<pb::AdminResponse>, Status> {
+        buses::grpc_delete_seat(self, request).await
+    }
+
+    async fn get_seat_layout_report(
+        &self,
+        request: Request<pb::GetSeatLayoutReportRequest>,
+    ) -> Result<Response<pb::SeatLayoutReport>, Status> {
+        buses::grpc_get_seat_layout_report(self, request).await
+    }
+
+    async fn create_trip(
+        &self,
+        request: Request<pb::TripInput>,
+    ) -> Result<Response<pb::ScheduledTrip>, Status> {
+        trips::grpc_create_trip(self, request).await
+    }
+
+    async fn update_trip(
+        &self,
+        request: Request<pb::UpdateTripRequest>,
+    ) -> Result<Response<pb::ScheduledTrip>, Status> {
+        trips::grpc_update_trip(self, request).await
+    }
+
+    async fn cancel_trip(
+        &self,
+        request: Request<pb::CancelTripRequest>,
+    ) -> Result<Response<pb::TripCancellation>, Status> {
+        trips::grpc_cancel_trip(self, request).await
+    }
+
+    async fn list_schedules(
+        &self,
+        request: Request<pb::ListSchedulesRequest>,
+    ) -> Result<Response<pb::ScheduleList>, Status> {
+        schedules::grpc_list_schedules(self, request).await
+    }
+
+    async fn create_schedule(
+        &self,
+        request: Request<pb::ScheduleInput>,
+    ) -> Result<Response<pb::Schedule>, Status> {
+        schedules::grpc_create_schedule(self, request).await
+    }
+
+    async fn deactivate_schedule(
+        &self,
+        request: Request<pb::DeactivateScheduleRequest>,
+    ) -> Result<Response<pb::AdminResponse>, Status> {
+        schedules::grpc_deactivate_schedule(self, request).await
+    }
+
+    async fn preview_schedule(
+        &self,
+        request: Request<pb::PreviewScheduleRequest>,
+    ) -> Result<Response<pb::SchedulePreview>, Status> {
+        schedules::grpc_preview_schedule(self, request).await
+    }
+
+    async fn generate_trips(
+        &self,
+        request: Request<pb::GenerateTripsRequest>,
+    ) -> Result<Response<pb::GenerationReport>, Status> {
+        schedules::grpc_generate_trips(self, request).await
+    }
+
+    async fn list_locations(
+        &self,
+        request: Request<pb::ListLocationsRequest>,
+    ) -> Result<Response<pb::LocationList>, Status> {
+        locations::grpc_list_locations(self, request).await
+    }
+
+    async fn create_location(
+        &self,
+        request: Request<pb::LocationInput>,
+    ) -> Result<Response<pb::Location>, Status> {
+        locations::grpc_create_location(self, request).await
+    }
+
+    async fn update_location(
+        &self,
+        request: Request<pb::UpdateLocationRequest>,
+    ) -> Result<Response<pb::Location>, Status> {
+        locations::grpc_update_location(self, request).await
+    }
+
+    async fn merge_location(
+        &self,
+        request: Request<pb::MergeLocationRequest>,
+    ) -> Result<Response<pb::Location>, Status> {
+        locations::grpc_merge_location(self, request).await
+    }
+
+    async fn list_promo_codes(
+        &self,
+        request: Request<pb::ListPromoCodesRequest>,
+    ) -> Result<Response<pb::PromoCodeList>, Status> {
+        promo_codes::grpc_list_promo_codes(self, request).await
+    }
+
+    async fn create_promo_code(
+        &self,
+        request: Request<pb::PromoCodeInput>,
+    ) -> Result<Response<pb::PromoCode>, Status> {
+        promo_codes::grpc_create_promo_code(self, request).await
+    }
+
+    async fn update_promo_code(
+        &self,
+        request: Request<pb::UpdatePromoCodeRequest>,
+    ) -> Result<Response<pb::PromoCode>, Status> {
+        promo_codes::grpc_update_promo_code(self, request).await
+    }
+}
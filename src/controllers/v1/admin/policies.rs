@@ -0,0 +1,139 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::errors::extract_validation_messages;
+use crate::entities::policy::Model as PolicyModel;
+use crate::pb;
+
+use super::{AdminControllerError, AdminState};
+
+#[derive(Validate)]
+pub struct PolicyValidation {
+    #[validate(length(min = 1, max = 128, message = "policy name must be 1-128 characters"))]
+    pub name: String,
+
+    #[validate(length(max = 255, message = "description must be at most 255 characters"))]
+    pub description: String,
+}
+
+impl From<PolicyModel> for pb::Policy {
+    fn from(policy: PolicyModel) -> Self {
+        pb::Policy {
+            policy_id: policy.policy_id,
+            name: policy.name,
+            description: policy.description,
+        }
+    }
+}
+
+// HTTP
+
+pub async fn list_policies(
+    State(state): State<Arc<AdminState>>,
+) -> Result<Json<pb::PolicyList>, AdminControllerError> {
+    Ok(Json(do_list_policies(&state).await?))
+}
+
+pub async fn create_policy(
+    State(state): State<Arc<AdminState>>,
+    Json(input): Json<pb::PolicyInput>,
+) -> Result<Json<pb::Policy>, AdminControllerError> {
+    let input = validate(input.name, input.description)?;
+
+    let policy = state.role_service.create_policy(&state.db, &input.name, &input.description).await?;
+
+    Ok(Json(policy.into()))
+}
+
+pub async fn update_policy(
+    State(state): State<Arc<AdminState>>,
+    Path(policy_id): Path<i32>,
+    Json(input): Json<pb::PolicyInput>,
+) -> Result<Json<pb::Policy>, AdminControllerError> {
+    let input = validate(input.name, input.description)?;
+
+    let policy = state.role_service.update_policy(&state.db, policy_id, &input.name, &input.description).await?;
+
+    Ok(Json(policy.into()))
+}
+
+pub async fn delete_policy(
+    State(state): State<Arc<AdminState>>,
+    Path(policy_id): Path<i32>,
+) -> Result<Json<pb::AdminResponse>, AdminControllerError> {
+    state.role_service.delete_policy(&state.db, policy_id).await?;
+
+    Ok(Json(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+// gRPC handlers
+
+pub async fn grpc_list_policies(
+    service: &AdminState,
+    _request: Request<pb::ListPoliciesRequest>,
+) -> Result<Response<pb::PolicyList>, Status> {
+    Ok(Response::new(do_list_policies(service).await?))
+}
+
+pub async fn grpc_create_policy(
+    service: &AdminState,
+    request: Request<pb::PolicyInput>,
+) -> Result<Response<pb::Policy>, Status> {
+    let input = request.into_inner();
+    let input = validate(input.name, input.description)?;
+
+    let policy = service.role_service.create_policy(&service.db, &input.name, &input.description)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(policy.into()))
+}
+
+pub async fn grpc_update_policy(
+    service: &AdminState,
+    request: Request<pb::UpdatePolicyRequest>,
+) -> Result<Response<pb::Policy>, Status> {
+    let input = request.into_inner();
+    let policy_id = input.policy_id;
+    let input = validate(input.name, input.description)?;
+
+    let policy = service.role_service.update_policy(&service.db, policy_id, &input.name, &input.description)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(policy.into()))
+}
+
+pub async fn grpc_delete_policy(
+    service: &AdminState,
+    request: Request<pb::DeletePolicyRequest>,
+) -> Result<Response<pb::AdminResponse>, Status> {
+    service.role_service.delete_policy(&service.db, request.into_inner().policy_id)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+async fn do_list_policies(state: &AdminState) -> Result<pb::PolicyList, AdminControllerError> {
+    let policies = state.role_service.list_policies(&state.db).await?;
+
+    Ok(pb::PolicyList {
+        policies: policies.into_iter().map(pb::Policy::from).collect(),
+    })
+}
+
+fn validate(name: String, description: Option<String>) -> Result<PolicyValidation, AdminControllerError> {
+    let input = PolicyValidation {
+        name,
+        description: description.unwrap_or_default(),
+    };
+    input.validate().map_err(|e| AdminControllerError::Validation(extract_validation_messages(e)))?;
+
+    Ok(input)
+}
@@ -0,0 +1,215 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::NaiveDateTime;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::controllers::v1::booking::bookings::DATE_FORMAT;
+use crate::entities::sea_orm_active_enums::PromoKind;
+use crate::pb;
+use crate::services::pricing::{PromoDetails, PromoInput, kind_name, normalize_code, parse_kind};
+
+use super::{AdminControllerError, AdminState};
+
+const MAX_ROUTES: usize = 100;
+
+impl From<PromoDetails> for pb::PromoCode {
+    fn from(details: PromoDetails) -> Self {
+        let promo = details.promo;
+        pb::PromoCode {
+            promo_code_id: promo.promo_code_id,
+            code: promo.code,
+            kind: kind_name(promo.kind).to_string(),
+            value: promo.value,
+            max_discount: promo.max_discount,
+            min_spend: promo.min_spend,
+            valid_from: promo.valid_from.map(|from| from.format(DATE_FORMAT).to_string()),
+            valid_until: promo.valid_until.map(|until| until.format(DATE_FORMAT).to_string()),
+            max_uses: promo.max_uses,
+            max_uses_per_user: promo.max_uses_per_user,
+            route_ids: details.route_ids,
+            active: promo.active,
+            uses: details.uses,
+            created_at: promo.created_at.format(DATE_FORMAT).to_string(),
+        }
+    }
+}
+
+impl From<pb::UpdatePromoCodeRequest> for pb::PromoCodeInput {
+    fn from(request: pb::UpdatePromoCodeRequest) -> Self {
+        pb::PromoCodeInput {
+            code: request.code,
+            kind: request.kind,
+            value: request.value,
+            max_discount: request.max_discount,
+            min_spend: request.min_spend,
+            valid_from: request.valid_from,
+            valid_until: request.valid_until,
+            max_uses: request.max_uses,
+            max_uses_per_user: request.max_uses_per_user,
+            route_ids: request.route_ids,
+            active: request.active,
+        }
+    }
+}
+
+// HTTP
+
+pub async fn list_promo_codes(
+    State(state): State<Arc<AdminState>>,
+) -> Result<Json<pb::PromoCodeList>, AdminControllerError> {
+    Ok(Json(do_list_promo_codes(&state).await?))
+}
+
+pub async fn create_promo_code(
+    State(state): State<Arc<AdminState>>,
+    Json(input): Json<pb::PromoCodeInput>,
+) -> Result<Json<pb::PromoCode>, AdminControllerError> {
+    let input = validate_promo_code(input)?;
+
+    Ok(Json(state.pricing_service.create_promo_code(&state.db, input).await?.into()))
+}
+
+pub async fn update_promo_code(
+    State(state): State<Arc<AdminState>>,
+    Path(promo_code_id): Path<i32>,
+    Json(input): Json<pb::PromoCodeInput>,
+) -> Result<Json<pb::PromoCode>, AdminControllerError> {
+    let input = validate_promo_code(input)?;
+
+    Ok(Json(state.pricing_service.update_promo_code(&state.db, promo_code_id, input).await?.into()))
+}
+
+// gRPC handlers
+
+pub async fn grpc_list_promo_codes(
+    service: &AdminState,
+    _request: Request<pb::ListPromoCodesRequest>,
+) -> Result<Response<pb::PromoCodeList>, Status> {
+    Ok(Response::new(do_list_promo_codes(service).await?))
+}
+
+pub async fn grpc_create_promo_code(
+    service: &AdminState,
+    request: Request<pb::PromoCodeInput>,
+) -> Result<Response<pb::PromoCode>, Status> {
+    let input = validate_promo_code(request.into_inner())?;
+
+    let promo = service.pricing_service
+        .create_promo_code(&service.db, input)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(promo.into()))
+}
+
+pub async fn grpc_update_promo_code(
+    service: &AdminState,
+    request: Request<pb::UpdatePromoCodeRequest>,
+) -> Result<Response<pb::PromoCode>, Status> {
+    let request = request.into_inner();
+    let promo_code_id = request.promo_code_id;
+    let input = validate_promo_code(request.into())?;
+
+    let promo = service.pricing_service
+        .update_promo_code(&service.db, promo_code_id, input)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(promo.into()))
+}
+
+async fn do_list_promo_codes(state: &AdminState) -> Result<pb::PromoCodeList, AdminControllerError> {
+    let promo_codes = state.pricing_service.list_promo_codes(&state.db).await?;
+
+    Ok(pb::PromoCodeList {
+        promo_codes: promo_codes.into_iter().map(pb::PromoCode::from).collect(),
+    })
+}
+
+fn validate_promo_code(input: pb::PromoCodeInput) -> Result<PromoInput, AdminControllerError> {
+    let mut messages: Vec<String> = Vec::new();
+    let mut report = |message: String| {
+        if !messages.contains(&message) {
+            messages.push(message);
+        }
+    };
+
+    let code = normalize_code(&input.code);
+    if !(3..=32).contains(&code.len())
+        || !code.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    {
+        report("code must be 3-32 letters, digits, '_' or '-'".to_string());
+    }
+
+    let kind = parse_kind(input.kind.trim());
+    if kind.is_none() {
+        report("kind must be percentage or fixed".to_string());
+    }
+    if input.value <= 0 {
+        report("value must be positive".to_string());
+    }
+    if kind == Some(PromoKind::Percentage) && input.value > 100 {
+        report("a percentage value must be at most 100".to_string());
+    }
+    if let Some(max_discount) = input.max_discount {
+        if max_discount <= 0 {
+            report("max_discount must be positive".to_string());
+        }
+        if kind == Some(PromoKind::Fixed) {
+            report("max_discount only applies to percentage codes".to_string());
+        }
+    }
+
+    let min_spend = input.min_spend.unwrap_or(0);
+    if min_spend < 0 {
+        report("min_spend must not be negative".to_string());
+    }
+    if input.max_uses.is_some_and(|max_uses| max_uses <= 0) {
+        report("max_uses must be positive".to_string());
+    }
+    if input.max_uses_per_user.is_some_and(|max_uses| max_uses <= 0) {
+        report("max_uses_per_user must be positive".to_string());
+    }
+
+    let mut parse = |field: &str, value: Option<String>| {
+        let value = value.filter(|value| !value.trim().is_empty())?;
+        NaiveDateTime::parse_from_str(value.trim(), DATE_FORMAT)
+            .map_err(|_| report(format!("{field} must look like 2024-01-31T08:00:00")))
+            .ok()
+    };
+    let valid_from = parse("valid_from", input.valid_from);
+    let valid_until = parse("valid_until", input.valid_until);
+    if let (Some(valid_from), Some(valid_until)) = (valid_from, valid_until)
+        && valid_until <= valid_from
+    {
+        report("valid_until must be after valid_from".to_string());
+    }
+
+    let mut route_ids = input.route_ids;
+    route_ids.sort_unstable();
+    route_ids.dedup();
+    if route_ids.len() > MAX_ROUTES {
+        report(format!("a promo code applies to at most {MAX_ROUTES} routes"));
+    }
+
+    let Some(kind) = kind.filter(|_| messages.is_empty()) else {
+        return Err(AdminControllerError::Validation(messages));
+    };
+
+    Ok(PromoInput {
+        code,
+        kind,
+        value: input.value,
+        max_discount: input.max_discount,
+        min_spend,
+        valid_from,
+        valid_until,
+        max_uses: input.max_uses,
+        max_uses_per_user: input.max_uses_per_user,
+        route_ids,
+        active: input.active.unwrap_or(true),
+    })
+}
@@ -0,0 +1,113 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::pb;
+use crate::services::ticket::RefundTier;
+
+use super::{AdminControllerError, AdminState};
+
+fn to_pb(route_id: i32, tiers: Vec<RefundTier>) -> pb::RefundPolicy {
+    pb::RefundPolicy {
+        route_id,
+        tiers: tiers
+            .into_iter()
+            .map(|tier| pb::RefundTier {
+                min_hours_before: tier.min_hours_before,
+                refund_percent: tier.refund_percent,
+            })
+            .collect(),
+    }
+}
+
+// HTTP
+
+pub async fn get_refund_policy(
+    State(state): State<Arc<AdminState>>,
+    Path(route_id): Path<i32>,
+) -> Result<Json<pb::RefundPolicy>, AdminControllerError> {
+    let tiers = state.ticket_service.refund_policy(&state.db, route_id).await?;
+
+    Ok(Json(to_pb(route_id, tiers)))
+}
+
+pub async fn set_refund_policy(
+    State(state): State<Arc<AdminState>>,
+    Path(route_id): Path<i32>,
+    Json(input): Json<pb::RefundPolicyInput>,
+) -> Result<Json<pb::RefundPolicy>, AdminControllerError> {
+    Ok(Json(do_set_refund_policy(&state, route_id, &input.tiers).await?))
+}
+
+// gRPC handlers
+
+pub async fn grpc_get_refund_policy(
+    service: &AdminState,
+    request: Request<pb::GetRefundPolicyRequest>,
+) -> Result<Response<pb::RefundPolicy>, Status> {
+    let route_id = request.into_inner().route_id;
+    let tiers = service.ticket_service
+        .refund_policy(&service.db, route_id)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(to_pb(route_id, tiers)))
+}
+
+pub async fn grpc_set_refund_policy(
+    service: &AdminState,
+    request: Request<pb::RefundPolicy>,
+) -> Result<Response<pb::RefundPolicy>, Status> {
+    let input = request.into_inner();
+
+    Ok(Response::new(do_set_refund_policy(service, input.route_id, &input.tiers).await?))
+}
+
+async fn do_set_refund_policy(
+    state: &AdminState,
+    route_id: i32,
+    tiers: &[pb::RefundTier],
+) -> Result<pb::RefundPolicy, AdminControllerError> {
+    let tiers = validate_tiers(tiers)?;
+    let tiers = state.ticket_service.set_refund_policy(&state.db, route_id, &tiers).await?;
+
+    Ok(to_pb(route_id, tiers))
+}
+
+fn validate_tiers(tiers: &[pb::RefundTier]) -> Result<Vec<RefundTier>, AdminControllerError> {
+    let mut messages: Vec<String> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut report = |message: String| {
+        if !messages.contains(&message) {
+            messages.push(message);
+        }
+    };
+
+    for tier in tiers {
+        if tier.min_hours_before < 0 {
+            report("min_hours_before must not be negative".to_string());
+        }
+        if !(0..=100).contains(&tier.refund_percent) {
+            report("refund_percent must be between 0 and 100".to_string());
+        }
+        if !seen.insert(tier.min_hours_before) {
+            report(format!("min_hours_before {} is listed more than once", tier.min_hours_before));
+        }
+    }
+
+    if !messages.is_empty() {
+        return Err(AdminControllerError::Validation(messages));
+    }
+
+    Ok(tiers
+        .iter()
+        .map(|tier| RefundTier {
+            min_hours_before: tier.min_hours_before,
+            refund_percent: tier.refund_percent,
+        })
+        .collect())
+}
@@ -0,0 +1,138 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::errors::extract_validation_messages;
+use crate::pb;
+use crate::services::role::RoleDetails;
+
+use super::{AdminControllerError, AdminState};
+
+#[derive(Validate)]
+pub struct RoleValidation {
+    #[validate(length(min = 1, max = 64, message = "role name must be 1-64 characters"))]
+    pub name: String,
+}
+
+impl From<RoleDetails> for pb::Role {
+    fn from(details: RoleDetails) -> Self {
+        pb::Role {
+            role_id: details.role.role_id,
+            name: details.role.name,
+            policies: details.policies,
+            mfa_required: details.role.mfa_required,
+        }
+    }
+}
+
+// HTTP
+
+pub async fn list_roles(
+    State(state): State<Arc<AdminState>>,
+) -> Result<Json<pb::RoleList>, AdminControllerError> {
+    Ok(Json(do_list_roles(&state).await?))
+}
+
+pub async fn create_role(
+    State(state): State<Arc<AdminState>>,
+    Json(input): Json<pb::RoleInput>,
+) -> Result<Json<pb::Role>, AdminControllerError> {
+    validate_name(&input.name)?;
+
+    let role = state.role_service
+        .create_role(&state.db, &input.name, &input.policies, input.mfa_required.unwrap_or(false))
+        .await?;
+
+    Ok(Json(role.into()))
+}
+
+pub async fn update_role(
+    State(state): State<Arc<AdminState>>,
+    Path(role_id): Path<i32>,
+    Json(input): Json<pb::RoleInput>,
+) -> Result<Json<pb::Role>, AdminControllerError> {
+    validate_name(&input.name)?;
+
+    let role = state.role_service
+        .update_role(&state.db, role_id, &input.name, &input.policies, input.mfa_required)
+        .await?;
+
+    Ok(Json(role.into()))
+}
+
+pub async fn delete_role(
+    State(state): State<Arc<AdminState>>,
+    Path(role_id): Path<i32>,
+) -> Result<Json<pb::AdminResponse>, AdminControllerError> {
+    state.role_service.delete_role(&state.db, role_id).await?;
+
+    Ok(Json(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+// gRPC handlers
+
+pub async fn grpc_list_roles(
+    service: &AdminState,
+    _request: Request<pb::ListRolesRequest>,
+) -> Result<Response<pb::RoleList>, Status> {
+    Ok(Response::new(do_list_roles(service).await?))
+}
+
+pub async fn grpc_create_role(
+    service: &AdminState,
+    request: Request<pb::RoleInput>,
+) -> Result<Response<pb::Role>, Status> {
+    let input = request.into_inner();
+    validate_name(&input.name)?;
+
+    let role = service.role_service
+        .create_role(&service.db, &input.name, &input.policies, input.mfa_required.unwrap_or(false))
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(role.into()))
+}
+
+pub async fn grpc_update_role(
+    service: &AdminState,
+    request: Request<pb::UpdateRoleRequest>,
+) -> Result<Response<pb::Role>, Status> {
+    let input = request.into_inner();
+    validate_name(&input.name)?;
+
+    let role = service.role_service
+        .update_role(&service.db, input.role_id, &input.name, &input.policies, input.mfa_required)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(role.into()))
+}
+
+pub async fn grpc_delete_role(
+    service: &AdminState,
+    request: Request<pb::DeleteRoleRequest>,
+) -> Result<Response<pb::AdminResponse>, Status> {
+    service.role_service.delete_role(&service.db, request.into_inner().role_id)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+async fn do_list_roles(state: &AdminState) -> Result<pb::RoleList, AdminControllerError> {
+    let roles = state.role_service.list_roles(&state.db).await?;
+
+    Ok(pb::RoleList {
+        roles: roles.into_iter().map(pb::Role::from).collect(),
+    })
+}
+
+fn validate_name(name: &str) -> Result<(), AdminControllerError> {
+    RoleValidation { name: name.to_string() }
+        .validate()
+        .map_err(|e| AdminControllerError::Validation(extract_validation_messages(e)))
+}
@@ -0,0 +1,327 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::errors::extract_validation_messages;
+use crate::entities::route::Model as RouteModel;
+use crate::pb;
+use crate::services::route::{RouteInput, RouteStop, StopInput};
+
+use super::{AdminControllerError, AdminState};
+
+const MAX_STOPS: usize = 30;
+
+#[derive(Validate)]
+pub struct RouteValidation {
+    #[validate(length(min = 1, max = 128, message = "from_location must be 1-128 characters"))]
+    pub from_location: String,
+
+    #[validate(length(min = 1, max = 128, message = "to_location must be 1-128 characters"))]
+    pub to_location: String,
+
+    #[validate(range(min = 1, message = "base_price must be positive"))]
+    pub base_price: i32,
+}
+
+impl From<RouteModel> for pb::Route {
+    fn from(route: RouteModel) -> Self {
+        pb::Route {
+            route_id: route.route_id,
+            from_location: route.from_location,
+            to_location: route.to_location,
+            base_price: route.base_price,
+            deleted: route.deleted,
+            from_location_id: route.from_location_id,
+            to_location_id: route.to_location_id,
+        }
+    }
+}
+
+impl From<RouteStop> for pb::RouteStop {
+    fn from(stop: RouteStop) -> Self {
+        pb::RouteStop {
+            stop: stop.stop_order,
+            location: stop.location,
+            offset_minutes: stop.offset_minutes,
+            fare: stop.fare,
+            fare_from_origin: stop.fare_from_origin,
+        }
+    }
+}
+
+fn to_route_stops(route_id: i32, stops: Vec<RouteStop>) -> pb::RouteStops {
+    pb::RouteStops {
+        route_id,
+        stops: stops.into_iter().map(pb::RouteStop::from).collect(),
+    }
+}
+
+// HTTP
+
+pub async fn list_routes(
+    State(state): State<Arc<AdminState>>,
+    Query(input): Query<pb::ListRoutesRequest>,
+) -> Result<Json<pb::RouteList>, AdminControllerError> {
+    Ok(Json(do_list_routes(&state, input).await?))
+}
+
+pub async fn get_route(
+    State(state): State<Arc<AdminState>>,
+    Path(route_id): Path<i32>,
+) -> Result<Json<pb::Route>, AdminControllerError> {
+    Ok(Json(state.route_service.get_route(&state.db, route_id).await?.into()))
+}
+
+pub async fn create_route(
+    State(state): State<Arc<AdminState>>,
+    Json(input): Json<pb::RouteInput>,
+) -> Result<Json<pb::Route>, AdminControllerError> {
+    let input = validate_route(input.from_location, input.to_location, input.base_price)?;
+
+    Ok(Json(state.route_service.create_route(&state.db, input).await?.into()))
+}
+
+pub async fn update_route(
+    State(state): State<Arc<AdminState>>,
+    Path(route_id): Path<i32>,
+    Json(input): Json<pb::RouteInput>,
+) -> Result<Json<pb::Route>, AdminControllerError> {
+    let input = validate_route(input.from_location, input.to_location, input.base_price)?;
+
+    Ok(Json(state.route_service.update_route(&state.db, route_id, input).await?.into()))
+}
+
+pub async fn delete_route(
+    State(state): State<Arc<AdminState>>,
+    Path(route_id): Path<i32>,
+    Query(options): Query<pb::DeleteRouteOptions>,
+) -> Result<Json<pb::AdminResponse>, AdminControllerError> {
+    state.route_service
+        .delete_route(&state.db, route_id, options.force.unwrap_or(false))
+        .await?;
+
+    Ok(Json(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+pub async fn restore_route(
+    State(state): State<Arc<AdminState>>,
+    Path(route_id): Path<i32>,
+) -> Result<Json<pb::Route>, AdminControllerError> {
+    Ok(Json(state.route_service.restore_route(&state.db, route_id).await?.into()))
+}
+
+pub async fn get_route_stops(
+    State(state): State<Arc<AdminState>>,
+    Path(route_id): Path<i32>,
+) -> Result<Json<pb::RouteStops>, AdminControllerError> {
+    let stops = state.route_service.route_stops(&state.db, route_id).await?;
+
+    Ok(Json(to_route_stops(route_id, stops)))
+}
+
+pub async fn set_route_stops(
+    State(state): State<Arc<AdminState>>,
+    Path(route_id): Path<i32>,
+    Json(input): Json<pb::RouteStopsInput>,
+) -> Result<Json<pb::RouteStops>, AdminControllerError> {
+    let stops = validate_stops(input.stops)?;
+    let stops = state.route_service.set_route_stops(&state.db, route_id, stops).await?;
+
+    Ok(Json(to_route_stops(route_id, stops)))
+}
+
+// gRPC handlers
+
+pub async fn grpc_list_routes(
+    service: &AdminState,
+    request: Request<pb::ListRoutesRequest>,
+) -> Result<Response<pb::RouteList>, Status> {
+    Ok(Response::new(do_list_routes(service, request.into_inner()).await?))
+}
+
+pub async fn grpc_get_route(
+    service: &AdminState,
+    request: Request<pb::GetRouteRequest>,
+) -> Result<Response<pb::Route>, Status> {
+    let route = service.route_service
+        .get_route(&service.db, request.into_inner().route_id)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(route.into()))
+}
+
+pub async fn grpc_create_route(
+    service: &AdminState,
+    request: Request<pb::RouteInput>,
+) -> Result<Response<pb::Route>, Status> {
+    let input = request.into_inner();
+    let input = validate_route(input.from_location, input.to_location, input.base_price)?;
+
+    let route = service.route_service
+        .create_route(&service.db, input)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(route.into()))
+}
+
+pub async fn grpc_update_route(
+    service: &AdminState,
+    request: Request<pb::UpdateRouteRequest>,
+) -> Result<Response<pb::Route>, Status> {
+    let input = request.into_inner();
+    let route_id = input.route_id;
+    let input = validate_route(input.from_location, input.to_location, input.base_price)?;
+
+    let route = service.route_service
+        .update_route(&service.db, route_id, input)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(route.into()))
+}
+
+pub async fn grpc_delete_route(
+    service: &AdminState,
+    request: Request<pb::DeleteRouteRequest>,
+) -> Result<Response<pb::AdminResponse>, Status> {
+    let input = request.into_inner();
+
+    service.route_service
+        .delete_route(&service.db, input.route_id, input.force.unwrap_or(false))
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+pub async fn grpc_restore_route(
+    service: &AdminState,
+    request: Request<pb::RestoreRouteRequest>,
+) -> Result<Response<pb::Route>, Status> {
+    let route = service.route_service
+        .restore_route(&service.db, request.into_inner().route_id)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(route.into()))
+}
+
+pub async fn grpc_get_route_stops(
+    service: &AdminState,
+    request: Request<pb::GetRouteStopsRequest>,
+) -> Result<Response<pb::RouteStops>, Status> {
+    let route_id = request.into_inner().route_id;
+
+    let stops = service.route_service
+        .route_stops(&service.db, route_id)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(to_route_stops(route_id, stops)))
+}
+
+pub async fn grpc_set_route_stops(
+    service: &AdminState,
+    request: Request<pb::SetRouteStopsRequest>,
+) -> Result<Response<pb::RouteStops>, Status> {
+    let input = request.into_inner();
+    let stops = validate_stops(input.stops)?;
+
+    let stops = service.route_service
+        .set_route_stops(&service.db, input.route_id, stops)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(to_route_stops(input.route_id, stops)))
+}
+
+async fn do_list_routes(
+    state: &AdminState,
+    input: pb::ListRoutesRequest,
+) -> Result<pb::RouteList, AdminControllerError> {
+    let routes = state.route_service
+        .list_routes(&state.db, input.include_deleted.unwrap_or(false))
+        .await?;
+
+    Ok(pb::RouteList {
+        routes: routes.into_iter().map(pb::Route::from).collect(),
+    })
+}
+
+fn validate_route(
+    from_location: String,
+    to_location: String,
+    base_price: i32,
+) -> Result<RouteInput, AdminControllerError> {
+    let from_location = from_location.trim().to_string();
+    let to_location = to_location.trim().to_string();
+
+    let validation = RouteValidation { from_location, to_location, base_price };
+    let mut messages = match validation.validate() {
+        Ok(()) => Vec::new(),
+        Err(e) => extract_validation_messages(e),
+    };
+    if !validation.from_location.is_empty()
+        && validation.from_location.to_lowercase() == validation.to_location.to_lowercase()
+    {
+        messages.push("from_location and to_location must differ".to_string());
+    }
+    if !messages.is_empty() {
+        return Err(AdminControllerError::Validation(messages));
+    }
+
+    Ok(RouteInput {
+        from_location: validation.from_location,
+        to_location: validation.to_location,
+        base_price: validation.base_price,
+    })
+}
+
+/// Stops must be listed in travel order, so their offsets grow.
+fn validate_stops(stops: Vec<pb::StopInput>) -> Result<Vec<StopInput>, AdminControllerError> {
+    let mut messages: Vec<String> = Vec::new();
+    let mut report = |message: String| {
+        if !messages.contains(&message) {
+            messages.push(message);
+        }
+    };
+
+    if stops.len() > MAX_STOPS {
+        report(format!("a route has at most {MAX_STOPS} stops between its ends"));
+    }
+
+    let stops: Vec<StopInput> = stops
+        .into_iter()
+        .map(|stop| StopInput {
+            location: stop.location.trim().to_string(),
+            offset_minutes: stop.offset_minutes,
+            fare: stop.fare,
+        })
+        .collect();
+
+    let mut previous_offset = 0;
+    for stop in &stops {
+        if stop.location.is_empty() || stop.location.chars().count() > 128 {
+            report("stop location must be 1-128 characters".to_string());
+        }
+        if stop.offset_minutes <= previous_offset {
+            report("stop offsets must be positive and increase along the route".to_string());
+        }
+        if stop.fare < 1 {
+            report("stop fare must be positive".to_string());
+        }
+        previous_offset = stop.offset_minutes;
+    }
+
+    if !messages.is_empty() {
+        return Err(AdminControllerError::Validation(messages));
+    }
+
+    Ok(stops)
+}
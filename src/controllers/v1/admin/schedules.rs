@@ -0,0 +1,260 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{NaiveDate, NaiveTime};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::controllers::v1::booking::bookings::DATE_FORMAT;
+use crate::pb;
+use crate::services::schedule::{
+    GenerationConflict, GenerationReport, PlannedDeparture, ScheduleDetails, ScheduleInput,
+};
+
+use super::{AdminControllerError, AdminState};
+
+const DAY_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M";
+/// Bit `i` of `days_of_week` stands for `WEEKDAYS[i]`.
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+const MAX_PREVIEW_DAYS: u32 = 366;
+
+fn weekday_names(mask: i32) -> Vec<String> {
+    WEEKDAYS
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| mask & (1 << bit) != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+impl From<ScheduleDetails> for pb::Schedule {
+    fn from(details: ScheduleDetails) -> Self {
+        let schedule = details.schedule;
+
+        pb::Schedule {
+            schedule_id: schedule.schedule_id,
+            route_id: schedule.route_id,
+            bus_id: schedule.bus_id,
+            departure_time: schedule.departure_time.format(TIME_FORMAT).to_string(),
+            duration_minutes: schedule.duration_minutes,
+            days_of_week: weekday_names(schedule.days_of_week),
+            valid_from: schedule.valid_from.format(DAY_FORMAT).to_string(),
+            valid_until: schedule.valid_until.map(|date| date.format(DAY_FORMAT).to_string()),
+            exceptions: details
+                .exceptions
+                .iter()
+                .map(|date| date.format(DAY_FORMAT).to_string())
+                .collect(),
+            active: schedule.active,
+        }
+    }
+}
+
+impl From<PlannedDeparture> for pb::ScheduledDeparture {
+    fn from(departure: PlannedDeparture) -> Self {
+        pb::ScheduledDeparture {
+            start_date: departure.start_date.format(DATE_FORMAT).to_string(),
+            end_date: departure.end_date.format(DATE_FORMAT).to_string(),
+            conflicting_trip_ids: departure.conflicts,
+        }
+    }
+}
+
+impl From<GenerationConflict> for pb::GenerationConflict {
+    fn from(conflict: GenerationConflict) -> Self {
+        pb::GenerationConflict {
+            schedule_id: conflict.schedule_id,
+            start_date: conflict.start_date.format(DATE_FORMAT).to_string(),
+            trip_ids: conflict.trip_ids,
+        }
+    }
+}
+
+impl From<GenerationReport> for pb::GenerationReport {
+    fn from(report: GenerationReport) -> Self {
+        pb::GenerationReport {
+            created: report.created.into_iter().map(pb::ScheduledTrip::from).collect(),
+            conflicts: report.conflicts.into_iter().map(pb::GenerationConflict::from).collect(),
+        }
+    }
+}
+
+// HTTP
+
+pub async fn list_schedules(
+    State(state): State<Arc<AdminState>>,
+) -> Result<Json<pb::ScheduleList>, AdminControllerError> {
+    Ok(Json(do_list_schedules(&state).await?))
+}
+
+pub async fn create_schedule(
+    State(state): State<Arc<AdminState>>,
+    Json(input): Json<pb::ScheduleInput>,
+) -> Result<Json<pb::Schedule>, AdminControllerError> {
+    let input = validate_schedule(input)?;
+
+    Ok(Json(state.schedule_service.create_schedule(&state.db, input).await?.into()))
+}
+
+pub async fn deactivate_schedule(
+    State(state): State<Arc<AdminState>>,
+    Path(schedule_id): Path<i32>,
+) -> Result<Json<pb::AdminResponse>, AdminControllerError> {
+    state.schedule_service.deactivate_schedule(&state.db, schedule_id).await?;
+
+    Ok(Json(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+pub async fn preview_schedule(
+    State(state): State<Arc<AdminState>>,
+    Json(input): Json<pb::PreviewScheduleRequest>,
+) -> Result<Json<pb::SchedulePreview>, AdminControllerError> {
+    Ok(Json(do_preview_schedule(&state, input).await?))
+}
+
+pub async fn generate_trips(
+    State(state): State<Arc<AdminState>>,
+    Query(input): Query<pb::GenerateTripsRequest>,
+) -> Result<Json<pb::GenerationReport>, AdminControllerError> {
+    Ok(Json(state.schedule_service.generate(&state.db, input.schedule_id).await?.into()))
+}
+
+// gRPC handlers
+
+pub async fn grpc_list_schedules(
+    service: &AdminState,
+    _request: Request<pb::ListSchedulesRequest>,
+) -> Result<Response<pb::ScheduleList>, Status> {
+    Ok(Response::new(do_list_schedules(service).await?))
+}
+
+pub async fn grpc_create_schedule(
+    service: &AdminState,
+    request: Request<pb::ScheduleInput>,
+) -> Result<Response<pb::Schedule>, Status> {
+    let input = validate_schedule(request.into_inner())?;
+
+    let schedule = service.schedule_service
+        .create_schedule(&service.db, input)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(schedule.into()))
+}
+
+pub async fn grpc_deactivate_schedule(
+    service: &AdminState,
+    request: Request<pb::DeactivateScheduleRequest>,
+) -> Result<Response<pb::AdminResponse>, Status> {
+    service.schedule_service
+        .deactivate_schedule(&service.db, request.into_inner().schedule_id)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+pub async fn grpc_preview_schedule(
+    service: &AdminState,
+    request: Request<pb::PreviewScheduleRequest>,
+) -> Result<Response<pb::SchedulePreview>, Status> {
+    Ok(Response::new(do_preview_schedule(service, request.into_inner()).await?))
+}
+
+pub async fn grpc_generate_trips(
+    service: &AdminState,
+    request: Request<pb::GenerateTripsRequest>,
+) -> Result<Response<pb::GenerationReport>, Status> {
+    let report = service.schedule_service
+        .generate(&service.db, request.into_inner().schedule_id)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(report.into()))
+}
+
+async fn do_list_schedules(state: &AdminState) -> Result<pb::ScheduleList, AdminControllerError> {
+    let schedules = state.schedule_service.list_schedules(&state.db).await?;
+
+    Ok(pb::ScheduleList {
+        schedules: schedules.into_iter().map(pb::Schedule::from).collect(),
+    })
+}
+
+async fn do_preview_schedule(
+    state: &AdminState,
+    input: pb::PreviewScheduleRequest,
+) -> Result<pb::SchedulePreview, AdminControllerError> {
+    if matches!(input.days, Some(days) if days == 0 || days > MAX_PREVIEW_DAYS) {
+        return Err(AdminControllerError::Validation(vec![format!(
+            "days must be between 1 and {MAX_PREVIEW_DAYS}"
+        )]));
+    }
+    let schedule = validate_schedule(input.schedule)?;
+
+    let departures = state.schedule_service.preview(&state.db, &schedule, input.days).await?;
+
+    Ok(pb::SchedulePreview {
+        departures: departures.into_iter().map(pb::ScheduledDeparture::from).collect(),
+    })
+}
+
+fn validate_schedule(input: pb::ScheduleInput) -> Result<ScheduleInput, AdminControllerError> {
+    let mut messages: Vec<String> = Vec::new();
+    let mut report = |message: String| {
+        if !messages.contains(&message) {
+            messages.push(message);
+        }
+    };
+
+    let departure_time = NaiveTime::parse_from_str(input.departure_time.trim(), TIME_FORMAT)
+        .map_err(|_| report("departure_time must look like 08:30".to_string()))
+        .ok();
+
+    if !(1..=1440).contains(&input.duration_minutes) {
+        report("duration_minutes must be between 1 and 1440".to_string());
+    }
+
+    let mut days_of_week = 0;
+    for day in &input.days_of_week {
+        match WEEKDAYS.iter().position(|name| name.eq_ignore_ascii_case(day.trim())) {
+            Some(bit) => days_of_week |= 1 << bit,
+            None => report(format!("unknown day of week: {day}")),
+        }
+    }
+    if input.days_of_week.is_empty() {
+        report("days_of_week must name at least one day (mon..sun)".to_string());
+    }
+
+    let mut parse_date = |field: &str, value: &str| {
+        NaiveDate::parse_from_str(value.trim(), DAY_FORMAT)
+            .map_err(|_| report(format!("{field} must look like 2024-01-31")))
+            .ok()
+    };
+    let valid_from = parse_date("valid_from", &input.valid_from);
+    let valid_until = input.valid_until.as_deref().map(|value| parse_date("valid_until", value));
+    let exceptions: Vec<Option<NaiveDate>> =
+        input.exceptions.iter().map(|value| parse_date("exceptions", value)).collect();
+
+    if let (Some(valid_from), Some(Some(valid_until))) = (valid_from, valid_until)
+        && valid_until < valid_from
+    {
+        report("valid_until must not be before valid_from".to_string());
+    }
+
+    match (departure_time, valid_from) {
+        (Some(departure_time), Some(valid_from)) if messages.is_empty() => Ok(ScheduleInput {
+            route_id: input.route_id,
+            bus_id: input.bus_id,
+            departure_time,
+            duration_minutes: input.duration_minutes,
+            days_of_week,
+            valid_from,
+            valid_until: valid_until.flatten(),
+            exceptions: exceptions.into_iter().flatten().collect(),
+        }),
+        _ => Err(AdminControllerError::Validation(messages)),
+    }
+}
@@ -0,0 +1,47 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::entities::user::Model as UserModel;
+use crate::pb;
+
+use super::{AdminControllerError, AdminState};
+
+// HTTP
+
+pub async fn revoke_user_tokens(
+    State(state): State<Arc<AdminState>>,
+    Path(user_id): Path<i32>,
+) -> Result<Json<pb::AdminResponse>, AdminControllerError> {
+    do_revoke_user_tokens(&state, user_id).await?;
+
+    Ok(Json(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+// gRPC handler
+
+pub async fn grpc_revoke_user_tokens(
+    service: &AdminState,
+    request: Request<pb::RevokeUserTokensRequest>,
+) -> Result<Response<pb::AdminResponse>, Status> {
+    do_revoke_user_tokens(service, request.into_inner().user_id).await?;
+
+    Ok(Response::new(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+async fn do_revoke_user_tokens(state: &AdminState, user_id: i32) -> Result<(), AdminControllerError> {
+    let user = state.auth_service.find_user_by_id(&state.db, user_id).await?;
+
+    revoke_sessions(state, &user).await
+}
+
+/// Revokes every access and refresh token issued to the user so far.
+pub(super) async fn revoke_sessions(state: &AdminState, user: &UserModel) -> Result<(), AdminControllerError> {
+    state.revocation_service.revoke_subject(&state.db, &user.email).await?;
+    state.refresh_token_service.revoke_all_for_user(&state.db, user.user_id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use axum::{
+    extract::{Extension, Path, State},
+    Json,
+};
+use chrono::NaiveDateTime;
+use libs::jwt::Claims;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::controllers::v1::booking::bookings::DATE_FORMAT;
+use crate::entities::trip::Model as TripModel;
+use crate::pb;
+use crate::services::trip::TripSchedule;
+
+use super::{AdminControllerError, AdminState};
+
+impl From<TripModel> for pb::ScheduledTrip {
+    fn from(trip: TripModel) -> Self {
+        pb::ScheduledTrip {
+            trip_id: trip.trip_id,
+            route_id: trip.route_id,
+            bus_id: trip.bus_id,
+            start_date: trip.start_date.format(DATE_FORMAT).to_string(),
+            end_date: trip.end_date.format(DATE_FORMAT).to_string(),
+            cancelled: trip.cancelled,
+        }
+    }
+}
+
+// HTTP
+
+pub async fn create_trip(
+    State(state): State<Arc<AdminState>>,
+    Json(input): Json<pb::TripInput>,
+) -> Result<Json<pb::ScheduledTrip>, AdminControllerError> {
+    let schedule = validate_schedule(input.route_id, input.bus_id, &input.start_date, &input.end_date)?;
+
+    Ok(Json(state.trip_service.create_trip(&state.db, schedule).await?.into()))
+}
+
+pub async fn update_trip(
+    State(state): State<Arc<AdminState>>,
+    Path(trip_id): Path<i32>,
+    Json(input): Json<pb::TripInput>,
+) -> Result<Json<pb::ScheduledTrip>, AdminControllerError> {
+    let schedule = validate_schedule(input.route_id, input.bus_id, &input.start_date, &input.end_date)?;
+
+    Ok(Json(state.trip_service.update_trip(&state.db, trip_id, schedule).await?.into()))
+}
+
+pub async fn cancel_trip(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<Claims>,
+    Path(trip_id): Path<i32>,
+) -> Result<Json<pb::TripCancellation>, AdminControllerError> {
+    Ok(Json(do_cancel_trip(&state, &claims, trip_id).await?))
+}
+
+// gRPC handlers
+
+pub async fn grpc_create_trip(
+    service: &AdminState,
+    request: Request<pb::TripInput>,
+) -> Result<Response<pb::ScheduledTrip>, Status> {
+    let input = request.into_inner();
+    let schedule = validate_schedule(input.route_id, input.bus_id, &input.start_date, &input.end_date)?;
+
+    let trip = service.trip_service
+        .create_trip(&service.db, schedule)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(trip.into()))
+}
+
+pub async fn grpc_update_trip(
+    service: &AdminState,
+    request: Request<pb::UpdateTripRequest>,
+) -> Result<Response<pb::ScheduledTrip>, Status> {
+    let input = request.into_inner();
+    let schedule = validate_schedule(input.route_id, input.bus_id, &input.start_date, &input.end_date)?;
+
+    let trip = service.trip_service
+        .update_trip(&service.db, input.trip_id, schedule)
+        .await
+        .map_err(AdminControllerError::from)?;
+
+    Ok(Response::new(trip.into()))
+}
+
+pub async fn grpc_cancel_trip(
+    service: &AdminState,
+    request: Request<pb::CancelTripRequest>,
+) -> Result<Response<pb::TripCancellation>, Status> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AdminControllerError::MissingClaims)?;
+
+    Ok(Response::new(do_cancel_trip(service, &claims, request.into_inner().trip_id).await?))
+}
+
+async fn do_cancel_trip(
+    state: &AdminState,
+    claims: &Claims,
+    trip_id: i32,
+) -> Result<pb::TripCancellation, AdminControllerError> {
+    let actor = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+    let cancelled = state.trip_service.cancel_trip(&state.db, trip_id, actor.user_id).await?;
+
+    Ok(pb::TripCancellation {
+        trip: cancelled.trip.into(),
+        tickets_cancelled: cancelled.tickets_cancelled.try_into().unwrap_or(u32::MAX),
+    })
+}
+
+fn validate_schedule(
+    route_id: i32,
+    bus_id: i32,
+    start_date: &str,
+    end_date: &str,
+) -> Result<TripSchedule, AdminControllerError> {
+    let parse = |field: &str, value: &str| {
+        NaiveDateTime::parse_from_str(value.trim(), DATE_FORMAT)
+            .map_err(|_| format!("{field} must look like 2024-01-31T08:00:00"))
+    };
+
+    let mut messages = Vec::new();
+    let start_date = parse("start_date", start_date).map_err(|m| messages.push(m)).ok();
+    let end_date = parse("end_date", end_date).map_err(|m| messages.push(m)).ok();
+
+    if let (Some(start_date), Some(end_date)) = (start_date, end_date) {
+        if end_date <= start_date {
+            messages.push("end_date must be after start_date".to_string());
+        } else {
+            return Ok(TripSchedule { route_id, bus_id, start_date, end_date });
+        }
+    }
+
+    Err(AdminControllerError::Validation(messages))
+}
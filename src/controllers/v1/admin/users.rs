@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::pb;
+
+use super::tokens::revoke_sessions;
+use super::{AdminControllerError, AdminState};
+
+// HTTP
+
+pub async fn set_user_role(
+    State(state): State<Arc<AdminState>>,
+    Path(user_id): Path<i32>,
+    Json(input): Json<pb::UserRoleInput>,
+) -> Result<Json<pb::AdminResponse>, AdminControllerError> {
+    do_set_user_role(&state, user_id, input.role_id).await?;
+
+    Ok(Json(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+pub async fn unlock_user(
+    State(state): State<Arc<AdminState>>,
+    Path(user_id): Path<i32>,
+) -> Result<Json<pb::AdminResponse>, AdminControllerError> {
+    do_unlock_user(&state, user_id).await?;
+
+    Ok(Json(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+// gRPC handlers
+
+pub async fn grpc_set_user_role(
+    service: &AdminState,
+    request: Request<pb::SetUserRoleRequest>,
+) -> Result<Response<pb::AdminResponse>, Status> {
+    let input = request.into_inner();
+    do_set_user_role(service, input.user_id, input.role_id).await?;
+
+    Ok(Response::new(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+pub async fn grpc_unlock_user(
+    service: &AdminState,
+    request: Request<pb::UnlockUserRequest>,
+) -> Result<Response<pb::AdminResponse>, Status> {
+    do_unlock_user(service, request.into_inner().user_id).await?;
+
+    Ok(Response::new(pb::AdminResponse { status: "ok".to_string() }))
+}
+
+/// Tokens carry the old role's claims, so the user's sessions are revoked
+/// and the next login picks up the new role.
+async fn do_set_user_role(state: &AdminState, user_id: i32, role_id: i32) -> Result<(), AdminControllerError> {
+    let user = state.auth_service.find_user_by_id(&state.db, user_id).await?;
+
+    state.role_service.assign_role(&state.db, user.user_id, role_id).await?;
+    revoke_sessions(state, &user).await
+}
+
+/// Lifts the account lockout. A lockout of the client IP is left in place.
+async fn do_unlock_user(state: &AdminState, user_id: i32) -> Result<(), AdminControllerError> {
+    let user = state.auth_service.find_user_by_id(&state.db, user_id).await?;
+
+    Ok(state.login_throttle_service.unlock(&user.email).await?)
+}
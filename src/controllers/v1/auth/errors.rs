@@ -1,6 +1,6 @@
 use axum::{
     Json,
-    http::StatusCode,
+    http::{HeaderValue, StatusCode, header::RETRY_AFTER},
     response::IntoResponse,
 };
 use sea_orm::DbErr;
@@ -9,6 +9,13 @@ use thiserror::Error;
 use tonic::Status;
 
 use crate::services::auth::AuthError;
+use crate::services::login_throttle::LoginThrottleError;
+use crate::services::mfa::MfaError;
+use crate::services::password_reset::PasswordResetError;
+use crate::services::refresh_token::RefreshTokenError;
+use crate::services::registration::RegistrationError;
+use crate::services::revocation::RevocationError;
+use crate::services::role::RoleError;
 
 #[derive(Debug, Error)]
 pub enum AuthControllerError {
@@ -18,6 +25,27 @@ pub enum AuthControllerError {
     #[error("jwt service error: {0}")]
     JwtService(#[from] crate::services::jwt::JWTServiceError),
 
+    #[error("refresh token error: {0}")]
+    RefreshToken(#[from] RefreshTokenError),
+
+    #[error("revocation error: {0}")]
+    Revocation(#[from] RevocationError),
+
+    #[error("role error: {0}")]
+    Role(#[from] RoleError),
+
+    #[error("registration error: {0}")]
+    Registration(#[from] RegistrationError),
+
+    #[error("password reset error: {0}")]
+    PasswordReset(#[from] PasswordResetError),
+
+    #[error("login throttle error: {0}")]
+    LoginThrottle(#[from] LoginThrottleError),
+
+    #[error("mfa error: {0}")]
+    Mfa(#[from] MfaError),
+
     #[error("invalid cookie metadata")]
     InvalidCookie,
 
@@ -57,7 +85,56 @@ impl From<AuthControllerError> for Status {
         match err {
             AuthControllerError::Auth(e) => match e {
                 AuthError::InvalidCredentials => Status::unauthenticated("invalid credentials"),
+                AuthError::EmailNotVerified => Status::permission_denied("email not verified"),
+                AuthError::UserNotFound => Status::not_found("user not found"),
                 AuthError::Database(_) => Status::internal("database error"),
+                AuthError::Password(_) => Status::internal("password verification failed"),
+            },
+            AuthControllerError::RefreshToken(e) => match e {
+                RefreshTokenError::Database(_) => Status::internal("database error"),
+                RefreshTokenError::Invalid | RefreshTokenError::Expired | RefreshTokenError::Reused => {
+                    Status::unauthenticated("invalid refresh token")
+                }
+            },
+            AuthControllerError::Revocation(_) => Status::internal("failed to revoke token"),
+            AuthControllerError::Role(_) => Status::internal("failed to resolve user roles"),
+            AuthControllerError::Registration(e) => match e {
+                RegistrationError::InvalidToken | RegistrationError::Expired => {
+                    Status::invalid_argument("invalid or expired verification token")
+                }
+                RegistrationError::Mailer(_) => Status::internal("failed to send mail"),
+                RegistrationError::Database(_) => Status::internal("database error"),
+                RegistrationError::Password(_) => Status::internal("password hashing failed"),
+            },
+            AuthControllerError::PasswordReset(e) => match e {
+                PasswordResetError::InvalidToken | PasswordResetError::Expired => {
+                    Status::invalid_argument("invalid or expired reset token")
+                }
+                PasswordResetError::Mailer(_) => Status::internal("failed to send mail"),
+                PasswordResetError::Database(_) => Status::internal("database error"),
+                PasswordResetError::Password(_) => Status::internal("password hashing failed"),
+            },
+            AuthControllerError::LoginThrottle(e) => match e {
+                LoginThrottleError::Locked { retry_after } => {
+                    let mut status = Status::resource_exhausted(format!(
+                        "too many failed login attempts, retry after {retry_after} seconds"
+                    ));
+                    status.metadata_mut().insert("retry-after", retry_after.into());
+                    status
+                }
+                LoginThrottleError::Database(_) => Status::internal("database error"),
+            },
+            AuthControllerError::Mfa(e) => match e {
+                MfaError::InvalidChallenge | MfaError::InvalidCode => Status::unauthenticated(e.to_string()),
+                MfaError::NotEnrolled | MfaError::AlreadyEnabled | MfaError::Required => {
+                    Status::failed_precondition(e.to_string())
+                }
+                MfaError::Locked { retry_after } => {
+                    let mut status = Status::resource_exhausted(e.to_string());
+                    status.metadata_mut().insert("retry-after", retry_after.into());
+                    status
+                }
+                MfaError::Database(_) | MfaError::Throttle(_) => Status::internal("database error"),
             },
             AuthControllerError::JwtService(_) => Status::internal("failed to sign token"),
             AuthControllerError::InvalidCookie => Status::internal("invalid cookie metadata"),
@@ -69,16 +146,71 @@ impl From<AuthControllerError> for Status {
 // HTTP
 impl IntoResponse for AuthControllerError {
     fn into_response(self) -> axum::response::Response {
+        let retry_after = match &self {
+            AuthControllerError::LoginThrottle(LoginThrottleError::Locked { retry_after })
+            | AuthControllerError::Mfa(MfaError::Locked { retry_after }) => Some(*retry_after),
+            _ => None,
+        };
+
         let (status, messages) = match self {
             AuthControllerError::Auth(e) => match e {
                 AuthError::InvalidCredentials => (StatusCode::UNAUTHORIZED, vec!["invalid credentials".to_string()]),
+                AuthError::EmailNotVerified => (StatusCode::FORBIDDEN, vec!["email not verified".to_string()]),
+                AuthError::UserNotFound => (StatusCode::NOT_FOUND, vec!["user not found".to_string()]),
                 AuthError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+                AuthError::Password(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["password verification failed".to_string()]),
+            },
+            AuthControllerError::RefreshToken(e) => match e {
+                RefreshTokenError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+                RefreshTokenError::Invalid | RefreshTokenError::Expired | RefreshTokenError::Reused => {
+                    (StatusCode::UNAUTHORIZED, vec!["invalid refresh token".to_string()])
+                }
+            },
+            AuthControllerError::Revocation(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["failed to revoke token".to_string()]),
+            AuthControllerError::Role(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["failed to resolve user roles".to_string()]),
+            AuthControllerError::Registration(e) => match e {
+                RegistrationError::InvalidToken | RegistrationError::Expired => {
+                    (StatusCode::BAD_REQUEST, vec!["invalid or expired verification token".to_string()])
+                }
+                RegistrationError::Mailer(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["failed to send mail".to_string()]),
+                RegistrationError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+                RegistrationError::Password(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["password hashing failed".to_string()]),
+            },
+            AuthControllerError::PasswordReset(e) => match e {
+                PasswordResetError::InvalidToken | PasswordResetError::Expired => {
+                    (StatusCode::BAD_REQUEST, vec!["invalid or expired reset token".to_string()])
+                }
+                PasswordResetError::Mailer(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["failed to send mail".to_string()]),
+                PasswordResetError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+                PasswordResetError::Password(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["password hashing failed".to_string()]),
+            },
+            AuthControllerError::LoginThrottle(e) => match e {
+                LoginThrottleError::Locked { retry_after } => (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    vec![format!("too many failed login attempts, retry after {retry_after} seconds")],
+                ),
+                LoginThrottleError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            AuthControllerError::Mfa(e) => match e {
+                MfaError::InvalidChallenge | MfaError::InvalidCode => (StatusCode::UNAUTHORIZED, vec![e.to_string()]),
+                MfaError::NotEnrolled | MfaError::AlreadyEnabled | MfaError::Required => {
+                    (StatusCode::CONFLICT, vec![e.to_string()])
+                }
+                MfaError::Locked { .. } => (StatusCode::TOO_MANY_REQUESTS, vec![e.to_string()]),
+                MfaError::Database(_) | MfaError::Throttle(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()])
+                }
             },
             AuthControllerError::JwtService(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["failed to sign token".to_string()]),
             AuthControllerError::InvalidCookie => (StatusCode::INTERNAL_SERVER_ERROR, vec!["invalid cookie metadata".to_string()]),
             AuthControllerError::Validation(msgs) => (StatusCode::BAD_REQUEST, msgs),
         };
 
-        (status, Json(ErrorResponse { code: status.as_u16(), messages })).into_response()
+        let mut response = (status, Json(ErrorResponse { code: status.as_u16(), messages })).into_response();
+        if let Some(retry_after) = retry_after {
+            response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+
+        response
     }
 }
@@ -1,18 +1,22 @@
 use axum::{
-    extract::State,
+    extract::{ConnectInfo, State},
     http::{HeaderMap, HeaderValue, header::SET_COOKIE},
     response::IntoResponse,
     Json,
 };
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 use validator::Validate;
 
+use crate::controllers::client_ip;
 use crate::controllers::v1::auth::{AuthControllerError, AuthState};
+use crate::entities::user::Model as UserModel;
 use crate::pb;
-use crate::services::jwt::JWTService;
+use crate::services::auth::AuthError;
+use crate::services::mfa::MfaStep;
 
-use super::build_auth_cookie;
+use super::{GRPC_REFRESH_COOKIE_PATH, HTTP_REFRESH_COOKIE_PATH, build_auth_cookie, build_refresh_cookie};
 
 #[derive(Validate)]
 pub struct LoginInput {
@@ -23,54 +27,54 @@ pub struct LoginInput {
     pub password: String,
 }
 
+pub struct LoginTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+/// The result of a correct password: tokens, or a challenge for the second
+/// factor that `VerifyMfa` turns into tokens.
+enum LoginOutcome {
+    Tokens(LoginTokens),
+    Mfa { status: &'static str, mfa_token: String },
+}
+
 // HTTP
 
 pub async fn login_jwt(
     State(state): State<Arc<AuthState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(input): Json<pb::LoginRequest>,
 ) -> Result<Json<pb::LoginJwtResponse>, AuthControllerError> {
-    let login_input = LoginInput {
-        username: input.username.clone(),
-        password: input.password.clone(),
-    };
-    login_input.validate().map_err(|e| AuthControllerError::Validation(super::errors::extract_validation_messages(e)))?;
-
-    let user = state.auth_service.find_user_by_email_password(&state.db, &input.username, &input.password)
-        .await
-        .map_err(AuthControllerError::from)?;
-
-    let token = do_login(&state.jwt_service, &state.config, &user.email)
-        .await?;
+    let ip = client_ip::from_http(peer, &headers, &state.config.trusted_proxies);
+    let user = authenticate(&state, &input, ip).await?;
 
-    Ok(Json(pb::LoginJwtResponse {
-        status: "ok".to_string(),
-        token,
-    }))
+    Ok(Json(jwt_response(begin_login(&state, &user, false).await?)))
 }
 
 pub async fn login_cookie(
     State(state): State<Arc<AuthState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(input): Json<pb::LoginRequest>,
 ) -> Result<impl IntoResponse, AuthControllerError> {
-    let login_input = LoginInput {
-        username: input.username.clone(),
-        password: input.password.clone(),
+    let ip = client_ip::from_http(peer, &headers, &state.config.trusted_proxies);
+    let user = authenticate(&state, &input, ip).await?;
+
+    let tokens = match begin_login(&state, &user, true).await? {
+        LoginOutcome::Tokens(tokens) => tokens,
+        LoginOutcome::Mfa { status, mfa_token } => {
+            return Ok((
+                HeaderMap::new(),
+                Json(pb::LoginResponse { status: status.to_string(), mfa_token: Some(mfa_token) }),
+            ));
+        }
     };
-    login_input.validate().map_err(|e| AuthControllerError::Validation(super::errors::extract_validation_messages(e)))?;
 
-    let user = state.auth_service.find_user_by_email_password(&state.db, &input.username, &input.password)
-        .await
-        .map_err(AuthControllerError::from)?;
+    let headers = http_login_cookies(&state, &tokens)?;
 
-    let token = do_login(&state.jwt_service, &state.config, &user.email)
-        .await?;
-
-    let mut headers = HeaderMap::new();
-    let cookie = format!("auth_token={token}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}", state.config.jwt_ttl);
-    let value = HeaderValue::from_str(&cookie).map_err(|_| AuthControllerError::InvalidCookie)?;
-    headers.insert(SET_COOKIE, value);
-
-    Ok((headers, Json(pb::LoginResponse { status: "ok".to_string() })))
+    Ok((headers, Json(pb::LoginResponse { status: "ok".to_string(), mfa_token: None })))
 }
 
 // gRPC handlers
@@ -79,61 +83,153 @@ pub async fn grpc_login_jwt(
     service: &AuthState,
     request: Request<pb::LoginRequest>,
 ) -> Result<Response<pb::LoginJwtResponse>, Status> {
+    let ip = client_ip::from_grpc(&request, &service.config.trusted_proxies);
     let input = request.into_inner();
-    let login_input = LoginInput {
-        username: input.username.clone(),
-        password: input.password.clone(),
-    };
-    login_input.validate().map_err(|e| AuthControllerError::Validation(super::errors::extract_validation_messages(e)))?;
+    let user = authenticate(service, &input, ip).await?;
 
-    let user = service.auth_service.find_user_by_email_password(&service.db, &input.username, &input.password)
-        .await
-        .map_err(AuthControllerError::from)?;
-
-    let token = do_login(&service.jwt_service, &service.config, &user.email)
-        .await?;
-
-    Ok(Response::new(pb::LoginJwtResponse {
-        status: "ok".to_string(),
-        token,
-    }))
+    Ok(Response::new(jwt_response(begin_login(service, &user, false).await?)))
 }
 
 pub async fn grpc_login_cookie(
     service: &AuthState,
     request: Request<pb::LoginRequest>,
 ) -> Result<Response<pb::LoginResponse>, Status> {
+    let ip = client_ip::from_grpc(&request, &service.config.trusted_proxies);
     let input = request.into_inner();
+    let user = authenticate(service, &input, ip).await?;
+
+    let tokens = match begin_login(service, &user, true).await? {
+        LoginOutcome::Tokens(tokens) => tokens,
+        LoginOutcome::Mfa { status, mfa_token } => {
+            return Ok(Response::new(pb::LoginResponse {
+                status: status.to_string(),
+                mfa_token: Some(mfa_token),
+            }));
+        }
+    };
+
+    let mut response = Response::new(pb::LoginResponse {
+        status: "ok".to_string(),
+        mfa_token: None,
+    });
+    grpc_login_cookies(service, &tokens, &mut response)?;
+
+    Ok(response)
+}
+
+/// Validates the input and checks the password, refusing while the account
+/// or client IP is locked out. Only a wrong password counts as a failure; an
+/// unverified email still proves the password and resets the account counter.
+async fn authenticate(
+    state: &AuthState,
+    input: &pb::LoginRequest,
+    ip: Option<IpAddr>,
+) -> Result<UserModel, AuthControllerError> {
     let login_input = LoginInput {
         username: input.username.clone(),
         password: input.password.clone(),
     };
     login_input.validate().map_err(|e| AuthControllerError::Validation(super::errors::extract_validation_messages(e)))?;
 
-    let user = service.auth_service.find_user_by_email_password(&service.db, &input.username, &input.password)
-        .await
-        .map_err(AuthControllerError::from)?;
+    state.login_throttle_service.check(&input.username, ip).await?;
+
+    match state.auth_service.find_user_by_email_password(&state.db, &input.username, &input.password).await {
+        Err(AuthError::InvalidCredentials) => {
+            state.login_throttle_service.record_failure(&input.username, ip).await?;
+            Err(AuthError::InvalidCredentials.into())
+        }
+        Err(AuthError::EmailNotVerified) => {
+            state.login_throttle_service.record_success(&input.username).await?;
+            Err(AuthError::EmailNotVerified.into())
+        }
+        Err(err) => Err(err.into()),
+        Ok(user) => {
+            state.login_throttle_service.record_success(&input.username).await?;
+            Ok(user)
+        }
+    }
+}
 
-    let token = do_login(&service.jwt_service, &service.config, &user.email)
-        .await?;
+/// Signs an access token carrying the roles and policies of the user's role.
+pub async fn sign_access_token(state: &AuthState, user: &UserModel) -> Result<String, AuthControllerError> {
+    let access = state.role_service.resolve(&state.db, user.role_id).await?;
 
-    let cookie = build_auth_cookie(&token, service.config.jwt_ttl);
-    let cookie_meta = tonic::metadata::MetadataValue::try_from(cookie.as_str())
-        .map_err(|_| AuthControllerError::InvalidCookie)?;
+    Ok(state.jwt_service.sign_token(
+        user.email.clone(),
+        state.config.jwt_ttl,
+        access.roles,
+        access.policies,
+    ).await?)
+}
 
-    let mut response = Response::new(pb::LoginResponse {
-        status: "ok".to_string(),
-    });
-    response.metadata_mut().insert("set-cookie", cookie_meta);
+/// Issues tokens right away unless the account has, or must enroll, a
+/// second factor. `cookie` is remembered on the challenge so `VerifyMfa`
+/// finishes the login the way it started.
+async fn begin_login(state: &AuthState, user: &UserModel, cookie: bool) -> Result<LoginOutcome, AuthControllerError> {
+    let status = match state.mfa_service.login_step(&state.db, user).await? {
+        None => return Ok(LoginOutcome::Tokens(do_login(state, user).await?)),
+        Some(MfaStep::Verify) => "mfa_required",
+        Some(MfaStep::Enroll) => "mfa_enrollment_required",
+    };
 
-    Ok(response)
+    let mfa_token = state.mfa_service.start_challenge(&state.db, user.user_id, cookie).await?;
+
+    Ok(LoginOutcome::Mfa { status, mfa_token })
 }
 
-async fn do_login(jwt_service: &Arc<dyn JWTService>, config: &crate::config::Config, email: &str) -> Result<String, AuthControllerError> {
-    Ok(jwt_service.sign_token(
-        email.to_string(),
-        config.jwt_ttl,
-        vec!["user".to_string()],
-        vec!["protected.read".to_string()],
-    ).await?)
+fn jwt_response(outcome: LoginOutcome) -> pb::LoginJwtResponse {
+    match outcome {
+        LoginOutcome::Tokens(tokens) => pb::LoginJwtResponse {
+            status: "ok".to_string(),
+            token: Some(tokens.access_token),
+            refresh_token: Some(tokens.refresh_token),
+            mfa_token: None,
+        },
+        LoginOutcome::Mfa { status, mfa_token } => pb::LoginJwtResponse {
+            status: status.to_string(),
+            token: None,
+            refresh_token: None,
+            mfa_token: Some(mfa_token),
+        },
+    }
+}
+
+pub fn http_login_cookies(state: &AuthState, tokens: &LoginTokens) -> Result<HeaderMap, AuthControllerError> {
+    let mut headers = HeaderMap::new();
+    for cookie in [
+        build_auth_cookie(&tokens.access_token, state.config.jwt_ttl),
+        build_refresh_cookie(&tokens.refresh_token, state.config.refresh_token_ttl, HTTP_REFRESH_COOKIE_PATH),
+    ] {
+        let value = HeaderValue::from_str(&cookie).map_err(|_| AuthControllerError::InvalidCookie)?;
+        headers.append(SET_COOKIE, value);
+    }
+
+    Ok(headers)
+}
+
+pub fn grpc_login_cookies<T>(
+    state: &AuthState,
+    tokens: &LoginTokens,
+    response: &mut Response<T>,
+) -> Result<(), AuthControllerError> {
+    for cookie in [
+        build_auth_cookie(&tokens.access_token, state.config.jwt_ttl),
+        build_refresh_cookie(&tokens.refresh_token, state.config.refresh_token_ttl, GRPC_REFRESH_COOKIE_PATH),
+    ] {
+        let value = tonic::metadata::MetadataValue::try_from(cookie.as_str())
+            .map_err(|_| AuthControllerError::InvalidCookie)?;
+        response.metadata_mut().append("set-cookie", value);
+    }
+
+    Ok(())
+}
+
+pub async fn do_login(state: &AuthState, user: &UserModel) -> Result<LoginTokens, AuthControllerError> {
+    let access_token = sign_access_token(state, user).await?;
+    let refresh_token = state.refresh_token_service.issue(&state.db, user.user_id).await?;
+
+    Ok(LoginTokens {
+        access_token,
+        refresh_token,
+    })
 }
@@ -1,35 +1,83 @@
 use axum::{
+    extract::State,
     http::{HeaderMap, HeaderValue, header::SET_COOKIE},
     response::IntoResponse,
     Json,
 };
+use libs::axum::middlewares::jwt_authorize::extract_token;
+use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
 use crate::pb;
 
-use super::{AuthControllerError, clear_auth_cookie, AuthState};
+use super::{
+    AuthControllerError, AuthState, GRPC_REFRESH_COOKIE_PATH, HTTP_REFRESH_COOKIE_PATH, clear_auth_cookie,
+    clear_refresh_cookie,
+};
+
+pub async fn logout(
+    State(state): State<Arc<AuthState>>,
+    request_headers: HeaderMap,
+    input: Option<Json<pb::LogoutRequest>>,
+) -> Result<impl IntoResponse, AuthControllerError> {
+    let refresh_token = input.and_then(|Json(input)| input.refresh_token);
+    revoke_session(&state, extract_token(&request_headers), refresh_token).await?;
 
-pub async fn logout() -> Result<impl IntoResponse, AuthControllerError> {
     let mut headers = HeaderMap::new();
     let value = HeaderValue::from_str("auth_token=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0")
         .map_err(|_| AuthControllerError::InvalidCookie)?;
     headers.insert(SET_COOKIE, value);
 
+    let value = HeaderValue::from_str(&clear_refresh_cookie(HTTP_REFRESH_COOKIE_PATH))
+        .map_err(|_| AuthControllerError::InvalidCookie)?;
+    headers.append(SET_COOKIE, value);
+
     Ok((headers, Json(pb::LogoutResponse { status: "ok".to_string() })))
 }
 
 // gRPC handler
 pub async fn grpc_logout(
-    _service: &AuthState,
-    _request: Request<pb::LogoutRequest>,
+    service: &AuthState,
+    request: Request<pb::LogoutRequest>,
 ) -> Result<Response<pb::LogoutResponse>, Status> {
+    let access_token = libs::tonic::middlewares::jwt_authorize::extract_token(request.metadata());
+    revoke_session(service, access_token, request.into_inner().refresh_token).await?;
+
     let cookie_meta = tonic::metadata::MetadataValue::try_from(clear_auth_cookie().as_str())
         .map_err(|_| AuthControllerError::InvalidCookie)?;
+    let refresh_cookie_meta = tonic::metadata::MetadataValue::try_from(clear_refresh_cookie(GRPC_REFRESH_COOKIE_PATH).as_str())
+        .map_err(|_| AuthControllerError::InvalidCookie)?;
 
     let mut response = Response::new(pb::LogoutResponse {
         status: "ok".to_string(),
     });
     response.metadata_mut().insert("set-cookie", cookie_meta);
+    response.metadata_mut().append("set-cookie", refresh_cookie_meta);
 
     Ok(response)
 }
+
+/// Revokes the presented access token and refresh token family. Tokens that
+/// are missing or already invalid are ignored so logout always succeeds.
+async fn revoke_session(
+    state: &AuthState,
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+) -> Result<(), AuthControllerError> {
+    if let Some(token) = access_token
+        && let Ok(claims) = state.jwt_service.verify_token(&token).await
+    {
+        // Tokens issued before `jti` existed cannot be revoked one by one.
+        if claims.jti.is_empty() {
+            state.revocation_service.revoke_subject(&state.db, &claims.sub).await?;
+        } else {
+            state.revocation_service.revoke_token(&state.db, &claims).await?;
+        }
+    }
+
+    if let Some(token) = refresh_token {
+        state.refresh_token_service.revoke_family(&state.db, &token).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::controllers::v1::auth::{AuthControllerError, AuthState};
+use crate::pb;
+use crate::services::mfa::{TotpEnrollment, VerifiedChallenge};
+
+use super::login::{LoginTokens, do_login, grpc_login_cookies, http_login_cookies};
+
+impl From<TotpEnrollment> for pb::TotpEnrollment {
+    fn from(enrollment: TotpEnrollment) -> Self {
+        pb::TotpEnrollment {
+            secret: enrollment.secret,
+            otpauth_uri: enrollment.otpauth_uri,
+        }
+    }
+}
+
+// HTTP
+
+pub async fn verify_mfa(
+    State(state): State<Arc<AuthState>>,
+    Json(input): Json<pb::VerifyMfaRequest>,
+) -> Result<impl IntoResponse, AuthControllerError> {
+    let (verified, tokens) = do_verify_mfa(&state, &input).await?;
+
+    if verified.cookie {
+        let headers = http_login_cookies(&state, &tokens)?;
+        return Ok((headers, Json(cookie_response(verified))));
+    }
+
+    Ok((HeaderMap::new(), Json(token_response(verified, tokens))))
+}
+
+pub async fn enroll_mfa(
+    State(state): State<Arc<AuthState>>,
+    Json(input): Json<pb::EnrollMfaRequest>,
+) -> Result<Json<pb::TotpEnrollment>, AuthControllerError> {
+    let enrollment = state.mfa_service.enroll_challenge(&state.db, &input.mfa_token).await?;
+
+    Ok(Json(enrollment.into()))
+}
+
+// gRPC handlers
+
+pub async fn grpc_verify_mfa(
+    service: &AuthState,
+    request: Request<pb::VerifyMfaRequest>,
+) -> Result<Response<pb::VerifyMfaResponse>, Status> {
+    let (verified, tokens) = do_verify_mfa(service, request.get_ref()).await?;
+
+    if verified.cookie {
+        let mut response = Response::new(cookie_response(verified));
+        grpc_login_cookies(service, &tokens, &mut response)?;
+        return Ok(response);
+    }
+
+    Ok(Response::new(token_response(verified, tokens)))
+}
+
+pub async fn grpc_enroll_mfa(
+    service: &AuthState,
+    request: Request<pb::EnrollMfaRequest>,
+) -> Result<Response<pb::TotpEnrollment>, Status> {
+    let enrollment = service.mfa_service
+        .enroll_challenge(&service.db, &request.get_ref().mfa_token)
+        .await
+        .map_err(AuthControllerError::from)?;
+
+    Ok(Response::new(enrollment.into()))
+}
+
+async fn do_verify_mfa(
+    state: &AuthState,
+    input: &pb::VerifyMfaRequest,
+) -> Result<(VerifiedChallenge, LoginTokens), AuthControllerError> {
+    let verified = state.mfa_service.verify_challenge(&state.db, &input.mfa_token, &input.code).await?;
+    let tokens = do_login(state, &verified.user).await?;
+
+    Ok((verified, tokens))
+}
+
+fn token_response(verified: VerifiedChallenge, tokens: LoginTokens) -> pb::VerifyMfaResponse {
+    pb::VerifyMfaResponse {
+        status: "ok".to_string(),
+        token: Some(tokens.access_token),
+        refresh_token: Some(tokens.refresh_token),
+        recovery_codes: verified.recovery_codes,
+    }
+}
+
+fn cookie_response(verified: VerifiedChallenge) -> pb::VerifyMfaResponse {
+    pb::VerifyMfaResponse {
+        status: "ok".to_string(),
+        token: None,
+        refresh_token: None,
+        recovery_codes: verified.recovery_codes,
+    }
+}
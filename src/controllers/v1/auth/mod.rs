@@ -6,36 +6,65 @@ use tonic::{Request, Response, Status};
 pub mod errors;
 pub mod login;
 pub mod logout;
+pub mod mfa;
+pub mod password;
+pub mod refresh;
+pub mod register;
 
 use crate::config::Config;
+use crate::inject::{InjectError, InjectFactory};
 use crate::pb;
 use crate::services::auth::Auth;
 use crate::services::jwt::JWTService;
+use crate::services::login_throttle::LoginThrottleService;
+use crate::services::mfa::MfaService;
+use crate::services::password_reset::PasswordResetService;
+use crate::services::refresh_token::RefreshTokenService;
+use crate::services::registration::RegistrationService;
+use crate::services::revocation::RevocationService;
+use crate::services::role::RoleService;
 
 pub use errors::AuthControllerError;
 pub use login::{login_cookie, login_jwt};
 pub use logout::logout;
+pub use mfa::{enroll_mfa, verify_mfa};
+pub use password::{forgot_password, reset_password};
+pub use refresh::refresh;
+pub use register::{register, verify_email};
+
+/// Refresh cookies are only sent to the endpoint that consumes them.
+pub const HTTP_REFRESH_COOKIE_PATH: &str = "/api/v1/auth/refresh";
+pub const GRPC_REFRESH_COOKIE_PATH: &str = "/api.AuthService/Refresh";
 
 pub struct AuthState {
     pub config: Arc<Config>,
     pub jwt_service: Arc<dyn JWTService>,
     pub auth_service: Arc<dyn Auth>,
+    pub refresh_token_service: Arc<dyn RefreshTokenService>,
+    pub revocation_service: Arc<dyn RevocationService>,
+    pub role_service: Arc<dyn RoleService>,
+    pub registration_service: Arc<dyn RegistrationService>,
+    pub password_reset_service: Arc<dyn PasswordResetService>,
+    pub login_throttle_service: Arc<dyn LoginThrottleService>,
+    pub mfa_service: Arc<dyn MfaService>,
     pub db: Arc<DatabaseConnection>,
 }
 
 impl AuthState {
-    pub fn new(
-        config: Arc<Config>,
-        jwt_service: Arc<dyn JWTService>,
-        auth_service: Arc<dyn Auth>,
-        db: Arc<DatabaseConnection>,
-    ) -> Self {
-        Self {
-            config,
-            jwt_service,
-            auth_service,
-            db,
-        }
+    pub fn new(injector: &dyn InjectFactory) -> Result<Self, InjectError> {
+        Ok(Self {
+            config: injector.config()?,
+            jwt_service: injector.jwt_service()?,
+            auth_service: injector.auth_service()?,
+            refresh_token_service: injector.refresh_token_service()?,
+            revocation_service: injector.revocation_service()?,
+            role_service: injector.role_service()?,
+            registration_service: injector.registration_service()?,
+            password_reset_service: injector.password_reset_service()?,
+            login_throttle_service: injector.login_throttle_service()?,
+            mfa_service: injector.mfa_service()?,
+            db: injector.database()?,
+        })
     }
 }
 
@@ -55,12 +84,61 @@ impl pb::auth_service_server::AuthService for AuthState {
         login::grpc_login_cookie(self, request).await
     }
 
+    async fn refresh(
+        &self,
+        request: Request<pb::RefreshRequest>,
+    ) -> Result<Response<pb::RefreshResponse>, Status> {
+        refresh::grpc_refresh(self, request).await
+    }
+
     async fn logout(
         &self,
         request: Request<pb::LogoutRequest>,
     ) -> Result<Response<pb::LogoutResponse>, Status> {
         logout::grpc_logout(self, request).await
     }
+
+    async fn register(
+        &self,
+        request: Request<pb::RegisterRequest>,
+    ) -> Result<Response<pb::RegisterResponse>, Status> {
+        register::grpc_register(self, request).await
+    }
+
+    async fn verify_email(
+        &self,
+        request: Request<pb::VerifyEmailRequest>,
+    ) -> Result<Response<pb::VerifyEmailResponse>, Status> {
+        register::grpc_verify_email(self, request).await
+    }
+
+    async fn forgot_password(
+        &self,
+        request: Request<pb::ForgotPasswordRequest>,
+    ) -> Result<Response<pb::ForgotPasswordResponse>, Status> {
+        password::grpc_forgot_password(self, request).await
+    }
+
+    async fn reset_password(
+        &self,
+        request: Request<pb::ResetPasswordRequest>,
+    ) -> Result<Response<pb::ResetPasswordResponse>, Status> {
+        password::grpc_reset_password(self, request).await
+    }
+
+    async fn verify_mfa(
+        &self,
+        request: Request<pb::VerifyMfaRequest>,
+    ) -> Result<Response<pb::VerifyMfaResponse>, Status> {
+        mfa::grpc_verify_mfa(self, request).await
+    }
+
+    async fn enroll_mfa(
+        &self,
+        request: Request<pb::EnrollMfaRequest>,
+    ) -> Result<Response<pb::TotpEnrollment>, Status> {
+        mfa::grpc_enroll_mfa(self, request).await
+    }
 }
 
 pub fn build_auth_cookie(token: &str, ttl: u64) -> String {
@@ -70,3 +148,20 @@ pub fn build_auth_cookie(token: &str, ttl: u64) -> String {
 pub fn clear_auth_cookie() -> String {
     "auth_token=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0".to_string()
 }
+
+pub fn build_refresh_cookie(token: &str, ttl: u64, path: &str) -> String {
+    format!("refresh_token={token}; Path={path}; HttpOnly; SameSite=Strict; Max-Age={ttl}")
+}
+
+pub fn clear_refresh_cookie(path: &str) -> String {
+    format!("refresh_token=; Path={path}; HttpOnly; SameSite=Strict; Max-Age=0")
+}
+
+pub fn read_cookie(cookie_header: &str, name: &str) -> Option<String> {
+    cookie_header.split(';').find_map(|part| {
+        part.trim()
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('='))
+            .map(str::to_string)
+    })
+}
@@ -0,0 +1,77 @@
+use axum::{extract::State, Json};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::{AuthControllerError, AuthState};
+use crate::pb;
+
+#[derive(Validate)]
+pub struct NewPasswordInput {
+    #[validate(length(min = 8, max = 128, message = "password must be 8-128 characters"))]
+    pub password: String,
+}
+
+impl NewPasswordInput {
+    pub fn check(password: &str) -> Result<(), Vec<String>> {
+        Self { password: password.to_string() }
+            .validate()
+            .map_err(super::errors::extract_validation_messages)
+    }
+}
+
+// HTTP
+
+pub async fn forgot_password(
+    State(state): State<Arc<AuthState>>,
+    Json(input): Json<pb::ForgotPasswordRequest>,
+) -> Result<Json<pb::ForgotPasswordResponse>, AuthControllerError> {
+    state.password_reset_service.request_reset(&state.db, &input.email).await?;
+
+    Ok(Json(pb::ForgotPasswordResponse { status: "ok".to_string() }))
+}
+
+pub async fn reset_password(
+    State(state): State<Arc<AuthState>>,
+    Json(input): Json<pb::ResetPasswordRequest>,
+) -> Result<Json<pb::ResetPasswordResponse>, AuthControllerError> {
+    do_reset_password(&state, &input.token, &input.new_password).await?;
+
+    Ok(Json(pb::ResetPasswordResponse { status: "ok".to_string() }))
+}
+
+// gRPC handlers
+
+pub async fn grpc_forgot_password(
+    service: &AuthState,
+    request: Request<pb::ForgotPasswordRequest>,
+) -> Result<Response<pb::ForgotPasswordResponse>, Status> {
+    service.password_reset_service.request_reset(&service.db, &request.into_inner().email)
+        .await
+        .map_err(AuthControllerError::from)?;
+
+    Ok(Response::new(pb::ForgotPasswordResponse { status: "ok".to_string() }))
+}
+
+pub async fn grpc_reset_password(
+    service: &AuthState,
+    request: Request<pb::ResetPasswordRequest>,
+) -> Result<Response<pb::ResetPasswordResponse>, Status> {
+    let input = request.into_inner();
+    do_reset_password(service, &input.token, &input.new_password).await?;
+
+    Ok(Response::new(pb::ResetPasswordResponse { status: "ok".to_string() }))
+}
+
+/// A completed reset signs the account out everywhere, since whoever held
+/// the old password may still have live sessions.
+async fn do_reset_password(state: &AuthState, token: &str, new_password: &str) -> Result<(), AuthControllerError> {
+    NewPasswordInput::check(new_password).map_err(AuthControllerError::Validation)?;
+
+    let user = state.password_reset_service.reset_password(&state.db, token, new_password).await?;
+
+    state.revocation_service.revoke_subject(&state.db, &user.email).await?;
+    state.refresh_token_service.revoke_all_for_user(&state.db, user.user_id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, HeaderValue, header::{COOKIE, SET_COOKIE}},
+    response::{IntoResponse, Response as HttpResponse},
+    Json,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::controllers::v1::auth::{AuthControllerError, AuthState};
+use crate::pb;
+use crate::services::refresh_token::RefreshTokenError;
+
+use super::login::{LoginTokens, sign_access_token};
+use super::{
+    GRPC_REFRESH_COOKIE_PATH, HTTP_REFRESH_COOKIE_PATH, build_auth_cookie, build_refresh_cookie, read_cookie,
+};
+
+// HTTP
+
+pub async fn refresh(
+    State(state): State<Arc<AuthState>>,
+    headers: HeaderMap,
+    input: Option<Json<pb::RefreshRequest>>,
+) -> Result<HttpResponse, AuthControllerError> {
+    if let Some(token) = input.and_then(|Json(input)| input.refresh_token) {
+        let tokens = do_refresh(&state, &token).await?;
+
+        return Ok(Json(pb::RefreshResponse {
+            status: "ok".to_string(),
+            token: Some(tokens.access_token),
+            refresh_token: Some(tokens.refresh_token),
+        })
+        .into_response());
+    }
+
+    let token = headers
+        .get(COOKIE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|cookies| read_cookie(cookies, "refresh_token"))
+        .ok_or(RefreshTokenError::Invalid)?;
+
+    let tokens = do_refresh(&state, &token).await?;
+
+    let mut headers = HeaderMap::new();
+    for cookie in [
+        build_auth_cookie(&tokens.access_token, state.config.jwt_ttl),
+        build_refresh_cookie(&tokens.refresh_token, state.config.refresh_token_ttl, HTTP_REFRESH_COOKIE_PATH),
+    ] {
+        let value = HeaderValue::from_str(&cookie).map_err(|_| AuthControllerError::InvalidCookie)?;
+        headers.append(SET_COOKIE, value);
+    }
+
+    Ok((
+        headers,
+        Json(pb::RefreshResponse {
+            status: "ok".to_string(),
+            token: None,
+            refresh_token: None,
+        }),
+    )
+        .into_response())
+}
+
+// gRPC handler
+
+pub async fn grpc_refresh(
+    service: &AuthState,
+    request: Request<pb::RefreshRequest>,
+) -> Result<Response<pb::RefreshResponse>, Status> {
+    let cookie_token = request
+        .metadata()
+        .get("cookie")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|cookies| read_cookie(cookies, "refresh_token"));
+
+    if let Some(token) = request.into_inner().refresh_token {
+        let tokens = do_refresh(service, &token).await?;
+
+        return Ok(Response::new(pb::RefreshResponse {
+            status: "ok".to_string(),
+            token: Some(tokens.access_token),
+            refresh_token: Some(tokens.refresh_token),
+        }));
+    }
+
+    let token = cookie_token.ok_or(AuthControllerError::from(RefreshTokenError::Invalid))?;
+    let tokens = do_refresh(service, &token).await?;
+
+    let mut response = Response::new(pb::RefreshResponse {
+        status: "ok".to_string(),
+        token: None,
+        refresh_token: None,
+    });
+    for cookie in [
+        build_auth_cookie(&tokens.access_token, service.config.jwt_ttl),
+        build_refresh_cookie(&tokens.refresh_token, service.config.refresh_token_ttl, GRPC_REFRESH_COOKIE_PATH),
+    ] {
+        let cookie_meta = tonic::metadata::MetadataValue::try_from(cookie.as_str())
+            .map_err(|_| AuthControllerError::InvalidCookie)?;
+        response.metadata_mut().append("set-cookie", cookie_meta);
+    }
+
+    Ok(response)
+}
+
+async fn do_refresh(state: &AuthState, token: &str) -> Result<LoginTokens, AuthControllerError> {
+    let (user, refresh_token) = state.refresh_token_service.rotate(&state.db, token).await?;
+    let access_token = sign_access_token(state, &user).await?;
+
+    Ok(LoginTokens {
+        access_token,
+        refresh_token,
+    })
+}
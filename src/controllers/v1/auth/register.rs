@@ -0,0 +1,111 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::{Validate, ValidationError};
+
+use crate::controllers::v1::auth::{AuthControllerError, AuthState};
+use crate::pb;
+use crate::services::registration::NewUser;
+
+#[derive(Validate)]
+pub struct RegisterInput {
+    #[validate(email(message = "email must be a valid email address"), length(max = 128, message = "email must be at most 128 characters"))]
+    pub email: String,
+
+    #[validate(length(min = 8, max = 128, message = "password must be 8-128 characters"))]
+    pub password: String,
+
+    #[validate(length(min = 1, max = 128, message = "fullname must be 1-128 characters"))]
+    pub fullname: String,
+
+    #[validate(custom(function = "validate_phone", message = "phone must be a valid phone number"))]
+    pub phone: String,
+
+    #[validate(length(max = 128, message = "address must be at most 128 characters"))]
+    pub address: String,
+}
+
+/// Digits with optional `+`, spaces, dashes and parentheses, fitting `user.phone`.
+fn validate_phone(phone: &str) -> Result<(), ValidationError> {
+    let digits = phone.chars().filter(char::is_ascii_digit).count();
+    let allowed = phone
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | ' ' | '(' | ')'));
+
+    if allowed && (6..=15).contains(&digits) && phone.len() <= 16 {
+        Ok(())
+    } else {
+        Err(ValidationError::new("phone"))
+    }
+}
+
+impl From<pb::RegisterRequest> for RegisterInput {
+    fn from(input: pb::RegisterRequest) -> Self {
+        Self {
+            email: input.email.trim().to_string(),
+            password: input.password,
+            fullname: input.fullname.trim().to_string(),
+            phone: input.phone.trim().to_string(),
+            address: input.address.unwrap_or_default().trim().to_string(),
+        }
+    }
+}
+
+// HTTP
+
+pub async fn register(
+    State(state): State<Arc<AuthState>>,
+    Json(input): Json<pb::RegisterRequest>,
+) -> Result<Json<pb::RegisterResponse>, AuthControllerError> {
+    do_register(&state, input.into()).await?;
+
+    Ok(Json(pb::RegisterResponse { status: "ok".to_string() }))
+}
+
+pub async fn verify_email(
+    State(state): State<Arc<AuthState>>,
+    Query(input): Query<pb::VerifyEmailRequest>,
+) -> Result<Json<pb::VerifyEmailResponse>, AuthControllerError> {
+    state.registration_service.verify_email(&state.db, &input.token).await?;
+
+    Ok(Json(pb::VerifyEmailResponse { status: "ok".to_string() }))
+}
+
+// gRPC handlers
+
+pub async fn grpc_register(
+    service: &AuthState,
+    request: Request<pb::RegisterRequest>,
+) -> Result<Response<pb::RegisterResponse>, Status> {
+    do_register(service, request.into_inner().into()).await?;
+
+    Ok(Response::new(pb::RegisterResponse { status: "ok".to_string() }))
+}
+
+pub async fn grpc_verify_email(
+    service: &AuthState,
+    request: Request<pb::VerifyEmailRequest>,
+) -> Result<Response<pb::VerifyEmailResponse>, Status> {
+    service.registration_service.verify_email(&service.db, &request.into_inner().token)
+        .await
+        .map_err(AuthControllerError::from)?;
+
+    Ok(Response::new(pb::VerifyEmailResponse { status: "ok".to_string() }))
+}
+
+async fn do_register(state: &AuthState, input: RegisterInput) -> Result<(), AuthControllerError> {
+    input.validate().map_err(|e| AuthControllerError::Validation(super::errors::extract_validation_messages(e)))?;
+
+    state.registration_service.register(&state.db, NewUser {
+        email: input.email,
+        password: input.password,
+        fullname: input.fullname,
+        phone: input.phone,
+        address: input.address,
+    }).await?;
+
+    Ok(())
+}
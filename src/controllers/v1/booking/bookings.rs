@@ -0,0 +1,97 @@
+use axum::{
+    extract::{Extension, State},
+    Json,
+};
+use libs::jwt::Claims;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::errors::extract_validation_messages;
+use crate::pb;
+use crate::services::route::Segment;
+use crate::services::ticket::{TicketDetails, status_name};
+
+use super::{BookingControllerError, BookingState};
+
+/// Timestamps are exchanged as UTC in ISO 8601 without an offset.
+pub const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Validate)]
+pub struct BookSeatsInput {
+    #[validate(length(min = 1, max = 10, message = "between 1 and 10 seats can be booked at once"))]
+    pub seat_ids: Vec<i32>,
+}
+
+impl From<TicketDetails> for pb::Ticket {
+    fn from(details: TicketDetails) -> Self {
+        let ticket = details.ticket;
+        pb::Ticket {
+            ticket_id: ticket.ticket_id,
+            trip_id: ticket.trip_id,
+            seat_id: ticket.seat_id,
+            seat_name: details.seat_name,
+            price: ticket.price,
+            status: status_name(ticket.status).to_string(),
+            booked_date: ticket.booked_date.format(DATE_FORMAT).to_string(),
+            from_location: ticket.from_location,
+            to_location: ticket.to_location,
+            refund_amount: ticket.refund_amount,
+            discount: ticket.discount,
+        }
+    }
+}
+
+// HTTP
+
+pub async fn book_seats(
+    State(state): State<Arc<BookingState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<pb::BookSeatsRequest>,
+) -> Result<Json<pb::Booking>, BookingControllerError> {
+    Ok(Json(do_book_seats(&state, &claims, input).await?))
+}
+
+// gRPC handler
+
+pub async fn grpc_book_seats(
+    service: &BookingState,
+    request: Request<pb::BookSeatsRequest>,
+) -> Result<Response<pb::Booking>, Status> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(BookingControllerError::MissingClaims)?;
+
+    Ok(Response::new(do_book_seats(service, &claims, request.into_inner()).await?))
+}
+
+async fn do_book_seats(
+    state: &BookingState,
+    claims: &Claims,
+    input: pb::BookSeatsRequest,
+) -> Result<pb::Booking, BookingControllerError> {
+    BookSeatsInput { seat_ids: input.seat_ids.clone() }
+        .validate()
+        .map_err(|e| BookingControllerError::Validation(extract_validation_messages(e)))?;
+
+    let segment = Segment {
+        from_stop: input.from_stop,
+        to_stop: input.to_stop,
+    };
+
+    let user = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+    let booked = state.booking_service
+        .book(&state.db, user.user_id, input.trip_id, segment, &input.seat_ids, input.promo_code.as_deref())
+        .await?;
+
+    let quote = booked.quote;
+    Ok(pb::Booking {
+        tickets: booked.tickets.into_iter().map(pb::Ticket::from).collect(),
+        total_price: quote.total,
+        subtotal: quote.subtotal,
+        discount: quote.discount,
+        promo_code: quote.promo.map(|promo| promo.code),
+    })
+}
@@ -0,0 +1,54 @@
+use axum::{
+    extract::{Extension, Path, State},
+    Json,
+};
+use libs::jwt::Claims;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::pb;
+
+use super::{BookingControllerError, BookingState};
+
+/// Roles allowed to cancel tickets they do not own.
+const STAFF_ROLES: [&str; 2] = ["staff", "admin"];
+
+// HTTP
+
+pub async fn cancel_ticket(
+    State(state): State<Arc<BookingState>>,
+    Extension(claims): Extension<Claims>,
+    Path(ticket_id): Path<i32>,
+) -> Result<Json<pb::Ticket>, BookingControllerError> {
+    Ok(Json(do_cancel_ticket(&state, &claims, ticket_id).await?))
+}
+
+// gRPC handler
+
+pub async fn grpc_cancel_ticket(
+    service: &BookingState,
+    request: Request<pb::CancelTicketRequest>,
+) -> Result<Response<pb::Ticket>, Status> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(BookingControllerError::MissingClaims)?;
+
+    Ok(Response::new(do_cancel_ticket(service, &claims, request.into_inner().ticket_id).await?))
+}
+
+async fn do_cancel_ticket(
+    state: &BookingState,
+    claims: &Claims,
+    ticket_id: i32,
+) -> Result<pb::Ticket, BookingControllerError> {
+    let user = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+    let staff = claims.roles.iter().any(|role| STAFF_ROLES.contains(&role.as_str()));
+
+    let ticket = state.ticket_service
+        .cancel(&state.db, ticket_id, user.user_id, staff)
+        .await?;
+
+    Ok(ticket.into())
+}
@@ -0,0 +1,121 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use thiserror::Error;
+use tonic::Status;
+
+use crate::services::auth::AuthError;
+use crate::services::booking::BookingError;
+use crate::services::pricing::PricingError;
+use crate::services::payment::PaymentError;
+use crate::services::ticket::TicketError;
+
+#[derive(Debug, Error)]
+pub enum BookingControllerError {
+    #[error("auth error: {0}")]
+    Auth(#[from] AuthError),
+
+    #[error("booking error: {0}")]
+    Booking(#[from] BookingError),
+
+    #[error("ticket error: {0}")]
+    Ticket(#[from] TicketError),
+
+    #[error("missing claims")]
+    MissingClaims,
+
+    #[error("validation error: {0:?}")]
+    Validation(Vec<String>),
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    code: u16,
+    messages: Vec<String>,
+}
+
+// gRPC
+impl From<BookingControllerError> for Status {
+    fn from(err: BookingControllerError) -> Self {
+        match err {
+            BookingControllerError::Auth(e) => match e {
+                AuthError::UserNotFound => Status::unauthenticated("user not found"),
+                _ => Status::internal("database error"),
+            },
+            BookingControllerError::Booking(e) => match e {
+                BookingError::TripNotFound | BookingError::SeatsNotFound(_) => Status::not_found(e.to_string()),
+                BookingError::TripDeparted | BookingError::TripUnavailable => Status::failed_precondition(e.to_string()),
+                BookingError::DuplicateSeats | BookingError::InvalidSegment => Status::invalid_argument(e.to_string()),
+                BookingError::SeatsUnavailable(_) | BookingError::SeatsHeld(_) => Status::already_exists(e.to_string()),
+                BookingError::Contention => Status::aborted(e.to_string()),
+                BookingError::Pricing(PricingError::PromoNotFound) => Status::not_found(e.to_string()),
+                BookingError::Pricing(PricingError::AmountTooLarge) => Status::invalid_argument(e.to_string()),
+                BookingError::Pricing(PricingError::Database(_)) | BookingError::Database(_) => {
+                    Status::internal("database error")
+                }
+                BookingError::Pricing(_) => Status::failed_precondition(e.to_string()),
+            },
+            BookingControllerError::Ticket(e) => match e {
+                TicketError::TicketNotFound | TicketError::RouteNotFound => Status::not_found(e.to_string()),
+                TicketError::NotTicketOwner => Status::permission_denied(e.to_string()),
+                TicketError::AmountTooLarge => Status::invalid_argument(e.to_string()),
+                TicketError::IllegalTransition { .. } | TicketError::CancelRequired(_) | TicketError::TripDeparted => {
+                    Status::failed_precondition(e.to_string())
+                }
+                TicketError::Payment(PaymentError::Provider(_)) => Status::unavailable("payment provider error"),
+                TicketError::Payment(_) | TicketError::Database(_) => Status::internal("database error"),
+            },
+            BookingControllerError::MissingClaims => Status::unauthenticated("missing claims"),
+            BookingControllerError::Validation(msgs) => Status::invalid_argument(msgs.join(", ")),
+        }
+    }
+}
+
+// HTTP
+impl IntoResponse for BookingControllerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, messages) = match self {
+            BookingControllerError::Auth(e) => match e {
+                AuthError::UserNotFound => (StatusCode::UNAUTHORIZED, vec!["user not found".to_string()]),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            BookingControllerError::Booking(e) => match e {
+                BookingError::TripNotFound | BookingError::SeatsNotFound(_) => (StatusCode::NOT_FOUND, vec![e.to_string()]),
+                BookingError::TripDeparted | BookingError::TripUnavailable => (StatusCode::CONFLICT, vec![e.to_string()]),
+                BookingError::DuplicateSeats | BookingError::InvalidSegment => {
+                    (StatusCode::BAD_REQUEST, vec![e.to_string()])
+                }
+                BookingError::SeatsUnavailable(_) | BookingError::SeatsHeld(_) | BookingError::Contention => {
+                    (StatusCode::CONFLICT, vec![e.to_string()])
+                }
+                BookingError::Pricing(PricingError::PromoNotFound) => (StatusCode::NOT_FOUND, vec![e.to_string()]),
+                BookingError::Pricing(PricingError::AmountTooLarge) => (StatusCode::BAD_REQUEST, vec![e.to_string()]),
+                BookingError::Pricing(PricingError::Database(_)) | BookingError::Database(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()])
+                }
+                BookingError::Pricing(_) => (StatusCode::CONFLICT, vec![e.to_string()]),
+            },
+            BookingControllerError::Ticket(e) => match e {
+                TicketError::TicketNotFound | TicketError::RouteNotFound => (StatusCode::NOT_FOUND, vec![e.to_string()]),
+                TicketError::NotTicketOwner => (StatusCode::FORBIDDEN, vec![e.to_string()]),
+                TicketError::AmountTooLarge => (StatusCode::BAD_REQUEST, vec![e.to_string()]),
+                TicketError::IllegalTransition { .. } | TicketError::CancelRequired(_) | TicketError::TripDeparted => {
+                    (StatusCode::CONFLICT, vec![e.to_string()])
+                }
+                TicketError::Payment(PaymentError::Provider(_)) => {
+                    (StatusCode::BAD_GATEWAY, vec!["payment provider error".to_string()])
+                }
+                TicketError::Payment(_) | TicketError::Database(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()])
+                }
+            },
+            BookingControllerError::MissingClaims => (StatusCode::UNAUTHORIZED, vec!["missing claims".to_string()]),
+            BookingControllerError::Validation(msgs) => (StatusCode::BAD_REQUEST, msgs),
+        };
+
+        (status, Json(ErrorResponse { code: status.as_u16(), messages })).into_response()
+    }
+}
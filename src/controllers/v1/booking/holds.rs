@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Extension, State},
+    Json,
+};
+use libs::jwt::Claims;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::errors::extract_validation_messages;
+use crate::pb;
+use crate::services::route::Segment;
+
+use super::bookings::{BookSeatsInput, DATE_FORMAT};
+use super::{BookingControllerError, BookingState};
+
+// HTTP
+
+pub async fn hold_seats(
+    State(state): State<Arc<BookingState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<pb::HoldSeatsRequest>,
+) -> Result<Json<pb::SeatHold>, BookingControllerError> {
+    Ok(Json(do_hold_seats(&state, &claims, input).await?))
+}
+
+// gRPC handler
+
+pub async fn grpc_hold_seats(
+    service: &BookingState,
+    request: Request<pb::HoldSeatsRequest>,
+) -> Result<Response<pb::SeatHold>, Status> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(BookingControllerError::MissingClaims)?;
+
+    Ok(Response::new(do_hold_seats(service, &claims, request.into_inner()).await?))
+}
+
+async fn do_hold_seats(
+    state: &BookingState,
+    claims: &Claims,
+    input: pb::HoldSeatsRequest,
+) -> Result<pb::SeatHold, BookingControllerError> {
+    BookSeatsInput { seat_ids: input.seat_ids.clone() }
+        .validate()
+        .map_err(|e| BookingControllerError::Validation(extract_validation_messages(e)))?;
+
+    let segment = Segment {
+        from_stop: input.from_stop,
+        to_stop: input.to_stop,
+    };
+
+    let user = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+    let holds = state.booking_service
+        .hold(&state.db, user.user_id, input.trip_id, segment, &input.seat_ids)
+        .await?;
+
+    // All holds of one call share their expiry and stops.
+    let (expires_at, from_stop, to_stop) = holds
+        .first()
+        .map(|hold| (hold.expires_at.format(DATE_FORMAT).to_string(), hold.from_stop, hold.to_stop))
+        .unwrap_or_default();
+
+    Ok(pb::SeatHold {
+        trip_id: input.trip_id,
+        seat_ids: holds.into_iter().map(|hold| hold.seat_id).collect(),
+        expires_at,
+        from_stop,
+        to_stop,
+    })
+}
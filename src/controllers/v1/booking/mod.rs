@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use sea_orm::DatabaseConnection;
+use tonic::{Request, Response, Status};
+
+pub mod bookings;
+pub mod cancel;
+pub mod errors;
+pub mod holds;
+pub mod my_tickets;
+pub mod quote;
+
+use crate::inject::{InjectError, InjectFactory};
+use crate::pb;
+use crate::services::auth::Auth;
+use crate::services::booking::BookingService;
+use crate::services::ticket::TicketService;
+
+pub use bookings::book_seats;
+pub use cancel::cancel_ticket;
+pub use errors::BookingControllerError;
+pub use holds::hold_seats;
+pub use my_tickets::list_my_tickets;
+pub use quote::quote_price;
+
+pub struct BookingState {
+    pub auth_service: Arc<dyn Auth>,
+    pub booking_service: Arc<dyn BookingService>,
+    pub ticket_service: Arc<dyn TicketService>,
+    pub db: Arc<DatabaseConnection>,
+}
+
+impl BookingState {
+    pub fn new(injector: &dyn InjectFactory) -> Result<Self, InjectError> {
+        Ok(Self {
+            auth_service: injector.auth_service()?,
+            booking_service: injector.booking_service()?,
+            ticket_service: injector.ticket_service()?,
+            db: injector.database()?,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl pb::booking_service_server::BookingService for BookingState {
+    async fn book_seats(
+        &self,
+        request: Request<pb::BookSeatsRequest>,
+    ) -> Result<Response<pb::Booking>, Status> {
+        bookings::grpc_book_seats(self, request).await
+    }
+
+    async fn quote_price(
+        &self,
+        request: Request<pb::QuotePriceRequest>,
+    ) -> Result<Response<pb::PriceQuote>, Status> {
+        quote::grpc_quote_price(self, request).await
+    }
+
+    async fn hold_seats(
+        &self,
+        request: Request<pb::HoldSeatsRequest>,
+    ) -> Result<Response<pb::SeatHold>, Status> {
+        holds::grpc_hold_seats(self, request).await
+    }
+
+    async fn cancel_ticket(
+        &self,
+        request: Request<pb::CancelTicketRequest>,
+    ) -> Result<Response<pb::Ticket>, Status> {
+        cancel::grpc_cancel_ticket(self, request).await
+    }
+
+    async fn list_my_tickets(
+        &self,
+        request: Request<pb::ListMyTicketsRequest>,
+    ) -> Result<Response<pb::MyTicketPage>, Status> {
+        my_tickets::grpc_list_my_tickets(self, request).await
+    }
+}
@@ -0,0 +1,106 @@
+use axum::{
+    extract::{Extension, Query, State},
+    Json,
+};
+use libs::jwt::Claims;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::errors::extract_validation_messages;
+use crate::pb;
+use crate::services::ticket::{TicketFilter, UserTicket};
+
+use super::bookings::DATE_FORMAT;
+use super::{BookingControllerError, BookingState};
+
+const DEFAULT_PAGE_SIZE: u32 = 20;
+
+#[derive(Validate)]
+pub struct ListMyTicketsInput {
+    #[validate(range(min = 1, message = "page starts at 1"))]
+    pub page: u32,
+
+    #[validate(range(min = 1, max = 100, message = "page_size must be between 1 and 100"))]
+    pub page_size: u32,
+}
+
+impl From<UserTicket> for pb::MyTicket {
+    fn from(ticket: UserTicket) -> Self {
+        pb::MyTicket {
+            ticket: ticket.details.into(),
+            route_id: ticket.route_id,
+            route_from_location: ticket.route_from_location,
+            route_to_location: ticket.route_to_location,
+            departure_time: ticket.departure.format(DATE_FORMAT).to_string(),
+            arrival_time: ticket.arrival.format(DATE_FORMAT).to_string(),
+            bus_id: ticket.bus_id,
+            bus_name: ticket.bus_name,
+            license_plate: ticket.license_plate,
+        }
+    }
+}
+
+// HTTP
+
+pub async fn list_my_tickets(
+    State(state): State<Arc<BookingState>>,
+    Extension(claims): Extension<Claims>,
+    Query(input): Query<pb::ListMyTicketsRequest>,
+) -> Result<Json<pb::MyTicketPage>, BookingControllerError> {
+    Ok(Json(do_list_my_tickets(&state, &claims, input).await?))
+}
+
+// gRPC handler
+
+pub async fn grpc_list_my_tickets(
+    service: &BookingState,
+    request: Request<pb::ListMyTicketsRequest>,
+) -> Result<Response<pb::MyTicketPage>, Status> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(BookingControllerError::MissingClaims)?;
+
+    Ok(Response::new(do_list_my_tickets(service, &claims, request.into_inner()).await?))
+}
+
+async fn do_list_my_tickets(
+    state: &BookingState,
+    claims: &Claims,
+    input: pb::ListMyTicketsRequest,
+) -> Result<pb::MyTicketPage, BookingControllerError> {
+    let page = input.page.unwrap_or(1);
+    let page_size = input.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+
+    let mut messages = match (ListMyTicketsInput { page, page_size }).validate() {
+        Ok(()) => Vec::new(),
+        Err(e) => extract_validation_messages(e),
+    };
+    let filter = match input.filter.as_deref() {
+        None | Some("all") => TicketFilter::All,
+        Some("upcoming") => TicketFilter::Upcoming,
+        Some("past") => TicketFilter::Past,
+        Some("cancelled") => TicketFilter::Cancelled,
+        Some(_) => {
+            messages.push("filter must be upcoming, past or cancelled".to_string());
+            TicketFilter::All
+        }
+    };
+    if !messages.is_empty() {
+        return Err(BookingControllerError::Validation(messages));
+    }
+
+    let user = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+    let tickets = state.ticket_service
+        .list_for_user(&state.db, user.user_id, filter, page.into(), page_size.into())
+        .await?;
+
+    Ok(pb::MyTicketPage {
+        tickets: tickets.tickets.into_iter().map(pb::MyTicket::from).collect(),
+        total: tickets.total.try_into().unwrap_or(u32::MAX),
+        page,
+        page_size,
+    })
+}
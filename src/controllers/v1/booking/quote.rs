@@ -0,0 +1,101 @@
+use axum::{
+    extract::{Extension, State},
+    Json,
+};
+use libs::jwt::Claims;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::errors::extract_validation_messages;
+use crate::pb;
+use crate::services::booking::TripQuote;
+use crate::services::pricing::{PriceLine, kind_name};
+use crate::services::route::Segment;
+
+use super::bookings::BookSeatsInput;
+use super::{BookingControllerError, BookingState};
+
+impl From<PriceLine> for pb::PriceLine {
+    fn from(line: PriceLine) -> Self {
+        pb::PriceLine {
+            seat_id: line.seat_id,
+            seat_name: line.seat_name,
+            fare: line.fare,
+            seat_surcharge: line.surcharge,
+            discount: line.discount,
+            price: line.price,
+        }
+    }
+}
+
+impl From<TripQuote> for pb::PriceQuote {
+    fn from(trip: TripQuote) -> Self {
+        let quote = trip.quote;
+        pb::PriceQuote {
+            trip_id: trip.trip_id,
+            from_stop: trip.from_stop,
+            to_stop: trip.to_stop,
+            from_location: trip.from_location,
+            to_location: trip.to_location,
+            lines: quote.lines.into_iter().map(pb::PriceLine::from).collect(),
+            subtotal: quote.subtotal,
+            discount: quote.discount,
+            total: quote.total,
+            promo: quote.promo.map(|promo| pb::AppliedPromo {
+                code: promo.code,
+                kind: kind_name(promo.kind).to_string(),
+                value: promo.value,
+                max_discount: promo.max_discount,
+            }),
+        }
+    }
+}
+
+// HTTP
+
+pub async fn quote_price(
+    State(state): State<Arc<BookingState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<pb::QuotePriceRequest>,
+) -> Result<Json<pb::PriceQuote>, BookingControllerError> {
+    Ok(Json(do_quote_price(&state, &claims, input).await?))
+}
+
+// gRPC handler
+
+pub async fn grpc_quote_price(
+    service: &BookingState,
+    request: Request<pb::QuotePriceRequest>,
+) -> Result<Response<pb::PriceQuote>, Status> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(BookingControllerError::MissingClaims)?;
+
+    Ok(Response::new(do_quote_price(service, &claims, request.into_inner()).await?))
+}
+
+async fn do_quote_price(
+    state: &BookingState,
+    claims: &Claims,
+    input: pb::QuotePriceRequest,
+) -> Result<pb::PriceQuote, BookingControllerError> {
+    BookSeatsInput { seat_ids: input.seat_ids.clone() }
+        .validate()
+        .map_err(|e| BookingControllerError::Validation(extract_validation_messages(e)))?;
+
+    let segment = Segment {
+        from_stop: input.from_stop,
+        to_stop: input.to_stop,
+    };
+
+    // Per-customer limits depend on who asks, so quotes need a signed-in user.
+    let user = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+    let quote = state.booking_service
+        .quote(&state.db, user.user_id, input.trip_id, segment, &input.seat_ids, input.promo_code.as_deref())
+        .await?;
+
+    Ok(quote.into())
+}
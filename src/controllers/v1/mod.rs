@@ -1,35 +1,42 @@
+pub mod account;
+pub mod admin;
 pub mod auth;
+pub mod booking;
 pub mod heath;
+pub mod payment;
 pub mod protected;
+pub mod ticket;
+pub mod trip;
 
 use std::sync::Arc;
 
 use axum::{
     Router, middleware,
-    routing::{get, post},
+    routing::{delete, get, post, put},
 };
-use sea_orm::DatabaseConnection;
 use libs::axum::middlewares::jwt_authorize::{JwtAuth, jwt_authorize};
 use libs::tonic::middlewares::jwt_authorize::JwtAuthInterceptor;
 use tonic::service::interceptor::InterceptedService;
 use tonic::service::{LayerExt, Routes};
 
-use crate::{config::Config, pb, services::auth::Auth, services::jwt::JWTService};
+use crate::inject::{InjectError, InjectFactory};
+use crate::pb;
 
-pub fn http_router(
-    config: Arc<Config>,
-    jwt_service: Arc<dyn JWTService>,
-    auth_service: Arc<dyn Auth>,
-    db: Arc<DatabaseConnection>,
-) -> Router {
-    let auth_state = Arc::new(auth::AuthState::new(
-        config.clone(),
-        jwt_service.clone(),
-        auth_service.clone(),
-        db.clone(),
-    ));
+pub fn http_router(injector: &dyn InjectFactory) -> Result<Router, InjectError> {
+    let config = injector.config()?;
+    let jwt_service = injector.jwt_service()?;
+    let revocation_service = injector.revocation_service()?;
 
-    let jwt_auth = JwtAuth::with_policies(config.jwt_secret.clone(), vec!["protected.read"]);
+    let auth_state = Arc::new(auth::AuthState::new(injector)?);
+    let admin_state = Arc::new(admin::AdminState::new(injector)?);
+    let account_state = Arc::new(account::AccountState::new(injector)?);
+    let booking_state = Arc::new(booking::BookingState::new(injector)?);
+    let trip_state = Arc::new(trip::TripState::new(injector)?);
+    let ticket_state = Arc::new(ticket::TicketState::new(injector)?);
+    let payment_state = Arc::new(payment::PaymentState::new(injector)?);
+
+    let jwt_auth = JwtAuth::with_policies(jwt_service.key_set(), vec!["protected.read"])
+        .with_revocation(revocation_service.store());
 
     let protected_router = Router::new()
         .route("/protected", get(protected::me::me))
@@ -38,31 +45,142 @@ pub fn http_router(
     let auth_router = Router::new()
         .route("/auth/jwt", post(auth::login_jwt))
         .route("/auth/cookie", post(auth::login_cookie))
+        .route("/auth/refresh", post(auth::refresh))
         .route("/auth/logout", post(auth::logout))
+        .route("/auth/register", post(auth::register))
+        .route("/auth/verify-email", get(auth::verify_email))
+        .route("/auth/forgot-password", post(auth::forgot_password))
+        .route("/auth/reset-password", post(auth::reset_password))
+        .route("/auth/mfa/verify", post(auth::verify_mfa))
+        .route("/auth/mfa/enroll", post(auth::enroll_mfa))
         .with_state(auth_state);
 
-    Router::new()
+    let account_auth = JwtAuth::new(jwt_service.key_set()).with_revocation(revocation_service.store());
+
+    let account_router = Router::new()
+        .route("/account/change-password", post(account::change_password))
+        .route("/account/mfa/totp", post(account::enroll_totp))
+        .route("/account/mfa/totp/confirm", post(account::confirm_totp))
+        .route("/account/mfa/totp/disable", post(account::disable_totp))
+        .route("/account/mfa/recovery-codes", post(account::regenerate_recovery_codes))
+        .route_layer(middleware::from_fn_with_state(account_auth, jwt_authorize))
+        .with_state(account_state);
+
+    let trip_router = Router::new()
+        .route("/journeys", get(trip::plan_journey))
+        .route("/locations", get(trip::suggest_locations))
+        .route("/trips", get(trip::search_trips))
+        .route("/trips/{trip_id}/seats", get(trip::get_seat_map))
+        .with_state(trip_state);
+
+    let booking_auth = JwtAuth::new(jwt_service.key_set()).with_revocation(revocation_service.store());
+
+    let booking_router = Router::new()
+        .route("/bookings", post(booking::book_seats))
+        .route("/bookings/holds", post(booking::hold_seats))
+        .route("/bookings/quote", post(booking::quote_price))
+        .route("/tickets/{ticket_id}/cancel", post(booking::cancel_ticket))
+        .route("/me/tickets", get(booking::list_my_tickets))
+        .route_layer(middleware::from_fn_with_state(booking_auth, jwt_authorize))
+        .with_state(booking_state);
+
+    let payment_auth = JwtAuth::new(jwt_service.key_set()).with_revocation(revocation_service.store());
+
+    let payment_router = Router::new()
+        .route("/payments", post(payment::create_payment))
+        .route("/payments/{payment_id}", get(payment::get_payment))
+        .route_layer(middleware::from_fn_with_state(payment_auth, jwt_authorize))
+        .with_state(payment_state.clone());
+
+    // Called by the provider, which proves itself with the webhook signature.
+    let mut payment_webhook_router = Router::new().route("/payments/webhook", post(payment::payment_webhook));
+    // Marks payments paid without any money changing hands, so development only.
+    if config.payment_mock_checkout {
+        payment_webhook_router = payment_webhook_router
+            .route("/payments/mock/{provider_ref}/complete", post(payment::complete_mock_checkout));
+    }
+    let payment_webhook_router = payment_webhook_router.with_state(payment_state);
+
+    let ticket_auth = JwtAuth::with_roles(jwt_service.key_set(), vec!["staff", "admin"])
+        .with_revocation(revocation_service.store());
+
+    let ticket_router = Router::new()
+        .route("/tickets/{ticket_id}/status", post(ticket::update_ticket_status))
+        .route("/tickets/{ticket_id}/history", get(ticket::get_ticket_history))
+        .route_layer(middleware::from_fn_with_state(ticket_auth, jwt_authorize))
+        .with_state(ticket_state);
+
+    let admin_auth = JwtAuth::with_roles(jwt_service.key_set(), vec!["admin"])
+        .with_revocation(revocation_service.store());
+
+    let admin_router = Router::new()
+        .route("/admin/users/{user_id}/revoke-tokens", post(admin::revoke_user_tokens))
+        .route("/admin/users/{user_id}/role", put(admin::set_user_role))
+        .route("/admin/users/{user_id}/unlock", post(admin::unlock_user))
+        .route("/admin/roles", get(admin::list_roles).post(admin::create_role))
+        .route("/admin/roles/{role_id}", put(admin::update_role).delete(admin::delete_role))
+        .route("/admin/policies", get(admin::list_policies).post(admin::create_policy))
+        .route("/admin/policies/{policy_id}", put(admin::update_policy).delete(admin::delete_policy))
+        .route(
+            "/admin/routes/{route_id}/refund-policy",
+            get(admin::get_refund_policy).put(admin::set_refund_policy),
+        )
+        .route("/admin/routes", get(admin::list_routes).post(admin::create_route))
+        .route(
+            "/admin/routes/{route_id}",
+            get(admin::get_route).put(admin::update_route).delete(admin::delete_route),
+        )
+        .route("/admin/routes/{route_id}/restore", post(admin::restore_route))
+        .route(
+            "/admin/routes/{route_id}/stops",
+            get(admin::get_route_stops).put(admin::set_route_stops),
+        )
+        .route("/admin/buses", get(admin::list_buses).post(admin::create_bus))
+        .route("/admin/buses/layout-report", get(admin::get_seat_layout_report))
+        .route("/admin/buses/{bus_id}/seats", get(admin::get_seat_layout).post(admin::add_seats))
+        .route("/admin/buses/{bus_id}/seats/clone", post(admin::clone_seat_layout))
+        .route("/admin/buses/{bus_id}/seats/{seat_id}", delete(admin::delete_seat))
+        .route("/admin/trips", post(admin::create_trip))
+        .route("/admin/trips/{trip_id}", put(admin::update_trip))
+        .route("/admin/trips/{trip_id}/cancel", post(admin::cancel_trip))
+        .route("/admin/schedules", get(admin::list_schedules).post(admin::create_schedule))
+        .route("/admin/schedules/preview", post(admin::preview_schedule))
+        .route("/admin/schedules/generate", post(admin::generate_trips))
+        .route("/admin/schedules/{schedule_id}", delete(admin::deactivate_schedule))
+        .route("/admin/locations", get(admin::list_locations).post(admin::create_location))
+        .route("/admin/locations/{location_id}", put(admin::update_location))
+        .route("/admin/locations/{location_id}/merge", post(admin::merge_location))
+        .route("/admin/promo-codes", get(admin::list_promo_codes).post(admin::create_promo_code))
+        .route("/admin/promo-codes/{promo_code_id}", put(admin::update_promo_code))
+        .route_layer(middleware::from_fn_with_state(admin_auth, jwt_authorize))
+        .with_state(admin_state);
+
+    Ok(Router::new()
         .route("/", get(heath::healthz::healthz))
         .route("/healthz", get(heath::healthz::healthz))
         .merge(auth_router)
         .merge(protected_router)
-        .with_state(config)
+        .merge(account_router)
+        .merge(trip_router)
+        .merge(booking_router)
+        .merge(payment_router)
+        .merge(payment_webhook_router)
+        .merge(ticket_router)
+        .merge(admin_router)
+        .with_state(config))
 }
 
-pub fn grpc_router(
-    config: Arc<Config>,
-    jwt_service: Arc<dyn JWTService>,
-    auth_service: Arc<dyn Auth>,
-    db: Arc<DatabaseConnection>,
-) -> Router {
-    let jwt_secret = config.jwt_secret.clone();
-
-    let auth_state = auth::AuthState::new(
-        config.clone(),
-        jwt_service.clone(),
-        auth_service.clone(),
-        db.clone(),
-    );
+pub fn grpc_router(injector: &dyn InjectFactory) -> Result<Router, InjectError> {
+    let jwt_keys = injector.jwt_service()?.key_set();
+    let revocation_service = injector.revocation_service()?;
+
+    let auth_state = auth::AuthState::new(injector)?;
+    let admin_state = admin::AdminState::new(injector)?;
+    let account_state = account::AccountState::new(injector)?;
+    let booking_state = booking::BookingState::new(injector)?;
+    let trip_state = trip::TripState::new(injector)?;
+    let ticket_state = ticket::TicketState::new(injector)?;
+    let payment_state = payment::PaymentState::new(injector)?;
 
     let heath = tonic_web::GrpcWebLayer::new().named_layer(
         pb::heath_service_server::HeathServiceServer::new(heath::HeathController::new()),
@@ -72,15 +190,61 @@ pub fn grpc_router(
         pb::auth_service_server::AuthServiceServer::new(auth_state),
     );
 
-    let interceptor = JwtAuthInterceptor::with_policies(jwt_secret, vec!["protected.read"]);
+    let trip = tonic_web::GrpcWebLayer::new().named_layer(
+        pb::trip_service_server::TripServiceServer::new(trip_state),
+    );
+
+    let interceptor = JwtAuthInterceptor::with_policies(jwt_keys.clone(), vec!["protected.read"])
+        .with_revocation(revocation_service.store());
     let protected_service =
         pb::protected_service_server::ProtectedServiceServer::new(protected::ProtectedController);
     let protected =
         InterceptedService::new(protected_service, move |req| interceptor.intercept(req));
     let protected = tonic_web::GrpcWebLayer::new().named_layer(protected);
 
-    Routes::new(heath)
+    let account_interceptor =
+        JwtAuthInterceptor::new(jwt_keys.clone()).with_revocation(revocation_service.store());
+    let account_service = pb::account_service_server::AccountServiceServer::new(account_state);
+    let account =
+        InterceptedService::new(account_service, move |req| account_interceptor.intercept(req));
+    let account = tonic_web::GrpcWebLayer::new().named_layer(account);
+
+    let booking_interceptor =
+        JwtAuthInterceptor::new(jwt_keys.clone()).with_revocation(revocation_service.store());
+    let booking_service = pb::booking_service_server::BookingServiceServer::new(booking_state);
+    let booking =
+        InterceptedService::new(booking_service, move |req| booking_interceptor.intercept(req));
+    let booking = tonic_web::GrpcWebLayer::new().named_layer(booking);
+
+    let payment_interceptor =
+        JwtAuthInterceptor::new(jwt_keys.clone()).with_revocation(revocation_service.store());
+    let payment_service = pb::payment_service_server::PaymentServiceServer::new(payment_state);
+    let payment =
+        InterceptedService::new(payment_service, move |req| payment_interceptor.intercept(req));
+    let payment = tonic_web::GrpcWebLayer::new().named_layer(payment);
+
+    let ticket_interceptor = JwtAuthInterceptor::with_roles(jwt_keys.clone(), vec!["staff", "admin"])
+        .with_revocation(revocation_service.store());
+    let ticket_service = pb::ticket_service_server::TicketServiceServer::new(ticket_state);
+    let ticket =
+        InterceptedService::new(ticket_service, move |req| ticket_interceptor.intercept(req));
+    let ticket = tonic_web::GrpcWebLayer::new().named_layer(ticket);
+
+    let admin_interceptor = JwtAuthInterceptor::with_roles(jwt_keys, vec!["admin"])
+        .with_revocation(revocation_service.store());
+    let admin_service = pb::admin_service_server::AdminServiceServer::new(admin_state);
+    let admin =
+        InterceptedService::new(admin_service, move |req| admin_interceptor.intercept(req));
+    let admin = tonic_web::GrpcWebLayer::new().named_layer(admin);
+
+    Ok(Routes::new(heath)
         .add_service(auth)
         .add_service(protected)
-        .into_axum_router()
+        .add_service(account)
+        .add_service(trip)
+        .add_service(booking)
+        .add_service(payment)
+        .add_service(ticket)
+        .add_service(admin)
+        .into_axum_router())
 }
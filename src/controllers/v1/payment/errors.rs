@@ -0,0 +1,93 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use thiserror::Error;
+use tonic::Status;
+
+use crate::services::auth::AuthError;
+use crate::services::payment::PaymentError;
+
+#[derive(Debug, Error)]
+pub enum PaymentControllerError {
+    #[error("auth error: {0}")]
+    Auth(#[from] AuthError),
+
+    #[error("payment error: {0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("missing claims")]
+    MissingClaims,
+
+    #[error("validation error: {0:?}")]
+    Validation(Vec<String>),
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    code: u16,
+    messages: Vec<String>,
+}
+
+// gRPC
+impl From<PaymentControllerError> for Status {
+    fn from(err: PaymentControllerError) -> Self {
+        match err {
+            PaymentControllerError::Auth(e) => match e {
+                AuthError::UserNotFound => Status::unauthenticated("user not found"),
+                _ => Status::internal("database error"),
+            },
+            PaymentControllerError::Payment(e) => match e {
+                PaymentError::PaymentNotFound | PaymentError::TicketsNotFound(_) => Status::not_found(e.to_string()),
+                PaymentError::NotPaymentOwner => Status::permission_denied(e.to_string()),
+                PaymentError::DuplicateTickets
+                | PaymentError::InvalidSignature
+                | PaymentError::InvalidPayload(_)
+                | PaymentError::AmountMismatch
+                | PaymentError::AmountTooLarge => Status::invalid_argument(e.to_string()),
+                PaymentError::TicketsNotPayable(_) | PaymentError::CheckoutUnavailable => {
+                    Status::failed_precondition(e.to_string())
+                }
+                PaymentError::PaymentPending(_) => Status::already_exists(e.to_string()),
+                PaymentError::Provider(_) => Status::unavailable("payment provider error"),
+                PaymentError::Database(_) => Status::internal("database error"),
+            },
+            PaymentControllerError::MissingClaims => Status::unauthenticated("missing claims"),
+            PaymentControllerError::Validation(msgs) => Status::invalid_argument(msgs.join(", ")),
+        }
+    }
+}
+
+// HTTP
+impl IntoResponse for PaymentControllerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, messages) = match self {
+            PaymentControllerError::Auth(e) => match e {
+                AuthError::UserNotFound => (StatusCode::UNAUTHORIZED, vec!["user not found".to_string()]),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            PaymentControllerError::Payment(e) => match e {
+                PaymentError::PaymentNotFound | PaymentError::TicketsNotFound(_) => {
+                    (StatusCode::NOT_FOUND, vec![e.to_string()])
+                }
+                PaymentError::NotPaymentOwner => (StatusCode::FORBIDDEN, vec![e.to_string()]),
+                PaymentError::DuplicateTickets
+                | PaymentError::InvalidSignature
+                | PaymentError::InvalidPayload(_)
+                | PaymentError::AmountMismatch
+                | PaymentError::AmountTooLarge => (StatusCode::BAD_REQUEST, vec![e.to_string()]),
+                PaymentError::TicketsNotPayable(_)
+                | PaymentError::PaymentPending(_)
+                | PaymentError::CheckoutUnavailable => (StatusCode::CONFLICT, vec![e.to_string()]),
+                PaymentError::Provider(_) => (StatusCode::BAD_GATEWAY, vec!["payment provider error".to_string()]),
+                PaymentError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            PaymentControllerError::MissingClaims => (StatusCode::UNAUTHORIZED, vec!["missing claims".to_string()]),
+            PaymentControllerError::Validation(msgs) => (StatusCode::BAD_REQUEST, msgs),
+        };
+
+        (status, Json(ErrorResponse { code: status.as_u16(), messages })).into_response()
+    }
+}
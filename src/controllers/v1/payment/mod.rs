@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use sea_orm::DatabaseConnection;
+use tonic::{Request, Response, Status};
+
+pub mod errors;
+pub mod payments;
+pub mod webhook;
+
+use crate::inject::{InjectError, InjectFactory};
+use crate::pb;
+use crate::services::auth::Auth;
+use crate::services::payment::PaymentService;
+
+pub use errors::PaymentControllerError;
+pub use payments::{create_payment, get_payment};
+pub use webhook::{complete_mock_checkout, payment_webhook};
+
+pub struct PaymentState {
+    pub auth_service: Arc<dyn Auth>,
+    pub payment_service: Arc<dyn PaymentService>,
+    pub db: Arc<DatabaseConnection>,
+}
+
+impl PaymentState {
+    pub fn new(injector: &dyn InjectFactory) -> Result<Self, InjectError> {
+        Ok(Self {
+            auth_service: injector.auth_service()?,
+            payment_service: injector.payment_service()?,
+            db: injector.database()?,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl pb::payment_service_server::PaymentService for PaymentState {
+    async fn create_payment(
+        &self,
+        request: Request<pb::CreatePaymentRequest>,
+    ) -> Result<Response<pb::Payment>, Status> {
+        payments::grpc_create_payment(self, request).await
+    }
+
+    async fn get_payment(
+        &self,
+        request: Request<pb::GetPaymentRequest>,
+    ) -> Result<Response<pb::Payment>, Status> {
+        payments::grpc_get_payment(self, request).await
+    }
+}
@@ -0,0 +1,117 @@
+use axum::{
+    extract::{Extension, Path, State},
+    Json,
+};
+use libs::jwt::Claims;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::errors::extract_validation_messages;
+use crate::controllers::v1::booking::bookings::DATE_FORMAT;
+use crate::pb;
+use crate::services::payment::{PaymentDetails, status_name};
+
+use super::{PaymentControllerError, PaymentState};
+
+#[derive(Validate)]
+pub struct CreatePaymentInput {
+    #[validate(length(min = 1, max = 10, message = "between 1 and 10 tickets can be paid at once"))]
+    pub ticket_ids: Vec<i32>,
+}
+
+impl From<PaymentDetails> for pb::Payment {
+    fn from(details: PaymentDetails) -> Self {
+        let intent = details.intent;
+        pb::Payment {
+            payment_id: intent.payment_intent_id,
+            ticket_ids: details.ticket_ids,
+            amount: intent.amount,
+            currency: intent.currency,
+            status: status_name(intent.status).to_string(),
+            provider: intent.provider,
+            provider_ref: intent.provider_ref,
+            checkout_url: intent.checkout_url,
+            refunded_amount: intent.refunded_amount,
+            created_at: intent.created_at.format(DATE_FORMAT).to_string(),
+            expires_at: intent.expires_at.map(|date| date.format(DATE_FORMAT).to_string()),
+            completed_at: intent.completed_at.map(|date| date.format(DATE_FORMAT).to_string()),
+        }
+    }
+}
+
+// HTTP
+
+pub async fn create_payment(
+    State(state): State<Arc<PaymentState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<pb::CreatePaymentRequest>,
+) -> Result<Json<pb::Payment>, PaymentControllerError> {
+    Ok(Json(do_create_payment(&state, &claims, input).await?))
+}
+
+pub async fn get_payment(
+    State(state): State<Arc<PaymentState>>,
+    Extension(claims): Extension<Claims>,
+    Path(payment_id): Path<i32>,
+) -> Result<Json<pb::Payment>, PaymentControllerError> {
+    Ok(Json(do_get_payment(&state, &claims, payment_id).await?))
+}
+
+// gRPC handlers
+
+pub async fn grpc_create_payment(
+    service: &PaymentState,
+    request: Request<pb::CreatePaymentRequest>,
+) -> Result<Response<pb::Payment>, Status> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(PaymentControllerError::MissingClaims)?;
+
+    Ok(Response::new(do_create_payment(service, &claims, request.into_inner()).await?))
+}
+
+pub async fn grpc_get_payment(
+    service: &PaymentState,
+    request: Request<pb::GetPaymentRequest>,
+) -> Result<Response<pb::Payment>, Status> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(PaymentControllerError::MissingClaims)?;
+
+    Ok(Response::new(do_get_payment(service, &claims, request.into_inner().payment_id).await?))
+}
+
+async fn do_create_payment(
+    state: &PaymentState,
+    claims: &Claims,
+    input: pb::CreatePaymentRequest,
+) -> Result<pb::Payment, PaymentControllerError> {
+    CreatePaymentInput { ticket_ids: input.ticket_ids.clone() }
+        .validate()
+        .map_err(|e| PaymentControllerError::Validation(extract_validation_messages(e)))?;
+
+    let user = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+    let payment = state.payment_service
+        .create_payment(&state.db, user.user_id, &input.ticket_ids)
+        .await?;
+
+    Ok(payment.into())
+}
+
+async fn do_get_payment(
+    state: &PaymentState,
+    claims: &Claims,
+    payment_id: i32,
+) -> Result<pb::Payment, PaymentControllerError> {
+    let user = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+    let payment = state.payment_service
+        .payment(&state.db, user.user_id, payment_id)
+        .await?;
+
+    Ok(payment.into())
+}
@@ -0,0 +1,64 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::pb;
+use crate::services::payment::{PaymentOutcome, WebhookReceipt};
+
+use super::{PaymentControllerError, PaymentState};
+
+/// Header carrying the provider's `t=<unix seconds>,v1=<hex HMAC>` signature.
+pub const SIGNATURE_HEADER: &str = "x-payment-signature";
+
+impl From<WebhookReceipt> for pb::PaymentWebhookReceipt {
+    fn from(receipt: WebhookReceipt) -> Self {
+        pb::PaymentWebhookReceipt {
+            payment: receipt.payment.into(),
+            duplicate: receipt.duplicate,
+        }
+    }
+}
+
+// HTTP only: providers call webhooks over plain HTTP and sign the raw body.
+
+pub async fn payment_webhook(
+    State(state): State<Arc<PaymentState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<pb::PaymentWebhookReceipt>, PaymentControllerError> {
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| PaymentControllerError::Validation(vec![format!("{SIGNATURE_HEADER} header is required")]))?;
+
+    let receipt = state.payment_service.handle_webhook(&state.db, signature, &body).await?;
+
+    Ok(Json(receipt.into()))
+}
+
+/// Stands in for the provider's checkout page.
+pub async fn complete_mock_checkout(
+    State(state): State<Arc<PaymentState>>,
+    Path(provider_ref): Path<String>,
+    Query(input): Query<pb::MockCheckoutRequest>,
+) -> Result<Json<pb::PaymentWebhookReceipt>, PaymentControllerError> {
+    let outcome = match input.outcome.as_deref() {
+        None | Some("succeeded") => PaymentOutcome::Succeeded,
+        Some("failed") => PaymentOutcome::Failed,
+        Some(_) => {
+            return Err(PaymentControllerError::Validation(vec![
+                "outcome must be succeeded or failed".to_string(),
+            ]));
+        }
+    };
+
+    let receipt = state.payment_service
+        .complete_checkout(&state.db, &provider_ref, outcome)
+        .await?;
+
+    Ok(Json(receipt.into()))
+}
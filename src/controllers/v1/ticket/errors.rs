@@ -0,0 +1,87 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use thiserror::Error;
+use tonic::Status;
+
+use crate::services::auth::AuthError;
+use crate::services::payment::PaymentError;
+use crate::services::ticket::TicketError;
+
+#[derive(Debug, Error)]
+pub enum TicketControllerError {
+    #[error("auth error: {0}")]
+    Auth(#[from] AuthError),
+
+    #[error("ticket error: {0}")]
+    Ticket(#[from] TicketError),
+
+    #[error("missing claims")]
+    MissingClaims,
+
+    #[error("validation error: {0:?}")]
+    Validation(Vec<String>),
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    code: u16,
+    messages: Vec<String>,
+}
+
+// gRPC
+impl From<TicketControllerError> for Status {
+    fn from(err: TicketControllerError) -> Self {
+        match err {
+            TicketControllerError::Auth(e) => match e {
+                AuthError::UserNotFound => Status::unauthenticated("user not found"),
+                _ => Status::internal("database error"),
+            },
+            TicketControllerError::Ticket(e) => match e {
+                TicketError::TicketNotFound | TicketError::RouteNotFound => Status::not_found(e.to_string()),
+                TicketError::NotTicketOwner => Status::permission_denied(e.to_string()),
+                TicketError::AmountTooLarge => Status::invalid_argument(e.to_string()),
+                TicketError::IllegalTransition { .. } | TicketError::CancelRequired(_) | TicketError::TripDeparted => {
+                    Status::failed_precondition(e.to_string())
+                }
+                TicketError::Payment(PaymentError::Provider(_)) => Status::unavailable("payment provider error"),
+                TicketError::Payment(_) | TicketError::Database(_) => Status::internal("database error"),
+            },
+            TicketControllerError::MissingClaims => Status::unauthenticated("missing claims"),
+            TicketControllerError::Validation(msgs) => Status::invalid_argument(msgs.join(", ")),
+        }
+    }
+}
+
+// HTTP
+impl IntoResponse for TicketControllerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, messages) = match self {
+            TicketControllerError::Auth(e) => match e {
+                AuthError::UserNotFound => (StatusCode::UNAUTHORIZED, vec!["user not found".to_string()]),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            TicketControllerError::Ticket(e) => match e {
+                TicketError::TicketNotFound | TicketError::RouteNotFound => (StatusCode::NOT_FOUND, vec![e.to_string()]),
+                TicketError::NotTicketOwner => (StatusCode::FORBIDDEN, vec![e.to_string()]),
+                TicketError::AmountTooLarge => (StatusCode::BAD_REQUEST, vec![e.to_string()]),
+                TicketError::IllegalTransition { .. } | TicketError::CancelRequired(_) | TicketError::TripDeparted => {
+                    (StatusCode::CONFLICT, vec![e.to_string()])
+                }
+                TicketError::Payment(PaymentError::Provider(_)) => {
+                    (StatusCode::BAD_GATEWAY, vec!["payment provider error".to_string()])
+                }
+                TicketError::Payment(_) | TicketError::Database(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()])
+                }
+            },
+            TicketControllerError::MissingClaims => (StatusCode::UNAUTHORIZED, vec!["missing claims".to_string()]),
+            TicketControllerError::Validation(msgs) => (StatusCode::BAD_REQUEST, msgs),
+        };
+
+        (status, Json(ErrorResponse { code: status.as_u16(), messages })).into_response()
+    }
+}
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use sea_orm::DatabaseConnection;
+use tonic::{Request, Response, Status};
+
+pub mod errors;
+pub mod status;
+
+use crate::inject::{InjectError, InjectFactory};
+use crate::pb;
+use crate::services::auth::Auth;
+use crate::services::ticket::TicketService;
+
+pub use errors::TicketControllerError;
+pub use status::{get_ticket_history, update_ticket_status};
+
+pub struct TicketState {
+    pub auth_service: Arc<dyn Auth>,
+    pub ticket_service: Arc<dyn TicketService>,
+    pub db: Arc<DatabaseConnection>,
+}
+
+impl TicketState {
+    pub fn new(injector: &dyn InjectFactory) -> Result<Self, InjectError> {
+        Ok(Self {
+            auth_service: injector.auth_service()?,
+            ticket_service: injector.ticket_service()?,
+            db: injector.database()?,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl pb::ticket_service_server::TicketService for TicketState {
+    async fn update_ticket_status(
+        &self,
+        request: Request<pb::UpdateTicketStatusRequest>,
+    ) -> Result<Response<pb::Ticket>, Status> {
+        status::grpc_update_ticket_status(self, request).await
+    }
+
+    async fn get_ticket_history(
+        &self,
+        request: Request<pb::GetTicketHistoryRequest>,
+    ) -> Result<Response<pb::TicketHistory>, Status> {
+        status::grpc_get_ticket_history(self, request).await
+    }
+}
@@ -0,0 +1,93 @@
+use axum::{
+    extract::{Extension, Path, State},
+    Json,
+};
+use libs::jwt::Claims;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::controllers::v1::booking::bookings::DATE_FORMAT;
+use crate::entities::ticket_status_history::Model as TicketStatusHistoryModel;
+use crate::pb;
+use crate::services::ticket::{parse_status, status_name};
+
+use super::{TicketControllerError, TicketState};
+
+impl From<TicketStatusHistoryModel> for pb::TicketStatusChange {
+    fn from(change: TicketStatusHistoryModel) -> Self {
+        pb::TicketStatusChange {
+            from_status: change.from_status.map(|status| status_name(status).to_string()),
+            to_status: status_name(change.to_status).to_string(),
+            actor_id: change.actor_id,
+            changed_at: change.changed_at.format(DATE_FORMAT).to_string(),
+        }
+    }
+}
+
+// HTTP
+
+pub async fn update_ticket_status(
+    State(state): State<Arc<TicketState>>,
+    Extension(claims): Extension<Claims>,
+    Path(ticket_id): Path<i32>,
+    Json(input): Json<pb::TicketStatusInput>,
+) -> Result<Json<pb::Ticket>, TicketControllerError> {
+    Ok(Json(do_update_ticket_status(&state, &claims, ticket_id, &input.status).await?))
+}
+
+pub async fn get_ticket_history(
+    State(state): State<Arc<TicketState>>,
+    Path(ticket_id): Path<i32>,
+) -> Result<Json<pb::TicketHistory>, TicketControllerError> {
+    Ok(Json(do_get_ticket_history(&state, ticket_id).await?))
+}
+
+// gRPC handlers
+
+pub async fn grpc_update_ticket_status(
+    service: &TicketState,
+    request: Request<pb::UpdateTicketStatusRequest>,
+) -> Result<Response<pb::Ticket>, Status> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(TicketControllerError::MissingClaims)?;
+    let input = request.into_inner();
+
+    Ok(Response::new(do_update_ticket_status(service, &claims, input.ticket_id, &input.status).await?))
+}
+
+pub async fn grpc_get_ticket_history(
+    service: &TicketState,
+    request: Request<pb::GetTicketHistoryRequest>,
+) -> Result<Response<pb::TicketHistory>, Status> {
+    Ok(Response::new(do_get_ticket_history(service, request.into_inner().ticket_id).await?))
+}
+
+async fn do_update_ticket_status(
+    state: &TicketState,
+    claims: &Claims,
+    ticket_id: i32,
+    status: &str,
+) -> Result<pb::Ticket, TicketControllerError> {
+    let to = parse_status(status).ok_or_else(|| {
+        TicketControllerError::Validation(vec![format!("unknown ticket status: {status}")])
+    })?;
+
+    let actor = state.auth_service.find_user_by_email(&state.db, &claims.sub).await?;
+    let ticket = state.ticket_service
+        .transition(&state.db, ticket_id, to, Some(actor.user_id))
+        .await?;
+
+    Ok(ticket.into())
+}
+
+async fn do_get_ticket_history(state: &TicketState, ticket_id: i32) -> Result<pb::TicketHistory, TicketControllerError> {
+    let changes = state.ticket_service.history(&state.db, ticket_id).await?;
+
+    Ok(pb::TicketHistory {
+        ticket_id,
+        changes: changes.into_iter().map(pb::TicketStatusChange::from).collect(),
+    })
+}
@@ -0,0 +1,67 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use thiserror::Error;
+use tonic::Status;
+
+use crate::services::journey::JourneyError;
+use crate::services::location::LocationError;
+use crate::services::trip::TripError;
+
+#[derive(Debug, Error)]
+pub enum TripControllerError {
+    #[error("trip error: {0}")]
+    Trip(#[from] TripError),
+
+    #[error("journey error: {0}")]
+    Journey(#[from] JourneyError),
+
+    #[error("location error: {0}")]
+    Location(#[from] LocationError),
+
+    #[error("validation error: {0:?}")]
+    Validation(Vec<String>),
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    code: u16,
+    messages: Vec<String>,
+}
+
+// gRPC
+impl From<TripControllerError> for Status {
+    fn from(err: TripControllerError) -> Self {
+        match err {
+            TripControllerError::Trip(e) => match e {
+                TripError::TripNotFound => Status::not_found(e.to_string()),
+                TripError::InvalidSegment => Status::invalid_argument(e.to_string()),
+                _ => Status::internal("database error"),
+            },
+            TripControllerError::Journey(_) | TripControllerError::Location(_) => Status::internal("database error"),
+            TripControllerError::Validation(msgs) => Status::invalid_argument(msgs.join(", ")),
+        }
+    }
+}
+
+// HTTP
+impl IntoResponse for TripControllerError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, messages) = match self {
+            TripControllerError::Trip(e) => match e {
+                TripError::TripNotFound => (StatusCode::NOT_FOUND, vec![e.to_string()]),
+                TripError::InvalidSegment => (StatusCode::BAD_REQUEST, vec![e.to_string()]),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()]),
+            },
+            TripControllerError::Journey(_) | TripControllerError::Location(_) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, vec!["database error".to_string()])
+            }
+            TripControllerError::Validation(msgs) => (StatusCode::BAD_REQUEST, msgs),
+        };
+
+        (status, Json(ErrorResponse { code: status.as_u16(), messages })).into_response()
+    }
+}
@@ -0,0 +1,132 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::errors::extract_validation_messages;
+use crate::controllers::v1::booking::bookings::DATE_FORMAT;
+use crate::pb;
+use crate::services::journey::{Journey, JourneyQuery, JourneySort};
+
+use super::search::parse_departure;
+use super::{TripControllerError, TripState};
+
+const DEFAULT_MAX_TRANSFERS: u32 = 1;
+const DEFAULT_MIN_CONNECTION_MINUTES: u32 = 15;
+const DEFAULT_LIMIT: u32 = 5;
+
+#[derive(Validate)]
+pub struct PlanJourneyInput {
+    #[validate(length(min = 1, max = 128, message = "from_location must be 1 to 128 characters"))]
+    pub from_location: String,
+
+    #[validate(length(min = 1, max = 128, message = "to_location must be 1 to 128 characters"))]
+    pub to_location: String,
+
+    #[validate(range(max = 3, message = "max_transfers must be at most 3"))]
+    pub max_transfers: u32,
+
+    #[validate(range(max = 1440, message = "min_connection_minutes must be at most 1440"))]
+    pub min_connection_minutes: u32,
+
+    #[validate(range(min = 1, max = 20, message = "limit must be between 1 and 20"))]
+    pub limit: u32,
+}
+
+impl From<Journey> for pb::Journey {
+    fn from(journey: Journey) -> Self {
+        let transfers = journey.transfers();
+        let departure_time = journey.legs.first().map(|leg| leg.start_date.format(DATE_FORMAT).to_string());
+        let arrival_time = journey.legs.last().map(|leg| leg.end_date.format(DATE_FORMAT).to_string());
+
+        pb::Journey {
+            departure_time: departure_time.unwrap_or_default(),
+            arrival_time: arrival_time.unwrap_or_default(),
+            price: journey.price,
+            transfers,
+            legs: journey.legs.into_iter().map(pb::TripSummary::from).collect(),
+        }
+    }
+}
+
+// HTTP
+
+pub async fn plan_journey(
+    State(state): State<Arc<TripState>>,
+    Query(input): Query<pb::PlanJourneyRequest>,
+) -> Result<Json<pb::JourneyPlan>, TripControllerError> {
+    Ok(Json(do_plan_journey(&state, input).await?))
+}
+
+// gRPC handler
+
+pub async fn grpc_plan_journey(
+    service: &TripState,
+    request: Request<pb::PlanJourneyRequest>,
+) -> Result<Response<pb::JourneyPlan>, Status> {
+    Ok(Response::new(do_plan_journey(service, request.into_inner()).await?))
+}
+
+fn query_from_request(input: pb::PlanJourneyRequest) -> Result<JourneyQuery, TripControllerError> {
+    let fields = PlanJourneyInput {
+        from_location: input.from_location.unwrap_or_default().trim().to_string(),
+        to_location: input.to_location.unwrap_or_default().trim().to_string(),
+        max_transfers: input.max_transfers.unwrap_or(DEFAULT_MAX_TRANSFERS),
+        min_connection_minutes: input.min_connection_minutes.unwrap_or(DEFAULT_MIN_CONNECTION_MINUTES),
+        limit: input.limit.unwrap_or(DEFAULT_LIMIT),
+    };
+
+    let mut messages = match fields.validate() {
+        Ok(()) => Vec::new(),
+        Err(e) => extract_validation_messages(e),
+    };
+
+    // Rides that have already left cannot be booked, so the window never
+    // starts in the past.
+    let now = Utc::now().naive_utc();
+    let departure_from = match input.departure_from.as_deref() {
+        Some(value) => parse_departure("departure_from", value, false)
+            .map(|departure_from| departure_from.max(now))
+            .map_err(|msg| messages.push(msg))
+            .ok(),
+        None => Some(now),
+    };
+
+    let sort = match input.sort_by.as_deref() {
+        None | Some("arrival") => Some(JourneySort::Arrival),
+        Some("price") => Some(JourneySort::Price),
+        Some(_) => {
+            messages.push("sort_by must be arrival or price".to_string());
+            None
+        }
+    };
+
+    match (departure_from, sort) {
+        (Some(departure_from), Some(sort)) if messages.is_empty() => Ok(JourneyQuery {
+            from_location: fields.from_location,
+            to_location: fields.to_location,
+            departure_from,
+            max_transfers: fields.max_transfers,
+            min_connection: Duration::minutes(fields.min_connection_minutes.into()),
+            sort,
+            limit: fields.limit as usize,
+        }),
+        _ => Err(TripControllerError::Validation(messages)),
+    }
+}
+
+async fn do_plan_journey(
+    state: &TripState,
+    input: pb::PlanJourneyRequest,
+) -> Result<pb::JourneyPlan, TripControllerError> {
+    let query = query_from_request(input)?;
+    let journeys = state.journey_service.plan(&state.db, &query).await?;
+
+    Ok(pb::JourneyPlan {
+        journeys: journeys.into_iter().map(pb::Journey::from).collect(),
+    })
+}
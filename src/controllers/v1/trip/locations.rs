@@ -0,0 +1,76 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::errors::extract_validation_messages;
+use crate::pb;
+use crate::services::location::LocationSuggestion;
+
+use super::{TripControllerError, TripState};
+
+const DEFAULT_LIMIT: u32 = 10;
+
+#[derive(Validate)]
+pub struct SuggestLocationsInput {
+    #[validate(length(min = 1, max = 64, message = "q must be 1-64 characters"))]
+    pub q: String,
+
+    #[validate(range(min = 1, max = 20, message = "limit must be between 1 and 20"))]
+    pub limit: u32,
+}
+
+impl From<LocationSuggestion> for pb::LocationSuggestion {
+    fn from(location: LocationSuggestion) -> Self {
+        pb::LocationSuggestion {
+            location_id: location.location_id,
+            name: location.name,
+            province: location.province,
+            latitude: location.latitude,
+            longitude: location.longitude,
+            matched: location.matched,
+        }
+    }
+}
+
+// HTTP
+
+pub async fn suggest_locations(
+    State(state): State<Arc<TripState>>,
+    Query(input): Query<pb::SuggestLocationsRequest>,
+) -> Result<Json<pb::LocationSuggestions>, TripControllerError> {
+    Ok(Json(do_suggest_locations(&state, input).await?))
+}
+
+// gRPC handler
+
+pub async fn grpc_suggest_locations(
+    service: &TripState,
+    request: Request<pb::SuggestLocationsRequest>,
+) -> Result<Response<pb::LocationSuggestions>, Status> {
+    Ok(Response::new(do_suggest_locations(service, request.into_inner()).await?))
+}
+
+async fn do_suggest_locations(
+    state: &TripState,
+    input: pb::SuggestLocationsRequest,
+) -> Result<pb::LocationSuggestions, TripControllerError> {
+    let input = SuggestLocationsInput {
+        q: input.q.unwrap_or_default().trim().to_string(),
+        limit: input.limit.unwrap_or(DEFAULT_LIMIT),
+    };
+    input
+        .validate()
+        .map_err(|e| TripControllerError::Validation(extract_validation_messages(e)))?;
+
+    let locations = state.location_service
+        .suggest(&state.db, &input.q, input.limit.into())
+        .await?;
+
+    Ok(pb::LocationSuggestions {
+        locations: locations.into_iter().map(pb::LocationSuggestion::from).collect(),
+    })
+}
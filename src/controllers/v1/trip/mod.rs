@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use sea_orm::DatabaseConnection;
+use tonic::{Request, Response, Status};
+
+pub mod errors;
+pub mod journeys;
+pub mod locations;
+pub mod search;
+pub mod seats;
+
+use crate::inject::{InjectError, InjectFactory};
+use crate::pb;
+use crate::services::journey::JourneyService;
+use crate::services::location::LocationService;
+use crate::services::trip::TripService;
+
+pub use errors::TripControllerError;
+pub use journeys::plan_journey;
+pub use locations::suggest_locations;
+pub use search::search_trips;
+pub use seats::get_seat_map;
+
+pub struct TripState {
+    pub trip_service: Arc<dyn TripService>,
+    pub journey_service: Arc<dyn JourneyService>,
+    pub location_service: Arc<dyn LocationService>,
+    pub db: Arc<DatabaseConnection>,
+}
+
+impl TripState {
+    pub fn new(injector: &dyn InjectFactory) -> Result<Self, InjectError> {
+        Ok(Self {
+            trip_service: injector.trip_service()?,
+            journey_service: injector.journey_service()?,
+            location_service: injector.location_service()?,
+            db: injector.database()?,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl pb::trip_service_server::TripService for TripState {
+    async fn search_trips(
+        &self,
+        request: Request<pb::SearchTripsRequest>,
+    ) -> Result<Response<pb::TripPage>, Status> {
+        search::grpc_search_trips(self, request).await
+    }
+
+    async fn get_seat_map(
+        &self,
+        request: Request<pb::GetSeatMapRequest>,
+    ) -> Result<Response<pb::SeatMap>, Status> {
+        seats::grpc_get_seat_map(self, request).await
+    }
+
+    async fn plan_journey(
+        &self,
+        request: Request<pb::PlanJourneyRequest>,
+    ) -> Result<Response<pb::JourneyPlan>, Status> {
+        journeys::grpc_plan_journey(self, request).await
+    }
+
+    async fn suggest_locations(
+        &self,
+        request: Request<pb::SuggestLocationsRequest>,
+    ) -> Result<Response<pb::LocationSuggestions>, Status> {
+        locations::grpc_suggest_locations(self, request).await
+    }
+}
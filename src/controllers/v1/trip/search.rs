@@ -0,0 +1,137 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+use crate::controllers::v1::auth::errors::extract_validation_messages;
+use crate::controllers::v1::booking::bookings::DATE_FORMAT;
+use crate::pb;
+use crate::services::trip::{TripAvailability, TripSearch, TripSort};
+
+use super::{TripControllerError, TripState};
+
+const DEFAULT_PAGE_SIZE: u32 = 20;
+
+#[derive(Validate)]
+pub struct SearchTripsInput {
+    #[validate(range(min = 1, message = "page starts at 1"))]
+    pub page: u32,
+
+    #[validate(range(min = 1, max = 100, message = "page_size must be between 1 and 100"))]
+    pub page_size: u32,
+}
+
+impl From<TripAvailability> for pb::TripSummary {
+    fn from(trip: TripAvailability) -> Self {
+        pb::TripSummary {
+            trip_id: trip.trip_id,
+            route_id: trip.route_id,
+            bus_id: trip.bus_id,
+            from_location: trip.from_location,
+            to_location: trip.to_location,
+            departure_time: trip.start_date.format(DATE_FORMAT).to_string(),
+            arrival_time: trip.end_date.format(DATE_FORMAT).to_string(),
+            bus_name: trip.bus_name,
+            license_plate: trip.license_plate,
+            seat_count: trip.seat_count,
+            available_seats: trip.available_seats,
+            min_price: trip.min_price,
+            max_price: trip.max_price,
+            from_stop: trip.from_stop,
+            to_stop: trip.to_stop,
+        }
+    }
+}
+
+// HTTP
+
+pub async fn search_trips(
+    State(state): State<Arc<TripState>>,
+    Query(input): Query<pb::SearchTripsRequest>,
+) -> Result<Json<pb::TripPage>, TripControllerError> {
+    Ok(Json(do_search_trips(&state, input).await?))
+}
+
+// gRPC handler
+
+pub async fn grpc_search_trips(
+    service: &TripState,
+    request: Request<pb::SearchTripsRequest>,
+) -> Result<Response<pb::TripPage>, Status> {
+    Ok(Response::new(do_search_trips(service, request.into_inner()).await?))
+}
+
+/// Parses a date or date-time. A plain date means its midnight, or the
+/// following midnight when it closes a range so that the whole day is in.
+pub fn parse_departure(field: &str, value: &str, end: bool) -> Result<NaiveDateTime, String> {
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(value, DATE_FORMAT) {
+        return Ok(date_time);
+    }
+
+    match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date) if end => Ok(date.and_time(Default::default()) + Duration::days(1)),
+        Ok(date) => Ok(date.and_time(Default::default())),
+        Err(_) => Err(format!("{field} must be YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS")),
+    }
+}
+
+fn search_from_request(input: &pb::SearchTripsRequest) -> Result<TripSearch, TripControllerError> {
+    let page = input.page.unwrap_or(1);
+    let page_size = input.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+
+    let mut messages = match (SearchTripsInput { page, page_size }).validate() {
+        Ok(()) => Vec::new(),
+        Err(e) => extract_validation_messages(e),
+    };
+
+    let departure_from = match input.departure_from.as_deref() {
+        Some(value) => parse_departure("departure_from", value, false).map_err(|msg| messages.push(msg)).ok(),
+        None => Some(Utc::now().naive_utc()),
+    };
+    let departure_to = match input.departure_to.as_deref() {
+        Some(value) => parse_departure("departure_to", value, true).map_err(|msg| messages.push(msg)).ok(),
+        None => None,
+    };
+
+    let sort = match input.sort_by.as_deref() {
+        None | Some("departure") => Some(TripSort::Departure),
+        Some("price") => Some(TripSort::Price),
+        Some(_) => {
+            messages.push("sort_by must be departure or price".to_string());
+            None
+        }
+    };
+
+    match (departure_from, sort) {
+        (Some(departure_from), Some(sort)) if messages.is_empty() => Ok(TripSearch {
+            from_location: input.from_location.clone(),
+            to_location: input.to_location.clone(),
+            departure_from,
+            departure_to,
+            sort,
+            descending: input.descending.unwrap_or(false),
+            page: page.into(),
+            page_size: page_size.into(),
+        }),
+        _ => Err(TripControllerError::Validation(messages)),
+    }
+}
+
+async fn do_search_trips(
+    state: &TripState,
+    input: pb::SearchTripsRequest,
+) -> Result<pb::TripPage, TripControllerError> {
+    let search = search_from_request(&input)?;
+    let page = state.trip_service.search(&state.db, &search).await?;
+
+    Ok(pb::TripPage {
+        trips: page.trips.into_iter().map(pb::TripSummary::from).collect(),
+        total: page.total.try_into().unwrap_or(u32::MAX),
+        page: search.page as u32,
+        page_size: search.page_size as u32,
+    })
+}
@@ -0,0 +1,89 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::controllers::v1::booking::bookings::DATE_FORMAT;
+use crate::pb;
+use crate::services::route::Segment;
+use crate::services::trip::{SeatStatus, TripSeat, TripStop};
+
+use super::{TripControllerError, TripState};
+
+impl From<TripSeat> for pb::TripSeat {
+    fn from(seat: TripSeat) -> Self {
+        let status = match seat.status {
+            SeatStatus::Available => "available",
+            SeatStatus::Held => "held",
+            SeatStatus::Sold => "sold",
+        };
+
+        pb::TripSeat {
+            seat_id: seat.seat_id,
+            name: seat.name,
+            price: seat.price,
+            fare: seat.fare,
+            status: status.to_string(),
+            row: seat.row,
+            column: seat.column,
+            deck: seat.deck,
+        }
+    }
+}
+
+impl From<TripStop> for pb::TripStop {
+    fn from(stop: TripStop) -> Self {
+        pb::TripStop {
+            stop: stop.stop_order,
+            location: stop.location,
+            time: stop.time.format(DATE_FORMAT).to_string(),
+        }
+    }
+}
+
+// HTTP
+
+pub async fn get_seat_map(
+    State(state): State<Arc<TripState>>,
+    Path(trip_id): Path<i32>,
+    Query(options): Query<pb::SeatMapOptions>,
+) -> Result<Json<pb::SeatMap>, TripControllerError> {
+    let segment = Segment {
+        from_stop: options.from_stop,
+        to_stop: options.to_stop,
+    };
+
+    Ok(Json(do_get_seat_map(&state, trip_id, segment).await?))
+}
+
+// gRPC handler
+
+pub async fn grpc_get_seat_map(
+    service: &TripState,
+    request: Request<pb::GetSeatMapRequest>,
+) -> Result<Response<pb::SeatMap>, Status> {
+    let input = request.into_inner();
+    let segment = Segment {
+        from_stop: input.from_stop,
+        to_stop: input.to_stop,
+    };
+
+    Ok(Response::new(do_get_seat_map(service, input.trip_id, segment).await?))
+}
+
+async fn do_get_seat_map(
+    state: &TripState,
+    trip_id: i32,
+    segment: Segment,
+) -> Result<pb::SeatMap, TripControllerError> {
+    let seats = state.trip_service.seat_map(&state.db, trip_id, segment).await?;
+    let stops = state.trip_service.trip_stops(&state.db, trip_id).await?;
+
+    Ok(pb::SeatMap {
+        trip_id,
+        seats: seats.into_iter().map(pb::TripSeat::from).collect(),
+        stops: stops.into_iter().map(pb::TripStop::from).collect(),
+    })
+}
@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderValue, header::CACHE_CONTROL},
+    response::IntoResponse,
+};
+use libs::jwt::KeySet;
+
+pub async fn jwks(State(keys): State<Arc<KeySet>>) -> impl IntoResponse {
+    (
+        [(CACHE_CONTROL, HeaderValue::from_static("public, max-age=300"))],
+        Json(keys.jwks()),
+    )
+}
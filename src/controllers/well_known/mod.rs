@@ -0,0 +1,14 @@
+pub mod jwks;
+
+use std::sync::Arc;
+
+use axum::{Router, routing::get};
+
+use crate::services::jwt::JWTService;
+
+/// Unversioned discovery documents served from the site root.
+pub fn http_router(jwt_service: Arc<dyn JWTService>) -> Router {
+    Router::new()
+        .route("/.well-known/jwks.json", get(jwks::jwks))
+        .with_state(jwt_service.key_set())
+}
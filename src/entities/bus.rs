@@ -5,7 +5,7 @@ use sea_orm::entity::prelude::*;
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "bus")]
 pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false)]
+    #[sea_orm(primary_key)]
     pub bus_id: i32,
     pub name: Option<String>,
     pub license_plate: String,
@@ -14,12 +14,20 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::schedule::Entity")]
+    Schedule,
     #[sea_orm(has_many = "super::seat::Entity")]
     Seat,
     #[sea_orm(has_many = "super::trip::Entity")]
     Trip,
 }
 
+impl Related<super::schedule::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Schedule.def()
+    }
+}
+
 impl Related<super::seat::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Seat.def()
@@ -3,8 +3,35 @@
 pub mod prelude;
 
 pub mod bus;
+pub mod email_verification;
+pub mod location;
+pub mod location_alias;
+pub mod login_attempt;
+pub mod mfa_challenge;
+pub mod mfa_recovery_code;
+pub mod password_reset;
+pub mod payment_event;
+pub mod payment_intent;
+pub mod payment_intent_ticket;
+pub mod policy;
+pub mod promo_code;
+pub mod promo_code_route;
+pub mod promo_redemption;
+pub mod refresh_token;
+pub mod revoked_token;
+pub mod role;
+pub mod role_policy;
 pub mod route;
+pub mod route_refund_tier;
+pub mod route_stop;
+pub mod schedule;
+pub mod schedule_exception;
+pub mod sea_orm_active_enums;
 pub mod seat;
+pub mod seat_hold;
+pub mod subject_revocation;
 pub mod ticket;
+pub mod ticket_status_history;
 pub mod trip;
 pub mod user;
+pub mod user_totp;
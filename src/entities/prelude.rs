@@ -1,8 +1,34 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
 
 pub use super::bus::Entity as Bus;
+pub use super::email_verification::Entity as EmailVerification;
+pub use super::location::Entity as Location;
+pub use super::location_alias::Entity as LocationAlias;
+pub use super::login_attempt::Entity as LoginAttempt;
+pub use super::mfa_challenge::Entity as MfaChallenge;
+pub use super::mfa_recovery_code::Entity as MfaRecoveryCode;
+pub use super::password_reset::Entity as PasswordReset;
+pub use super::payment_event::Entity as PaymentEvent;
+pub use super::payment_intent::Entity as PaymentIntent;
+pub use super::payment_intent_ticket::Entity as PaymentIntentTicket;
+pub use super::policy::Entity as Policy;
+pub use super::promo_code::Entity as PromoCode;
+pub use super::promo_code_route::Entity as PromoCodeRoute;
+pub use super::promo_redemption::Entity as PromoRedemption;
+pub use super::refresh_token::Entity as RefreshToken;
+pub use super::revoked_token::Entity as RevokedToken;
+pub use super::role::Entity as Role;
+pub use super::role_policy::Entity as RolePolicy;
 pub use super::route::Entity as Route;
+pub use super::route_refund_tier::Entity as RouteRefundTier;
+pub use super::route_stop::Entity as RouteStop;
+pub use super::schedule::Entity as Schedule;
+pub use super::schedule_exception::Entity as ScheduleException;
 pub use super::seat::Entity as Seat;
+pub use super::seat_hold::Entity as SeatHold;
+pub use super::subject_revocation::Entity as SubjectRevocation;
 pub use super::ticket::Entity as Ticket;
+pub use super::ticket_status_history::Entity as TicketStatusHistory;
 pub use super::trip::Entity as Trip;
 pub use super::user::Entity as User;
+pub use super::user_totp::Entity as UserTotp;
@@ -5,20 +5,70 @@ use sea_orm::entity::prelude::*;
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "route")]
 pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false)]
+    #[sea_orm(primary_key)]
     pub route_id: i32,
     pub from_location: String,
     pub to_location: String,
+    pub from_location_id: i32,
+    pub to_location_id: i32,
     pub base_price: i32,
     pub deleted: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::location::Entity",
+        from = "Column::FromLocationId",
+        to = "super::location::Column::LocationId",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    FromLocation,
+    #[sea_orm(
+        belongs_to = "super::location::Entity",
+        from = "Column::ToLocationId",
+        to = "super::location::Column::LocationId",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    ToLocation,
+    #[sea_orm(has_many = "super::promo_code_route::Entity")]
+    PromoCodeRoute,
+    #[sea_orm(has_many = "super::route_refund_tier::Entity")]
+    RouteRefundTier,
+    #[sea_orm(has_many = "super::route_stop::Entity")]
+    RouteStop,
+    #[sea_orm(has_many = "super::schedule::Entity")]
+    Schedule,
     #[sea_orm(has_many = "super::trip::Entity")]
     Trip,
 }
 
+impl Related<super::promo_code_route::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PromoCodeRoute.def()
+    }
+}
+
+impl Related<super::route_refund_tier::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RouteRefundTier.def()
+    }
+}
+
+impl Related<super::route_stop::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RouteStop.def()
+    }
+}
+
+impl Related<super::schedule::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Schedule.def()
+    }
+}
+
 impl Related<super::trip::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Trip.def()
@@ -5,12 +5,15 @@ use sea_orm::entity::prelude::*;
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "seat")]
 pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false)]
+    #[sea_orm(primary_key)]
     pub seat_id: i32,
     pub bus_id: i32,
     pub price: i32,
     pub deleted: bool,
     pub name: String,
+    pub seat_row: Option<i32>,
+    pub seat_column: Option<i32>,
+    pub deck: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -23,6 +26,8 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Bus,
+    #[sea_orm(has_many = "super::seat_hold::Entity")]
+    SeatHold,
     #[sea_orm(has_many = "super::ticket::Entity")]
     Ticket,
 }
@@ -33,6 +38,12 @@ impl Related<super::bus::Entity> for Entity {
     }
 }
 
+impl Related<super::seat_hold::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SeatHold.def()
+    }
+}
+
 impl Related<super::ticket::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Ticket.def()
@@ -1,13 +1,14 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
 
+use super::sea_orm_active_enums::TicketStatus;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "ticket")]
 pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false)]
+    #[sea_orm(primary_key)]
     pub ticket_id: i32,
-    pub status: i32,
+    pub status: TicketStatus,
     pub price: i32,
     pub trip_id: i32,
     pub seat_id: i32,
@@ -15,10 +16,25 @@ pub struct Model {
     pub user_id: i32,
     pub from_location: Option<String>,
     pub to_location: Option<String>,
+    pub refund_amount: Option<i32>,
+    pub from_stop: i32,
+    pub to_stop: i32,
+    pub discount: i32,
+    pub promo_redemption_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::payment_intent_ticket::Entity")]
+    PaymentIntentTicket,
+    #[sea_orm(
+        belongs_to = "super::promo_redemption::Entity",
+        from = "Column::PromoRedemptionId",
+        to = "super::promo_redemption::Column::PromoRedemptionId",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    PromoRedemption,
     #[sea_orm(
         belongs_to = "super::seat::Entity",
         from = "Column::SeatId",
@@ -27,6 +43,8 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Seat,
+    #[sea_orm(has_many = "super::ticket_status_history::Entity")]
+    TicketStatusHistory,
     #[sea_orm(
         belongs_to = "super::trip::Entity",
         from = "Column::TripId",
@@ -45,12 +63,30 @@ pub enum Relation {
     User,
 }
 
+impl Related<super::payment_intent_ticket::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PaymentIntentTicket.def()
+    }
+}
+
+impl Related<super::promo_redemption::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PromoRedemption.def()
+    }
+}
+
 impl Related<super::seat::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Seat.def()
     }
 }
 
+impl Related<super::ticket_status_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TicketStatusHistory.def()
+    }
+}
+
 impl Related<super::trip::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Trip.def()
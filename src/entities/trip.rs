@@ -5,12 +5,14 @@ use sea_orm::entity::prelude::*;
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "trip")]
 pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false)]
+    #[sea_orm(primary_key)]
     pub trip_id: i32,
     pub route_id: i32,
     pub start_date: DateTime,
     pub end_date: DateTime,
     pub bus_id: i32,
+    pub cancelled: bool,
+    pub schedule_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -31,6 +33,16 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Route,
+    #[sea_orm(
+        belongs_to = "super::schedule::Entity",
+        from = "Column::ScheduleId",
+        to = "super::schedule::Column::ScheduleId",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Schedule,
+    #[sea_orm(has_many = "super::seat_hold::Entity")]
+    SeatHold,
     #[sea_orm(has_many = "super::ticket::Entity")]
     Ticket,
 }
@@ -47,6 +59,18 @@ impl Related<super::route::Entity> for Entity {
     }
 }
 
+impl Related<super::schedule::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Schedule.def()
+    }
+}
+
+impl Related<super::seat_hold::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SeatHold.def()
+    }
+}
+
 impl Related<super::ticket::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Ticket.def()
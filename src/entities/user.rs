@@ -5,20 +5,104 @@ use sea_orm::entity::prelude::*;
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "user")]
 pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false)]
+    #[sea_orm(primary_key)]
     pub user_id: i32,
+    #[sea_orm(unique)]
     pub email: String,
     pub fullname: String,
     pub phone: String,
     pub address: String,
     pub role_id: i32,
     pub password: String,
+    pub email_verified: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::email_verification::Entity")]
+    EmailVerification,
+    #[sea_orm(has_many = "super::mfa_challenge::Entity")]
+    MfaChallenge,
+    #[sea_orm(has_many = "super::mfa_recovery_code::Entity")]
+    MfaRecoveryCode,
+    #[sea_orm(has_many = "super::password_reset::Entity")]
+    PasswordReset,
+    #[sea_orm(has_many = "super::payment_intent::Entity")]
+    PaymentIntent,
+    #[sea_orm(has_many = "super::promo_redemption::Entity")]
+    PromoRedemption,
+    #[sea_orm(has_many = "super::refresh_token::Entity")]
+    RefreshToken,
+    #[sea_orm(
+        belongs_to = "super::role::Entity",
+        from = "Column::RoleId",
+        to = "super::role::Column::RoleId",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Role,
+    #[sea_orm(has_many = "super::seat_hold::Entity")]
+    SeatHold,
     #[sea_orm(has_many = "super::ticket::Entity")]
     Ticket,
+    #[sea_orm(has_many = "super::ticket_status_history::Entity")]
+    TicketStatusHistory,
+    #[sea_orm(has_one = "super::user_totp::Entity")]
+    UserTotp,
+}
+
+impl Related<super::email_verification::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::EmailVerification.def()
+    }
+}
+
+impl Related<super::mfa_challenge::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MfaChallenge.def()
+    }
+}
+
+impl Related<super::mfa_recovery_code::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MfaRecoveryCode.def()
+    }
+}
+
+impl Related<super::password_reset::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PasswordReset.def()
+    }
+}
+
+impl Related<super::payment_intent::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PaymentIntent.def()
+    }
+}
+
+impl Related<super::promo_redemption::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PromoRedemption.def()
+    }
+}
+
+impl Related<super::refresh_token::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RefreshToken.def()
+    }
+}
+
+impl Related<super::role::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Role.def()
+    }
+}
+
+impl Related<super::seat_hold::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SeatHold.def()
+    }
 }
 
 impl Related<super::ticket::Entity> for Entity {
@@ -27,4 +111,16 @@ impl Related<super::ticket::Entity> for Entity {
     }
 }
 
+impl Related<super::ticket_status_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TicketStatusHistory.def()
+    }
+}
+
+impl Related<super::user_totp::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserTotp.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,10 +1,35 @@
 use std::sync::{Arc, OnceLock};
 
+use libs::jwt::{JwtKey, KeyError, KeySet, KeyStatus, parse_algorithm};
 use sea_orm::DatabaseConnection;
 use thiserror::Error;
 
-use crate::config::{Config, ConfigError};
+use crate::config::{Config, ConfigError, MailerKind, PaymentProviderKind, ThrottleStoreKind};
+use crate::services::auth::{Auth, AuthImpl};
+use crate::services::booking::{BookingService, BookingServiceImpl};
+use crate::services::bus::{BusService, BusServiceImpl};
+use crate::services::journey::{JourneyService, JourneyServiceImpl};
 use crate::services::jwt::{JWTService, JWTServiceImpl};
+use crate::services::location::{LocationService, LocationServiceImpl};
+use crate::services::login_throttle::{
+    AttemptStore, LoginThrottlePolicy, LoginThrottleService, LoginThrottleServiceImpl, MemoryAttemptStore,
+    PostgresAttemptStore,
+};
+use crate::services::mailer::{FileMailer, LogMailer, Mailer};
+use crate::services::mfa::{MfaService, MfaServiceImpl};
+use crate::services::opaque_token;
+use crate::services::password::{PasswordService, PasswordServiceError, PasswordServiceImpl};
+use crate::services::password_reset::{PasswordResetService, PasswordResetServiceImpl};
+use crate::services::payment::{MockProvider, PaymentProvider, PaymentService, PaymentServiceImpl};
+use crate::services::pricing::{PricingService, PricingServiceImpl};
+use crate::services::refresh_token::{RefreshTokenService, RefreshTokenServiceImpl};
+use crate::services::registration::{RegistrationService, RegistrationServiceImpl};
+use crate::services::revocation::{RevocationService, RevocationServiceImpl};
+use crate::services::role::{RoleService, RoleServiceImpl};
+use crate::services::route::{RouteService, RouteServiceImpl};
+use crate::services::schedule::{ScheduleService, ScheduleServiceImpl};
+use crate::services::ticket::{TicketService, TicketServiceImpl};
+use crate::services::trip::{TripService, TripServiceImpl};
 
 #[derive(Debug, Error)]
 pub enum InjectError {
@@ -12,6 +37,10 @@ pub enum InjectError {
     Config(#[from] ConfigError),
     #[error("database error: {0}")]
     Database(#[from] sea_orm::DbErr),
+    #[error("jwt key error: {0}")]
+    JwtKeys(#[from] KeyError),
+    #[error("password service error: {0}")]
+    PasswordService(#[from] PasswordServiceError),
     #[error("inject not initialized")]
     NotInitialized,
 }
@@ -20,12 +49,48 @@ pub trait InjectFactory {
     fn config(&self) -> Result<Arc<Config>, InjectError>;
     fn jwt_service(&self) -> Result<Arc<dyn JWTService>, InjectError>;
     fn database(&self) -> Result<Arc<DatabaseConnection>, InjectError>;
+    fn refresh_token_service(&self) -> Result<Arc<dyn RefreshTokenService>, InjectError>;
+    fn revocation_service(&self) -> Result<Arc<dyn RevocationService>, InjectError>;
+    fn auth_service(&self) -> Result<Arc<dyn Auth>, InjectError>;
+    fn role_service(&self) -> Result<Arc<dyn RoleService>, InjectError>;
+    fn registration_service(&self) -> Result<Arc<dyn RegistrationService>, InjectError>;
+    fn password_reset_service(&self) -> Result<Arc<dyn PasswordResetService>, InjectError>;
+    fn login_throttle_service(&self) -> Result<Arc<dyn LoginThrottleService>, InjectError>;
+    fn mfa_service(&self) -> Result<Arc<dyn MfaService>, InjectError>;
+    fn booking_service(&self) -> Result<Arc<dyn BookingService>, InjectError>;
+    fn trip_service(&self) -> Result<Arc<dyn TripService>, InjectError>;
+    fn ticket_service(&self) -> Result<Arc<dyn TicketService>, InjectError>;
+    fn route_service(&self) -> Result<Arc<dyn RouteService>, InjectError>;
+    fn bus_service(&self) -> Result<Arc<dyn BusService>, InjectError>;
+    fn schedule_service(&self) -> Result<Arc<dyn ScheduleService>, InjectError>;
+    fn journey_service(&self) -> Result<Arc<dyn JourneyService>, InjectError>;
+    fn location_service(&self) -> Result<Arc<dyn LocationService>, InjectError>;
+    fn payment_service(&self) -> Result<Arc<dyn PaymentService>, InjectError>;
+    fn pricing_service(&self) -> Result<Arc<dyn PricingService>, InjectError>;
 }
 
 pub struct InjectFactoryImpl {
     config: OnceLock<Arc<Config>>,
     jwt_service: OnceLock<Arc<dyn JWTService>>,
     database: OnceLock<Arc<DatabaseConnection>>,
+    refresh_token_service: OnceLock<Arc<dyn RefreshTokenService>>,
+    revocation_service: OnceLock<Arc<dyn RevocationService>>,
+    auth_service: OnceLock<Arc<dyn Auth>>,
+    role_service: OnceLock<Arc<dyn RoleService>>,
+    registration_service: OnceLock<Arc<dyn RegistrationService>>,
+    password_reset_service: OnceLock<Arc<dyn PasswordResetService>>,
+    login_throttle_service: OnceLock<Arc<dyn LoginThrottleService>>,
+    mfa_service: OnceLock<Arc<dyn MfaService>>,
+    booking_service: OnceLock<Arc<dyn BookingService>>,
+    trip_service: OnceLock<Arc<dyn TripService>>,
+    ticket_service: OnceLock<Arc<dyn TicketService>>,
+    route_service: OnceLock<Arc<dyn RouteService>>,
+    bus_service: OnceLock<Arc<dyn BusService>>,
+    schedule_service: OnceLock<Arc<dyn ScheduleService>>,
+    journey_service: OnceLock<Arc<dyn JourneyService>>,
+    location_service: OnceLock<Arc<dyn LocationService>>,
+    payment_service: OnceLock<Arc<dyn PaymentService>>,
+    pricing_service: OnceLock<Arc<dyn PricingService>>,
 }
 
 impl InjectFactoryImpl {
@@ -33,20 +98,160 @@ impl InjectFactoryImpl {
         let config = Arc::new(Config::from_env()?);
 
         let jwt_service: Arc<dyn JWTService> =
-            Arc::new(JWTServiceImpl::new(config.jwt_secret.clone()));
+            Arc::new(JWTServiceImpl::new(Arc::new(build_key_set(&config)?)));
+
+        let password_service: Arc<dyn PasswordService> = Arc::new(PasswordServiceImpl::new(
+            config.password_hash_memory_kib,
+            config.password_hash_iterations,
+            config.password_hash_parallelism,
+        )?);
+
+        let refresh_token_service: Arc<dyn RefreshTokenService> =
+            Arc::new(RefreshTokenServiceImpl::new(config.refresh_token_ttl));
+
+        let revocation_service: Arc<dyn RevocationService> = Arc::new(RevocationServiceImpl::new());
+
+        let auth_service: Arc<dyn Auth> = Arc::new(AuthImpl::new(password_service.clone()));
+
+        let role_service: Arc<dyn RoleService> = Arc::new(RoleServiceImpl::new());
+
+        let mailer: Arc<dyn Mailer> = match config.mailer {
+            MailerKind::Log => Arc::new(LogMailer::new(config.mail_from.clone())),
+            MailerKind::File => Arc::new(FileMailer::new(config.mail_from.clone(), &config.mail_dir)),
+        };
+
+        let registration_service: Arc<dyn RegistrationService> = Arc::new(RegistrationServiceImpl::new(
+            password_service.clone(),
+            mailer.clone(),
+            config.public_url.clone(),
+            config.email_verification_ttl,
+        ));
+
+        let password_reset_service: Arc<dyn PasswordResetService> = Arc::new(PasswordResetServiceImpl::new(
+            password_service,
+            mailer,
+            config.password_reset_url.clone(),
+            config.password_reset_ttl,
+        ));
+
+        let route_service: Arc<dyn RouteService> = Arc::new(RouteServiceImpl::new());
+        let bus_service: Arc<dyn BusService> = Arc::new(BusServiceImpl::new());
+        let schedule_service: Arc<dyn ScheduleService> =
+            Arc::new(ScheduleServiceImpl::new(config.schedule_horizon_days));
+        let journey_service: Arc<dyn JourneyService> =
+            Arc::new(JourneyServiceImpl::new(config.journey_window_hours));
+        let location_service: Arc<dyn LocationService> = Arc::new(LocationServiceImpl::new());
+        let pricing_service: Arc<dyn PricingService> = Arc::new(PricingServiceImpl::new());
+        let booking_service: Arc<dyn BookingService> =
+            Arc::new(BookingServiceImpl::new(config.seat_hold_ttl, pricing_service.clone()));
+
+        let payment_provider: Arc<dyn PaymentProvider> = match config.payment_provider {
+            PaymentProviderKind::Mock => Arc::new(MockProvider::new(
+                config.payment_webhook_secret.clone().unwrap_or_else(|| opaque_token::generate(32)),
+                config.public_url.clone(),
+                config.payment_mock_checkout,
+            )),
+        };
+        let payment_service: Arc<dyn PaymentService> = Arc::new(PaymentServiceImpl::new(
+            payment_provider,
+            config.payment_currency.clone(),
+            config.payment_timeout,
+        ));
+
+        let trip_service: Arc<dyn TripService> = Arc::new(TripServiceImpl::new(payment_service.clone()));
+        let ticket_service: Arc<dyn TicketService> = Arc::new(TicketServiceImpl::new(payment_service.clone()));
 
         // Connect to database
         let db = sea_orm::Database::connect(&config.database_url).await?;
         let database = Arc::new(db);
 
+        let attempt_store: Arc<dyn AttemptStore> = match config.login_throttle_store {
+            ThrottleStoreKind::Memory => Arc::new(MemoryAttemptStore::new()),
+            ThrottleStoreKind::Postgres => Arc::new(PostgresAttemptStore::new(database.clone())),
+        };
+
+        let mfa_service: Arc<dyn MfaService> = Arc::new(MfaServiceImpl::new(
+            config.mfa_issuer.clone(),
+            config.mfa_challenge_ttl,
+            attempt_store.clone(),
+            config.login_failure_window,
+            config.login_lockout,
+        ));
+
+        let login_throttle_service: Arc<dyn LoginThrottleService> = Arc::new(LoginThrottleServiceImpl::new(
+            attempt_store,
+            LoginThrottlePolicy {
+                max_account_failures: config.login_max_account_failures,
+                max_ip_failures: config.login_max_ip_failures,
+                failure_window: config.login_failure_window,
+                lockout: config.login_lockout,
+            },
+        ));
+
         Ok(Self {
             config: OnceLock::from(config),
             jwt_service: OnceLock::from(jwt_service),
             database: OnceLock::from(database),
+            refresh_token_service: OnceLock::from(refresh_token_service),
+            revocation_service: OnceLock::from(revocation_service),
+            auth_service: OnceLock::from(auth_service),
+            role_service: OnceLock::from(role_service),
+            registration_service: OnceLock::from(registration_service),
+            password_reset_service: OnceLock::from(password_reset_service),
+            login_throttle_service: OnceLock::from(login_throttle_service),
+            mfa_service: OnceLock::from(mfa_service),
+            booking_service: OnceLock::from(booking_service),
+            trip_service: OnceLock::from(trip_service),
+            ticket_service: OnceLock::from(ticket_service),
+            route_service: OnceLock::from(route_service),
+            bus_service: OnceLock::from(bus_service),
+            schedule_service: OnceLock::from(schedule_service),
+            journey_service: OnceLock::from(journey_service),
+            location_service: OnceLock::from(location_service),
+            payment_service: OnceLock::from(payment_service),
+            pricing_service: OnceLock::from(pricing_service),
         })
     }
 }
 
+/// PEM keys from `JWT_KEYS` sign and verify; `JWT_SECRET` alone keeps the
+/// legacy HS256 setup, and next to `JWT_KEYS` it only verifies older tokens.
+fn build_key_set(config: &Config) -> Result<KeySet, KeyError> {
+    if config.jwt_keys.is_empty() {
+        return Ok(KeySet::from_secret(config.jwt_secret.as_deref().unwrap_or_default()));
+    }
+
+    let mut keys = config
+        .jwt_keys
+        .iter()
+        .map(|spec| {
+            let status = if config.jwt_retired_kids.contains(&spec.kid) {
+                KeyStatus::Retired
+            } else {
+                KeyStatus::Verify
+            };
+            JwtKey::from_pem_file(&spec.kid, parse_algorithm(&spec.algorithm)?, &spec.path)
+                .map(|key| key.with_status(status))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let active_kid = match &config.jwt_active_kid {
+        Some(kid) => kid.clone(),
+        None => keys
+            .iter()
+            .rev()
+            .find(|key| key.status() != KeyStatus::Retired)
+            .map(|key| key.kid().to_string())
+            .unwrap_or_default(),
+    };
+
+    if let Some(secret) = &config.jwt_secret {
+        keys.push(JwtKey::from_secret("default", secret));
+    }
+
+    KeySet::new(keys, &active_kid)
+}
+
 impl InjectFactory for InjectFactoryImpl {
     fn config(&self) -> Result<Arc<Config>, InjectError> {
         self.config
@@ -68,4 +273,130 @@ impl InjectFactory for InjectFactoryImpl {
             .cloned()
             .ok_or(InjectError::NotInitialized)
     }
+
+    fn refresh_token_service(&self) -> Result<Arc<dyn RefreshTokenService>, InjectError> {
+        self.refresh_token_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn revocation_service(&self) -> Result<Arc<dyn RevocationService>, InjectError> {
+        self.revocation_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn auth_service(&self) -> Result<Arc<dyn Auth>, InjectError> {
+        self.auth_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn role_service(&self) -> Result<Arc<dyn RoleService>, InjectError> {
+        self.role_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn registration_service(&self) -> Result<Arc<dyn RegistrationService>, InjectError> {
+        self.registration_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn password_reset_service(&self) -> Result<Arc<dyn PasswordResetService>, InjectError> {
+        self.password_reset_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn login_throttle_service(&self) -> Result<Arc<dyn LoginThrottleService>, InjectError> {
+        self.login_throttle_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn mfa_service(&self) -> Result<Arc<dyn MfaService>, InjectError> {
+        self.mfa_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn booking_service(&self) -> Result<Arc<dyn BookingService>, InjectError> {
+        self.booking_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn trip_service(&self) -> Result<Arc<dyn TripService>, InjectError> {
+        self.trip_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn ticket_service(&self) -> Result<Arc<dyn TicketService>, InjectError> {
+        self.ticket_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn route_service(&self) -> Result<Arc<dyn RouteService>, InjectError> {
+        self.route_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn bus_service(&self) -> Result<Arc<dyn BusService>, InjectError> {
+        self.bus_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn schedule_service(&self) -> Result<Arc<dyn ScheduleService>, InjectError> {
+        self.schedule_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn journey_service(&self) -> Result<Arc<dyn JourneyService>, InjectError> {
+        self.journey_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn location_service(&self) -> Result<Arc<dyn LocationService>, InjectError> {
+        self.location_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn payment_service(&self) -> Result<Arc<dyn PaymentService>, InjectError> {
+        self.payment_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
+
+    fn pricing_service(&self) -> Result<Arc<dyn PricingService>, InjectError> {
+        self.pricing_service
+            .get()
+            .cloned()
+            .ok_or(InjectError::NotInitialized)
+    }
 }
@@ -1,11 +1,14 @@
+#![allow(clippy::module_inception)]
+
 use axum::Router;
 use axum::http::{HeaderValue, Method, header};
 use std::net::SocketAddr;
 use tower_http::cors::CorsLayer;
-use tracing::info;
+use tracing::{info, warn};
 
 mod config;
 mod controllers;
+#[allow(unused_imports)]
 mod entities;
 mod inject;
 mod services;
@@ -29,18 +32,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let injector = InjectFactoryImpl::init().await?;
 
     let config = injector.config()?;
-    let jwt_service = injector.jwt_service()?;
     let db = injector.database()?;
-    let auth_service: std::sync::Arc<dyn services::auth::Auth> = std::sync::Arc::new(services::auth::AuthImpl::new());
+
+    spawn_revocation_sync(injector.revocation_service()?, db.clone(), config.revocation_sync_interval);
+    spawn_login_attempt_purge(injector.login_throttle_service()?, config.login_failure_window);
+    spawn_seat_hold_purge(injector.booking_service()?, db.clone(), config.seat_hold_ttl);
+    spawn_unpaid_booking_release(injector.payment_service()?, db.clone(), config.payment_timeout);
+    spawn_trip_generation(injector.schedule_service()?, db, config.schedule_generation_interval);
 
     let addr: SocketAddr = format!("{}:{}", config.host, config.port).parse()?;
     let cors_origin = config.cors_origin.clone();
 
-    let http_app = Router::new().nest(
-        "/api/v1",
-        controllers::v1::http_router(config.clone(), jwt_service.clone(), auth_service.clone(), db.clone()),
-    );
-    let grpc_routes = controllers::v1::grpc_router(config, jwt_service, auth_service, db);
+    let http_app = Router::new()
+        .merge(controllers::well_known::http_router(injector.jwt_service()?))
+        .nest("/api/v1", controllers::v1::http_router(&injector)?);
+    let grpc_routes = controllers::v1::grpc_router(&injector)?;
 
     let http_app = http_app.layer(build_http_cors(&cors_origin));
     let grpc_routes = grpc_routes.layer(build_grpc_cors(&cors_origin));
@@ -49,11 +55,110 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("HTTP + gRPC listening on {addr}");
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
 }
 
+/// Keeps the revocation cache in step with revocations made on other nodes.
+fn spawn_revocation_sync(
+    revocation_service: std::sync::Arc<dyn services::revocation::RevocationService>,
+    db: std::sync::Arc<sea_orm::DatabaseConnection>,
+    interval_seconds: u64,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_seconds.max(1)));
+        loop {
+            interval.tick().await;
+            if let Err(err) = revocation_service.sync(&db).await {
+                warn!("failed to sync token revocations: {err}");
+            }
+        }
+    });
+}
+
+/// Drops expired login attempt counters so the store does not grow unbounded.
+fn spawn_login_attempt_purge(
+    login_throttle_service: std::sync::Arc<dyn services::login_throttle::LoginThrottleService>,
+    interval_seconds: u64,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_seconds.max(60)));
+        loop {
+            interval.tick().await;
+            if let Err(err) = login_throttle_service.purge().await {
+                warn!("failed to purge login attempts: {err}");
+            }
+        }
+    });
+}
+
+/// Deletes expired seat holds. They stop blocking seats as soon as they
+/// expire, so this only keeps the table small.
+fn spawn_seat_hold_purge(
+    booking_service: std::sync::Arc<dyn services::booking::BookingService>,
+    db: std::sync::Arc<sea_orm::DatabaseConnection>,
+    hold_ttl: u64,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(hold_ttl.clamp(1, 60)));
+        loop {
+            interval.tick().await;
+            if let Err(err) = booking_service.purge_expired_holds(&db).await {
+                warn!("failed to purge seat holds: {err}");
+            }
+        }
+    });
+}
+
+/// Cancels booked tickets left unpaid past `PAYMENT_TIMEOUT` so their seats
+/// can be sold again.
+fn spawn_unpaid_booking_release(
+    payment_service: std::sync::Arc<dyn services::payment::PaymentService>,
+    db: std::sync::Arc<sea_orm::DatabaseConnection>,
+    payment_timeout: u64,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(payment_timeout.clamp(1, 60)));
+        loop {
+            interval.tick().await;
+            match payment_service.release_unpaid(&db).await {
+                Ok(0) => {}
+                Ok(released) => info!("released {released} unpaid tickets"),
+                Err(err) => warn!("failed to release unpaid tickets: {err}"),
+            }
+        }
+    });
+}
+
+/// Turns active recurring schedules into trips over the configured horizon.
+fn spawn_trip_generation(
+    schedule_service: std::sync::Arc<dyn services::schedule::ScheduleService>,
+    db: std::sync::Arc<sea_orm::DatabaseConnection>,
+    interval_seconds: u64,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_seconds.max(1)));
+        loop {
+            interval.tick().await;
+            match schedule_service.generate(&db, None).await {
+                Ok(report) => {
+                    if !report.created.is_empty() {
+                        info!("generated {} trips from schedules", report.created.len());
+                    }
+                    for conflict in report.conflicts {
+                        warn!(
+                            "schedule {} skipped departure {}: bus busy with trips {:?}",
+                            conflict.schedule_id, conflict.start_date, conflict.trip_ids
+                        );
+                    }
+                }
+                Err(err) => warn!("failed to generate scheduled trips: {err}"),
+            }
+        }
+    });
+}
+
 fn parse_origins(cors_origin: &str) -> Vec<HeaderValue> {
     cors_origin
         .split(',')
@@ -65,14 +170,14 @@ fn build_http_cors(cors_origin: &str) -> CorsLayer {
     let origins = parse_origins(cors_origin);
 
     let mut layer = CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
         .allow_headers([
             header::AUTHORIZATION,
             header::CONTENT_TYPE,
             header::COOKIE,
         ])
         .allow_credentials(true)
-        .expose_headers([header::SET_COOKIE]);
+        .expose_headers([header::SET_COOKIE, header::RETRY_AFTER]);
 
     if !origins.is_empty() {
         layer = layer.allow_origin(origins);
@@ -97,6 +202,7 @@ fn build_grpc_cors(cors_origin: &str) -> CorsLayer {
         .allow_credentials(true)
         .expose_headers([
             header::SET_COOKIE,
+            header::RETRY_AFTER,
             header::HeaderName::from_static("grpc-status"),
             header::HeaderName::from_static("grpc-message"),
             header::HeaderName::from_static("grpc-status-details-bin"),
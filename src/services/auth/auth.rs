@@ -1,16 +1,51 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
-use sea_orm::{DatabaseConnection, EntityTrait, ColumnTrait, QueryFilter};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, DatabaseConnection, EntityTrait, ColumnTrait, QueryFilter};
+use tracing::warn;
 
 use crate::entities::user::Entity as User;
 use crate::entities::user::Column as UserColumn;
 use crate::entities::user::Model as UserModel;
+use crate::entities::user::ActiveModel as UserActiveModel;
 use crate::services::auth::errors::AuthError;
+use crate::services::password::{PasswordService, PasswordVerification};
 
-pub struct AuthImpl;
+pub struct AuthImpl {
+    password_service: Arc<dyn PasswordService>,
+}
 
 impl AuthImpl {
-    pub fn new() -> Self {
-        Self
+    pub fn new(password_service: Arc<dyn PasswordService>) -> Self {
+        Self { password_service }
+    }
+
+    /// Replaces a legacy or weaker stored password with a fresh hash. Failures
+    /// are logged and the login still succeeds; the next login retries.
+    async fn upgrade_password(
+        &self,
+        db: &DatabaseConnection,
+        user: UserModel,
+        password: &str,
+    ) -> UserModel {
+        let hash = match self.password_service.hash_password(password).await {
+            Ok(hash) => hash,
+            Err(err) => {
+                warn!(user_id = user.user_id, "failed to rehash password: {err}");
+                return user;
+            }
+        };
+
+        let mut active: UserActiveModel = user.clone().into();
+        active.password = Set(hash);
+
+        match active.update(db).await {
+            Ok(updated) => updated,
+            Err(err) => {
+                warn!(user_id = user.user_id, "failed to store rehashed password: {err}");
+                user
+            }
+        }
     }
 }
 
@@ -23,16 +58,67 @@ impl super::Auth for AuthImpl {
         password: &str,
     ) -> Result<UserModel, AuthError> {
         let user = User::find()
-            .filter(UserColumn::Email.eq(email))
+            .filter(UserColumn::Email.eq(super::normalize_email(email)))
             .one(db)
             .await?;
 
-        let user = user.ok_or(AuthError::InvalidCredentials)?;
-
-        if user.password != password {
+        let Some(user) = user else {
+            self.password_service.verify_dummy(password).await;
             return Err(AuthError::InvalidCredentials);
+        };
+
+        let user = match self.password_service.verify_password(password, &user.password).await? {
+            PasswordVerification::Valid => user,
+            PasswordVerification::NeedsRehash => self.upgrade_password(db, user, password).await,
+            PasswordVerification::Invalid => return Err(AuthError::InvalidCredentials),
+        };
+
+        // Checked only after the password so it does not reveal which
+        // addresses are registered.
+        if !user.email_verified {
+            return Err(AuthError::EmailNotVerified);
         }
 
         Ok(user)
     }
+
+    async fn find_user_by_id(&self, db: &DatabaseConnection, user_id: i32) -> Result<UserModel, AuthError> {
+        User::find_by_id(user_id)
+            .one(db)
+            .await?
+            .ok_or(AuthError::UserNotFound)
+    }
+
+    async fn find_user_by_email(&self, db: &DatabaseConnection, email: &str) -> Result<UserModel, AuthError> {
+        User::find()
+            .filter(UserColumn::Email.eq(super::normalize_email(email)))
+            .one(db)
+            .await?
+            .ok_or(AuthError::UserNotFound)
+    }
+
+    async fn change_password(
+        &self,
+        db: &DatabaseConnection,
+        email: &str,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<UserModel, AuthError> {
+        let user = User::find()
+            .filter(UserColumn::Email.eq(super::normalize_email(email)))
+            .one(db)
+            .await?
+            .ok_or(AuthError::UserNotFound)?;
+
+        if self.password_service.verify_password(current_password, &user.password).await?
+            == PasswordVerification::Invalid
+        {
+            return Err(AuthError::InvalidCredentials);
+        }
+
+        let mut active: UserActiveModel = user.into();
+        active.password = Set(self.password_service.hash_password(new_password).await?);
+
+        Ok(active.update(db).await?)
+    }
 }
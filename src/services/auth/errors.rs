@@ -1,10 +1,18 @@
 use sea_orm::DbErr;
 use thiserror::Error;
 
+use crate::services::password::PasswordServiceError;
+
 #[derive(Debug, Error)]
 pub enum AuthError {
     #[error("database error: {0}")]
     Database(#[from] DbErr),
+    #[error("password error: {0}")]
+    Password(#[from] PasswordServiceError),
     #[error("invalid credentials")]
     InvalidCredentials,
+    #[error("email not verified")]
+    EmailNotVerified,
+    #[error("user not found")]
+    UserNotFound,
 }
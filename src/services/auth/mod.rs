@@ -9,6 +9,11 @@ use crate::entities::user::Model as UserModel;
 pub use auth::AuthImpl;
 pub use errors::AuthError;
 
+/// Emails are stored and looked up trimmed and lowercased.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
 #[async_trait]
 pub trait Auth: Send + Sync {
     async fn find_user_by_email_password(
@@ -17,4 +22,18 @@ pub trait Auth: Send + Sync {
         email: &str,
         password: &str,
     ) -> Result<UserModel, AuthError>;
+
+    async fn find_user_by_id(&self, db: &DatabaseConnection, user_id: i32) -> Result<UserModel, AuthError>;
+
+    async fn find_user_by_email(&self, db: &DatabaseConnection, email: &str) -> Result<UserModel, AuthError>;
+
+    /// Replaces the password of the account with `email` after checking
+    /// `current_password`.
+    async fn change_password(
+        &self,
+        db: &DatabaseConnection,
+        email: &str,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<UserModel, AuthError>;
 }
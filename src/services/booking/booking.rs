@@ -0,0 +1,434 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbErr,
+    EntityTrait, IsolationLevel, QueryFilter, QueryOrder, QuerySelect, RuntimeErr, SqlErr, TransactionTrait,
+};
+
+use crate::entities::bus::Entity as Bus;
+use crate::entities::route::Entity as Route;
+use crate::entities::sea_orm_active_enums::TicketStatus;
+use crate::entities::seat::{Column as SeatColumn, Entity as Seat, Model as SeatModel};
+use crate::entities::seat_hold::{
+    ActiveModel as SeatHoldActiveModel, Column as SeatHoldColumn, Entity as SeatHold, Model as SeatHoldModel,
+};
+use crate::entities::ticket::{ActiveModel as TicketActiveModel, Column as TicketColumn, Entity as Ticket};
+use crate::entities::trip::Entity as Trip;
+
+use crate::services::pricing::{PriceRequest, PricingError, PricingService, SeatCharge};
+use crate::services::route::{Segment, itinerary};
+use crate::services::ticket::{SEAT_RELEASING_STATUSES, TicketDetails, record_transition};
+
+use super::{BookedTickets, BookingError, TripQuote};
+
+/// Serializable transactions can be aborted by a concurrent booking; they
+/// are retried this many times in total before giving up.
+const MAX_ATTEMPTS: usize = 3;
+
+/// The stops a booking rides between and its fare before seat surcharges.
+struct Leg {
+    route_id: i32,
+    from_stop: i32,
+    to_stop: i32,
+    from_location: String,
+    to_location: String,
+    fare: i32,
+}
+
+pub struct BookingServiceImpl {
+    hold_ttl: i64,
+    pricing_service: Arc<dyn PricingService>,
+}
+
+impl BookingServiceImpl {
+    pub fn new(hold_ttl: u64, pricing_service: Arc<dyn PricingService>) -> Self {
+        Self {
+            hold_ttl: hold_ttl as i64,
+            pricing_service,
+        }
+    }
+
+    async fn try_book(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        trip_id: i32,
+        segment: Segment,
+        seat_ids: &[i32],
+        promo_code: Option<&str>,
+    ) -> Result<BookedTickets, BookingError> {
+        let txn = db.begin_with_config(Some(IsolationLevel::Serializable), None).await?;
+        let now = Utc::now().naive_utc();
+
+        let (leg, seats) = reservable_seats(&txn, user_id, trip_id, segment, seat_ids, now).await?;
+        release_holds(&txn, trip_id, &leg, seat_ids).await?;
+
+        let seats = seat_charges(&seats);
+        let request = PriceRequest {
+            user_id,
+            route_id: leg.route_id,
+            fare: leg.fare,
+            seats: &seats,
+            promo_code,
+        };
+        let quote = self.pricing_service.price(&txn, &request, now).await?;
+        let promo_redemption_id = self.pricing_service.redeem(&txn, &quote, user_id, now).await?;
+
+        let mut booked = Vec::with_capacity(quote.lines.len());
+        for line in &quote.lines {
+            let ticket = TicketActiveModel {
+                status: Set(TicketStatus::Booked),
+                price: Set(line.price),
+                discount: Set(line.discount),
+                promo_redemption_id: Set(promo_redemption_id),
+                trip_id: Set(trip_id),
+                seat_id: Set(line.seat_id),
+                booked_date: Set(now),
+                user_id: Set(user_id),
+                from_location: Set(Some(leg.from_location.clone())),
+                to_location: Set(Some(leg.to_location.clone())),
+                refund_amount: Set(None),
+                from_stop: Set(leg.from_stop),
+                to_stop: Set(leg.to_stop),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await
+            .map_err(|err| {
+                // ticket_seat_no_overlap: sold by a booking that committed first.
+                if is_overlap_violation(&err) {
+                    BookingError::SeatsUnavailable(vec![line.seat_id])
+                } else {
+                    err.into()
+                }
+            })?;
+            record_transition(&txn, ticket.ticket_id, None, TicketStatus::Booked, Some(user_id), now).await?;
+
+            booked.push(TicketDetails {
+                ticket,
+                seat_name: line.seat_name.clone(),
+            });
+        }
+
+        txn.commit().await?;
+
+        Ok(BookedTickets { tickets: booked, quote })
+    }
+
+    async fn try_hold(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        trip_id: i32,
+        segment: Segment,
+        seat_ids: &[i32],
+    ) -> Result<Vec<SeatHoldModel>, BookingError> {
+        let txn = db.begin_with_config(Some(IsolationLevel::Serializable), None).await?;
+        let now = Utc::now().naive_utc();
+        let expires_at = now + Duration::seconds(self.hold_ttl);
+
+        let (leg, _) = reservable_seats(&txn, user_id, trip_id, segment, seat_ids, now).await?;
+        // What is left on these seats is expired or the caller's own, which is renewed.
+        release_holds(&txn, trip_id, &leg, seat_ids).await?;
+
+        let mut holds = Vec::with_capacity(seat_ids.len());
+        for seat_id in seat_ids {
+            let hold = SeatHoldActiveModel {
+                trip_id: Set(trip_id),
+                seat_id: Set(*seat_id),
+                user_id: Set(user_id),
+                expires_at: Set(expires_at),
+                created_at: Set(now),
+                from_stop: Set(leg.from_stop),
+                to_stop: Set(leg.to_stop),
+            }
+            .insert(&txn)
+            .await
+            .map_err(|err| {
+                if is_overlap_violation(&err) || matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) {
+                    BookingError::SeatsHeld(vec![*seat_id])
+                } else {
+                    err.into()
+                }
+            })?;
+
+            holds.push(hold);
+        }
+
+        txn.commit().await?;
+
+        Ok(holds)
+    }
+}
+
+/// Checks that the trip can still be booked and finds the leg and seats:
+/// the trip has not departed, its route and bus are active, the segment runs
+/// between two of the route's stops in order, and the seats are on its bus.
+/// `seat_ids` must be sorted.
+async fn trip_seats<C: ConnectionTrait>(
+    txn: &C,
+    trip_id: i32,
+    segment: Segment,
+    seat_ids: &[i32],
+    now: NaiveDateTime,
+) -> Result<(Leg, Vec<SeatModel>), BookingError> {
+    let trip = Trip::find_by_id(trip_id)
+        .one(txn)
+        .await?
+        .ok_or(BookingError::TripNotFound)?;
+    if trip.start_date <= now {
+        return Err(BookingError::TripDeparted);
+    }
+    if trip.cancelled {
+        return Err(BookingError::TripUnavailable);
+    }
+
+    let route = Route::find_by_id(trip.route_id)
+        .one(txn)
+        .await?
+        .filter(|route| !route.deleted)
+        .ok_or(BookingError::TripUnavailable)?;
+    Bus::find_by_id(trip.bus_id)
+        .one(txn)
+        .await?
+        .filter(|bus| !bus.deleted)
+        .ok_or(BookingError::TripUnavailable)?;
+
+    let stops = itinerary(txn, route.route_id).await?;
+    let (from, to) = segment.resolve(&stops).ok_or(BookingError::InvalidSegment)?;
+    let leg = Leg {
+        route_id: route.route_id,
+        from_stop: from.stop_order,
+        to_stop: to.stop_order,
+        from_location: from.location.clone(),
+        to_location: to.location.clone(),
+        fare: to.fare_from_origin - from.fare_from_origin,
+    };
+
+    let seats = Seat::find()
+        .filter(SeatColumn::SeatId.is_in(seat_ids.iter().copied()))
+        .filter(SeatColumn::BusId.eq(trip.bus_id))
+        .filter(SeatColumn::Deleted.eq(false))
+        .order_by_asc(SeatColumn::SeatId)
+        .all(txn)
+        .await?;
+
+    let missing: Vec<i32> = seat_ids
+        .iter()
+        .copied()
+        .filter(|seat_id| !seats.iter().any(|seat| seat.seat_id == *seat_id))
+        .collect();
+    if !missing.is_empty() {
+        return Err(BookingError::SeatsNotFound(missing));
+    }
+
+    Ok((leg, seats))
+}
+
+/// Checks that every seat can be sold to the user on the trip's segment
+/// right now: besides what `trip_seats` checks, the seats are not sold on an
+/// overlapping segment and not held there by anyone else. `seat_ids` must be
+/// sorted.
+async fn reservable_seats(
+    txn: &DatabaseTransaction,
+    user_id: i32,
+    trip_id: i32,
+    segment: Segment,
+    seat_ids: &[i32],
+    now: NaiveDateTime,
+) -> Result<(Leg, Vec<SeatModel>), BookingError> {
+    let (leg, seats) = trip_seats(txn, trip_id, segment, seat_ids, now).await?;
+
+    let mut sold: Vec<i32> = Ticket::find()
+        .select_only()
+        .column(TicketColumn::SeatId)
+        .filter(TicketColumn::TripId.eq(trip_id))
+        .filter(TicketColumn::SeatId.is_in(seat_ids.iter().copied()))
+        .filter(TicketColumn::Status.is_not_in(SEAT_RELEASING_STATUSES))
+        .filter(TicketColumn::FromStop.lt(leg.to_stop))
+        .filter(TicketColumn::ToStop.gt(leg.from_stop))
+        .into_tuple()
+        .all(txn)
+        .await?;
+    if !sold.is_empty() {
+        sold.sort_unstable();
+        sold.dedup();
+        return Err(BookingError::SeatsUnavailable(sold));
+    }
+
+    let held: Vec<i32> = SeatHold::find()
+        .select_only()
+        .column(SeatHoldColumn::SeatId)
+        .filter(SeatHoldColumn::TripId.eq(trip_id))
+        .filter(SeatHoldColumn::SeatId.is_in(seat_ids.iter().copied()))
+        .filter(SeatHoldColumn::UserId.ne(user_id))
+        .filter(SeatHoldColumn::ExpiresAt.gt(now))
+        .filter(SeatHoldColumn::FromStop.lt(leg.to_stop))
+        .filter(SeatHoldColumn::ToStop.gt(leg.from_stop))
+        .order_by_asc(SeatHoldColumn::SeatId)
+        .into_tuple()
+        .all(txn)
+        .await?;
+    if !held.is_empty() {
+        return Err(BookingError::SeatsHeld(held));
+    }
+
+    Ok((leg, seats))
+}
+
+/// Drops every hold on the seats overlapping the leg; call only after
+/// `reservable_seats`.
+async fn release_holds(
+    txn: &DatabaseTransaction,
+    trip_id: i32,
+    leg: &Leg,
+    seat_ids: &[i32],
+) -> Result<(), BookingError> {
+    SeatHold::delete_many()
+        .filter(SeatHoldColumn::TripId.eq(trip_id))
+        .filter(SeatHoldColumn::SeatId.is_in(seat_ids.iter().copied()))
+        .filter(SeatHoldColumn::FromStop.lt(leg.to_stop))
+        .filter(SeatHoldColumn::ToStop.gt(leg.from_stop))
+        .exec(txn)
+        .await?;
+
+    Ok(())
+}
+
+fn seat_charges(seats: &[SeatModel]) -> Vec<SeatCharge> {
+    seats
+        .iter()
+        .map(|seat| SeatCharge {
+            seat_id: seat.seat_id,
+            seat_name: seat.name.clone(),
+            surcharge: seat.price,
+        })
+        .collect()
+}
+
+/// Sorts the seats and refuses any listed twice.
+fn sorted_seats(seat_ids: &[i32]) -> Result<Vec<i32>, BookingError> {
+    let mut seat_ids = seat_ids.to_vec();
+    seat_ids.sort_unstable();
+    if seat_ids.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err(BookingError::DuplicateSeats);
+    }
+
+    Ok(seat_ids)
+}
+
+/// SQLSTATE 40001, raised when Postgres cannot serialize two transactions.
+fn is_serialization_failure(err: &DbErr) -> bool {
+    match err {
+        DbErr::Exec(RuntimeErr::SqlxError(sea_orm::sqlx::Error::Database(e)))
+        | DbErr::Query(RuntimeErr::SqlxError(sea_orm::sqlx::Error::Database(e))) => {
+            e.code().as_deref() == Some("40001")
+        }
+        _ => false,
+    }
+}
+
+/// SQLSTATE 23P01, raised by `ticket_seat_no_overlap` or
+/// `seat_hold_no_overlap` when a concurrent request took the seat first.
+fn is_overlap_violation(err: &DbErr) -> bool {
+    match err {
+        DbErr::Exec(RuntimeErr::SqlxError(sea_orm::sqlx::Error::Database(e)))
+        | DbErr::Query(RuntimeErr::SqlxError(sea_orm::sqlx::Error::Database(e))) => {
+            e.code().as_deref() == Some("23P01")
+        }
+        _ => false,
+    }
+}
+
+/// Runs a serializable transaction again when Postgres aborts it for a
+/// conflict, up to `MAX_ATTEMPTS` times.
+async fn retry_serializable<T, F, Fut>(mut attempt: F) -> Result<T, BookingError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, BookingError>>,
+{
+    for _ in 0..MAX_ATTEMPTS {
+        match attempt().await {
+            Err(BookingError::Database(err)) if is_serialization_failure(&err) => continue,
+            Err(BookingError::Pricing(PricingError::Database(err))) if is_serialization_failure(&err) => continue,
+            result => return result,
+        }
+    }
+
+    Err(BookingError::Contention)
+}
+
+#[async_trait]
+impl super::BookingService for BookingServiceImpl {
+    async fn book(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        trip_id: i32,
+        segment: Segment,
+        seat_ids: &[i32],
+        promo_code: Option<&str>,
+    ) -> Result<BookedTickets, BookingError> {
+        let seat_ids = sorted_seats(seat_ids)?;
+
+        retry_serializable(|| self.try_book(db, user_id, trip_id, segment, &seat_ids, promo_code)).await
+    }
+
+    async fn quote(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        trip_id: i32,
+        segment: Segment,
+        seat_ids: &[i32],
+        promo_code: Option<&str>,
+    ) -> Result<TripQuote, BookingError> {
+        let seat_ids = sorted_seats(seat_ids)?;
+        let now = Utc::now().naive_utc();
+
+        let (leg, seats) = trip_seats(db, trip_id, segment, &seat_ids, now).await?;
+        let seats = seat_charges(&seats);
+        let request = PriceRequest {
+            user_id,
+            route_id: leg.route_id,
+            fare: leg.fare,
+            seats: &seats,
+            promo_code,
+        };
+        let quote = self.pricing_service.quote(db, &request, now).await?;
+
+        Ok(TripQuote {
+            trip_id,
+            from_stop: leg.from_stop,
+            to_stop: leg.to_stop,
+            from_location: leg.from_location,
+            to_location: leg.to_location,
+            quote,
+        })
+    }
+
+    async fn hold(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        trip_id: i32,
+        segment: Segment,
+        seat_ids: &[i32],
+    ) -> Result<Vec<SeatHoldModel>, BookingError> {
+        let seat_ids = sorted_seats(seat_ids)?;
+
+        retry_serializable(|| self.try_hold(db, user_id, trip_id, segment, &seat_ids)).await
+    }
+
+    async fn purge_expired_holds(&self, db: &DatabaseConnection) -> Result<u64, BookingError> {
+        let result = SeatHold::delete_many()
+            .filter(SeatHoldColumn::ExpiresAt.lte(Utc::now().naive_utc()))
+            .exec(db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,30 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+use crate::services::pricing::PricingError;
+
+#[derive(Debug, Error)]
+pub enum BookingError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("trip not found")]
+    TripNotFound,
+    #[error("trip has already departed")]
+    TripDeparted,
+    #[error("trip is no longer available")]
+    TripUnavailable,
+    #[error("the trip does not run between those stops")]
+    InvalidSegment,
+    #[error("seat listed more than once")]
+    DuplicateSeats,
+    #[error("seats not found on this trip: {0:?}")]
+    SeatsNotFound(Vec<i32>),
+    #[error("seats already sold: {0:?}")]
+    SeatsUnavailable(Vec<i32>),
+    #[error("seats held by another customer: {0:?}")]
+    SeatsHeld(Vec<i32>),
+    #[error("booking conflicted with concurrent requests, try again")]
+    Contention,
+    #[error(transparent)]
+    Pricing(#[from] PricingError),
+}
@@ -0,0 +1,77 @@
+mod booking;
+mod errors;
+
+pub use booking::BookingServiceImpl;
+pub use errors::BookingError;
+
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+
+use crate::entities::seat_hold::Model as SeatHoldModel;
+use crate::services::pricing::Quote;
+use crate::services::route::Segment;
+use crate::services::ticket::TicketDetails;
+
+/// Tickets sold together and how they were priced.
+#[derive(Debug, Clone)]
+pub struct BookedTickets {
+    pub tickets: Vec<TicketDetails>,
+    pub quote: Quote,
+}
+
+/// What seats on a trip's segment would cost.
+#[derive(Debug, Clone)]
+pub struct TripQuote {
+    pub trip_id: i32,
+    pub from_stop: i32,
+    pub to_stop: i32,
+    pub from_location: String,
+    pub to_location: String,
+    pub quote: Quote,
+}
+
+#[async_trait]
+pub trait BookingService: Send + Sync {
+    /// Books every seat in `seat_ids` on the trip's `segment` for the user,
+    /// or none of them. A seat already sold on an overlapping segment fails
+    /// with `SeatsUnavailable`, also when a concurrent booking takes it first,
+    /// and one held there by another user with `SeatsHeld`. The user's own
+    /// holds on the seats become tickets. A promo code the booking does not
+    /// qualify for fails the whole booking with `Pricing`.
+    async fn book(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        trip_id: i32,
+        segment: Segment,
+        seat_ids: &[i32],
+        promo_code: Option<&str>,
+    ) -> Result<BookedTickets, BookingError>;
+
+    /// Prices the seats on the trip's `segment` as `book` would, without
+    /// checking they are still free.
+    async fn quote(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        trip_id: i32,
+        segment: Segment,
+        seat_ids: &[i32],
+        promo_code: Option<&str>,
+    ) -> Result<TripQuote, BookingError>;
+
+    /// Reserves every seat in `seat_ids` on the trip's `segment` for the user
+    /// until the hold expires, or none of them, under the same rules as
+    /// `book`. Holding seats the user already holds renews them.
+    async fn hold(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        trip_id: i32,
+        segment: Segment,
+        seat_ids: &[i32],
+    ) -> Result<Vec<SeatHoldModel>, BookingError>;
+
+    /// Deletes expired holds, returning how many were removed.
+    async fn purge_expired_holds(&self, db: &DatabaseConnection) -> Result<u64, BookingError>;
+}
@@ -0,0 +1,294 @@
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend,
+    EntityTrait, FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, SqlErr, Statement,
+    TransactionTrait,
+};
+
+use crate::entities::bus::{ActiveModel as BusActiveModel, Column as BusColumn, Entity as Bus};
+use crate::entities::seat::{
+    ActiveModel as SeatActiveModel, Column as SeatColumn, Entity as Seat, Model as SeatModel,
+};
+use crate::entities::seat_hold::{Column as SeatHoldColumn, Entity as SeatHold};
+use crate::entities::ticket::{Column as TicketColumn, Entity as Ticket};
+use crate::entities::trip::{Column as TripColumn, Entity as Trip};
+use crate::services::ticket::SEAT_RELEASING_STATUSES;
+
+use super::{BusError, BusSummary, DuplicateSeatName, SeatInput};
+
+const BUS_SUMMARY_QUERY: &str = r#"
+    SELECT bus_id, name, license_plate, deleted, seat_count
+    FROM bus_with_seat_count
+    WHERE ($1 OR NOT deleted) AND ($2::INT IS NULL OR bus_id = $2)
+    ORDER BY bus_id
+"#;
+
+/// Active seats whose name appears more than once on their bus.
+const DUPLICATE_SEATS_QUERY: &str = r#"
+    SELECT seat_id, bus_id, name
+    FROM (
+        SELECT seat_id, bus_id, name, COUNT(*) OVER (PARTITION BY bus_id, name) AS copies
+        FROM seat
+        WHERE NOT deleted
+    ) s
+    WHERE copies > 1
+    ORDER BY bus_id, name, seat_id
+"#;
+
+#[derive(FromQueryResult)]
+struct DuplicateSeatRow {
+    seat_id: i32,
+    bus_id: i32,
+    name: String,
+}
+
+pub struct BusServiceImpl;
+
+impl BusServiceImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+async fn bus_summaries<C: ConnectionTrait>(
+    conn: &C,
+    include_deleted: bool,
+    bus_id: Option<i32>,
+) -> Result<Vec<BusSummary>, BusError> {
+    Ok(BusSummary::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        BUS_SUMMARY_QUERY,
+        [include_deleted.into(), bus_id.into()],
+    ))
+    .all(conn)
+    .await?)
+}
+
+/// Locks the bus row so concurrent layout changes to it run one at a time.
+async fn lock_bus<C: ConnectionTrait>(conn: &C, bus_id: i32) -> Result<(), BusError> {
+    Bus::find_by_id(bus_id)
+        .lock_exclusive()
+        .one(conn)
+        .await?
+        .ok_or(BusError::BusNotFound)?;
+
+    Ok(())
+}
+
+async fn active_seats<C: ConnectionTrait>(conn: &C, bus_id: i32) -> Result<Vec<SeatModel>, BusError> {
+    Ok(Seat::find()
+        .filter(SeatColumn::BusId.eq(bus_id))
+        .filter(SeatColumn::Deleted.eq(false))
+        .order_by_asc(SeatColumn::Deck)
+        .order_by_asc(SeatColumn::SeatRow)
+        .order_by_asc(SeatColumn::SeatColumn)
+        .order_by_asc(SeatColumn::SeatId)
+        .all(conn)
+        .await?)
+}
+
+/// Inserts the seats after checking their names against each other and the
+/// bus's active seats, then returns the new layout. `seat_bus_name_idx` backs
+/// the check up for writers that do not lock the bus.
+async fn insert_seats<C: ConnectionTrait>(
+    conn: &C,
+    bus_id: i32,
+    seats: Vec<SeatInput>,
+) -> Result<Vec<SeatModel>, BusError> {
+    let existing = active_seats(conn, bus_id).await?;
+
+    let mut names: BTreeSet<&str> = existing.iter().map(|seat| seat.name.as_str()).collect();
+    let mut duplicates: BTreeSet<String> = BTreeSet::new();
+    for seat in &seats {
+        if !names.insert(seat.name.as_str()) {
+            duplicates.insert(seat.name.clone());
+        }
+    }
+    if !duplicates.is_empty() {
+        return Err(BusError::DuplicateSeatNames(duplicates.into_iter().collect()));
+    }
+
+    if !seats.is_empty() {
+        let names: Vec<String> = seats.iter().map(|seat| seat.name.clone()).collect();
+        Seat::insert_many(seats.into_iter().map(|seat| SeatActiveModel {
+            bus_id: Set(bus_id),
+            name: Set(seat.name),
+            price: Set(seat.price),
+            seat_row: Set(seat.row),
+            seat_column: Set(seat.column),
+            deck: Set(seat.deck),
+            deleted: Set(false),
+            ..Default::default()
+        }))
+        .exec(conn)
+        .await
+        .map_err(|err| match err.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => BusError::DuplicateSeatNames(names),
+            _ => err.into(),
+        })?;
+    }
+
+    active_seats(conn, bus_id).await
+}
+
+#[async_trait]
+impl super::BusService for BusServiceImpl {
+    async fn list_buses(&self, db: &DatabaseConnection, include_deleted: bool) -> Result<Vec<BusSummary>, BusError> {
+        bus_summaries(db, include_deleted, None).await
+    }
+
+    async fn create_bus(
+        &self,
+        db: &DatabaseConnection,
+        name: Option<String>,
+        license_plate: String,
+    ) -> Result<BusSummary, BusError> {
+        let txn = db.begin().await?;
+
+        let taken = Bus::find()
+            .filter(BusColumn::LicensePlate.eq(license_plate.as_str()))
+            .count(&txn)
+            .await?;
+        if taken > 0 {
+            return Err(BusError::LicensePlateTaken(license_plate));
+        }
+
+        let bus = BusActiveModel {
+            name: Set(name),
+            license_plate: Set(license_plate),
+            deleted: Set(false),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+
+        Ok(BusSummary {
+            bus_id: bus.bus_id,
+            name: bus.name,
+            license_plate: bus.license_plate,
+            deleted: bus.deleted,
+            seat_count: 0,
+        })
+    }
+
+    async fn seat_layout(&self, db: &DatabaseConnection, bus_id: i32) -> Result<Vec<SeatModel>, BusError> {
+        Bus::find_by_id(bus_id)
+            .one(db)
+            .await?
+            .ok_or(BusError::BusNotFound)?;
+
+        active_seats(db, bus_id).await
+    }
+
+    async fn add_seats(
+        &self,
+        db: &DatabaseConnection,
+        bus_id: i32,
+        seats: Vec<SeatInput>,
+    ) -> Result<Vec<SeatModel>, BusError> {
+        let txn = db.begin().await?;
+
+        lock_bus(&txn, bus_id).await?;
+        let layout = insert_seats(&txn, bus_id, seats).await?;
+        txn.commit().await?;
+
+        Ok(layout)
+    }
+
+    async fn clone_layout(
+        &self,
+        db: &DatabaseConnection,
+        bus_id: i32,
+        source_bus_id: i32,
+    ) -> Result<Vec<SeatModel>, BusError> {
+        let txn = db.begin().await?;
+
+        lock_bus(&txn, bus_id).await?;
+        Bus::find_by_id(source_bus_id)
+            .one(&txn)
+            .await?
+            .ok_or(BusError::BusNotFound)?;
+
+        let seats = active_seats(&txn, source_bus_id)
+            .await?
+            .into_iter()
+            .map(|seat| SeatInput {
+                name: seat.name,
+                price: seat.price,
+                row: seat.seat_row,
+                column: seat.seat_column,
+                deck: seat.deck,
+            })
+            .collect();
+        let layout = insert_seats(&txn, bus_id, seats).await?;
+        txn.commit().await?;
+
+        Ok(layout)
+    }
+
+    async fn delete_seat(&self, db: &DatabaseConnection, bus_id: i32, seat_id: i32) -> Result<(), BusError> {
+        let txn = db.begin().await?;
+
+        lock_bus(&txn, bus_id).await?;
+        let seat = Seat::find_by_id(seat_id)
+            .filter(SeatColumn::BusId.eq(bus_id))
+            .filter(SeatColumn::Deleted.eq(false))
+            .one(&txn)
+            .await?
+            .ok_or(BusError::SeatNotFound)?;
+
+        let now = Utc::now().naive_utc();
+        let tickets = Ticket::find()
+            .inner_join(Trip)
+            .filter(TicketColumn::SeatId.eq(seat_id))
+            .filter(TicketColumn::Status.is_not_in(SEAT_RELEASING_STATUSES))
+            .filter(TripColumn::StartDate.gt(now))
+            .count(&txn)
+            .await?;
+        let holds = SeatHold::find()
+            .filter(SeatHoldColumn::SeatId.eq(seat_id))
+            .filter(SeatHoldColumn::ExpiresAt.gt(now))
+            .count(&txn)
+            .await?;
+        if tickets > 0 || holds > 0 {
+            return Err(BusError::SeatInUse);
+        }
+
+        let mut active: SeatActiveModel = seat.into();
+        active.deleted = Set(true);
+        active.update(&txn).await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn duplicate_seat_names(&self, db: &DatabaseConnection) -> Result<Vec<DuplicateSeatName>, BusError> {
+        let rows = DuplicateSeatRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            DUPLICATE_SEATS_QUERY,
+            [],
+        ))
+        .all(db)
+        .await?;
+
+        let mut groups: Vec<DuplicateSeatName> = Vec::new();
+        for row in rows {
+            match groups.last_mut() {
+                Some(group) if group.bus_id == row.bus_id && group.name == row.name => {
+                    group.seat_ids.push(row.seat_id)
+                }
+                _ => groups.push(DuplicateSeatName {
+                    bus_id: row.bus_id,
+                    name: row.name,
+                    seat_ids: vec![row.seat_id],
+                }),
+            }
+        }
+
+        Ok(groups)
+    }
+}
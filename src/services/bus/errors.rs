@@ -0,0 +1,18 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BusError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("bus not found")]
+    BusNotFound,
+    #[error("seat not found")]
+    SeatNotFound,
+    #[error("license plate already registered: {0}")]
+    LicensePlateTaken(String),
+    #[error("seat names must be unique per bus: {}", .0.join(", "))]
+    DuplicateSeatNames(Vec<String>),
+    #[error("seat has tickets or holds on trips that have not departed")]
+    SeatInUse,
+}
@@ -0,0 +1,79 @@
+mod bus;
+mod errors;
+
+pub use bus::BusServiceImpl;
+pub use errors::BusError;
+
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, FromQueryResult};
+
+use crate::entities::seat::Model as SeatModel;
+
+/// A row of `bus_with_seat_count`; deleted seats are not counted.
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct BusSummary {
+    pub bus_id: i32,
+    pub name: Option<String>,
+    pub license_plate: String,
+    pub deleted: bool,
+    pub seat_count: i32,
+}
+
+/// A seat to add to a bus layout. `row` and `column` are 1-based positions
+/// on `deck` (1 lower, 2 upper).
+#[derive(Debug, Clone)]
+pub struct SeatInput {
+    pub name: String,
+    pub price: i32,
+    pub row: Option<i32>,
+    pub column: Option<i32>,
+    pub deck: i32,
+}
+
+/// Active seats of one bus sharing a name.
+#[derive(Debug, Clone)]
+pub struct DuplicateSeatName {
+    pub bus_id: i32,
+    pub name: String,
+    pub seat_ids: Vec<i32>,
+}
+
+#[async_trait]
+pub trait BusService: Send + Sync {
+    /// Buses ordered by id; soft-deleted ones only when `include_deleted`.
+    async fn list_buses(&self, db: &DatabaseConnection, include_deleted: bool) -> Result<Vec<BusSummary>, BusError>;
+
+    async fn create_bus(
+        &self,
+        db: &DatabaseConnection,
+        name: Option<String>,
+        license_plate: String,
+    ) -> Result<BusSummary, BusError>;
+
+    /// The bus's active seats ordered by deck, row, column and id.
+    async fn seat_layout(&self, db: &DatabaseConnection, bus_id: i32) -> Result<Vec<SeatModel>, BusError>;
+
+    /// Adds the seats in one go and returns the whole layout. Fails with
+    /// `DuplicateSeatNames` when a name repeats or is already on the bus.
+    async fn add_seats(
+        &self,
+        db: &DatabaseConnection,
+        bus_id: i32,
+        seats: Vec<SeatInput>,
+    ) -> Result<Vec<SeatModel>, BusError>;
+
+    /// Copies the active seats of `source_bus_id` onto the bus, as `add_seats`.
+    async fn clone_layout(
+        &self,
+        db: &DatabaseConnection,
+        bus_id: i32,
+        source_bus_id: i32,
+    ) -> Result<Vec<SeatModel>, BusError>;
+
+    /// Soft-deletes the seat. Fails with `SeatInUse` while it is sold or held
+    /// on a trip that has not departed.
+    async fn delete_seat(&self, db: &DatabaseConnection, bus_id: i32, seat_id: i32) -> Result<(), BusError>;
+
+    /// Every group of active seats that share a name on the same bus.
+    async fn duplicate_seat_names(&self, db: &DatabaseConnection) -> Result<Vec<DuplicateSeatName>, BusError>;
+}
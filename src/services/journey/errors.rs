@@ -0,0 +1,8 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum JourneyError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+}
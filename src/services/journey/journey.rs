@@ -0,0 +1,394 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::{ActiveEnum, DatabaseConnection, DbBackend, FromQueryResult, Statement};
+
+use crate::entities::sea_orm_active_enums::TicketStatus;
+use crate::services::trip::{SEGMENT_SEATS, TripAvailability, contains_pattern};
+
+use super::{Journey, JourneyError, JourneyQuery, JourneySort};
+
+/// Every ride with a seat left between two stops of a trip, departing from
+/// the boarding stop in `[$3, $4)`. Locations come folded as well so legs
+/// connect whatever the spelling; seats are counted by `SEGMENT_SEATS`, as in
+/// trip search.
+///
+/// $1 from, $2 to (LIKE patterns), $3 departure_from, $4 departure_until,
+/// $5 cancelled status, $6 refunded status, $7 now.
+fn legs_query() -> String {
+    format!(
+        r#"
+    SELECT t.trip_id, t.route_id, t.bus_id,
+           t.start_date + a.offset_minutes * INTERVAL '1 minute' AS start_date,
+           COALESCE(t.start_date + z.offset_minutes * INTERVAL '1 minute', t.end_date) AS end_date,
+           a.stop_order AS from_stop, z.stop_order AS to_stop,
+           a.location AS from_location, z.location AS to_location, b.name AS bus_name, b.license_plate,
+           seats.seat_count, seats.available_seats,
+           z.fare_from_origin - a.fare_from_origin + seats.min_surcharge AS min_price,
+           z.fare_from_origin - a.fare_from_origin + seats.max_surcharge AS max_price,
+           fold_location(a.location) AS from_key, fold_location(z.location) AS to_key,
+           fold_location(a.location) LIKE fold_location($1) AS boards_origin,
+           fold_location(z.location) LIKE fold_location($2) AS reaches_destination
+    FROM trip t
+    JOIN route r ON r.route_id = t.route_id AND NOT r.deleted
+    JOIN bus b ON b.bus_id = t.bus_id AND NOT b.deleted
+    JOIN route_itinerary a ON a.route_id = t.route_id
+    JOIN route_itinerary z ON z.route_id = t.route_id AND z.stop_order > a.stop_order
+    {SEGMENT_SEATS}
+    WHERE NOT t.cancelled
+      AND seats.available_seats > 0
+      AND t.start_date + a.offset_minutes * INTERVAL '1 minute' >= $3
+      AND t.start_date + a.offset_minutes * INTERVAL '1 minute' < $4
+    ORDER BY start_date, t.trip_id, a.stop_order, z.stop_order
+"#
+    )
+}
+
+#[derive(Debug, Clone, FromQueryResult)]
+struct LegRow {
+    trip_id: i32,
+    route_id: i32,
+    bus_id: i32,
+    start_date: NaiveDateTime,
+    end_date: NaiveDateTime,
+    from_stop: i32,
+    to_stop: i32,
+    from_location: String,
+    to_location: String,
+    bus_name: Option<String>,
+    license_plate: String,
+    seat_count: i32,
+    available_seats: i32,
+    min_price: Option<i32>,
+    max_price: Option<i32>,
+    from_key: String,
+    to_key: String,
+    boards_origin: bool,
+    reaches_destination: bool,
+}
+
+impl LegRow {
+    fn price(&self) -> i64 {
+        self.min_price.unwrap_or_default().into()
+    }
+}
+
+impl From<LegRow> for TripAvailability {
+    fn from(leg: LegRow) -> Self {
+        TripAvailability {
+            trip_id: leg.trip_id,
+            route_id: leg.route_id,
+            bus_id: leg.bus_id,
+            start_date: leg.start_date,
+            end_date: leg.end_date,
+            from_stop: leg.from_stop,
+            to_stop: leg.to_stop,
+            from_location: leg.from_location,
+            to_location: leg.to_location,
+            bus_name: leg.bus_name,
+            license_plate: leg.license_plate,
+            seat_count: leg.seat_count,
+            available_seats: leg.available_seats,
+            min_price: leg.min_price,
+            max_price: leg.max_price,
+        }
+    }
+}
+
+/// A partial itinerary: indices into the legs, ending where the last leg
+/// alights.
+#[derive(Debug, Clone)]
+struct Path {
+    legs: Vec<usize>,
+    arrival: NaiveDateTime,
+    price: i64,
+}
+
+impl Path {
+    fn transfers(&self) -> usize {
+        self.legs.len() - 1
+    }
+}
+
+pub struct JourneyServiceImpl {
+    window_hours: u32,
+}
+
+impl JourneyServiceImpl {
+    pub fn new(window_hours: u32) -> Self {
+        Self { window_hours }
+    }
+}
+
+/// Whether `(arrival, price)` is no better than a label already kept.
+fn dominated(labels: &[(NaiveDateTime, i64)], arrival: NaiveDateTime, price: i64) -> bool {
+    labels.iter().any(|&(a, p)| a <= arrival && p <= price)
+}
+
+/// Round-based search: round `n` extends the itineraries of round `n - 1`
+/// by one connection. An itinerary ends once it reaches the destination;
+/// otherwise it carries on only if no itinerary with as few transfers is
+/// already at the same stop no later and no dearer.
+fn search(legs: &[LegRow], query: &JourneyQuery) -> Vec<Path> {
+    let mut departures: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, leg) in legs.iter().enumerate() {
+        departures.entry(leg.from_key.as_str()).or_default().push(index);
+    }
+
+    let mut labels: HashMap<&str, Vec<(NaiveDateTime, i64)>> = HashMap::new();
+    let mut found = Vec::new();
+    let mut frontier = Vec::new();
+
+    let mut reach = |path: Path, frontier: &mut Vec<Path>| {
+        let last = &legs[*path.legs.last().expect("paths have legs")];
+        if last.reaches_destination {
+            found.push(path);
+            return;
+        }
+
+        let kept = labels.entry(last.to_key.as_str()).or_default();
+        if !dominated(kept, path.arrival, path.price) {
+            kept.push((path.arrival, path.price));
+            frontier.push(path);
+        }
+    };
+
+    for (index, leg) in legs.iter().enumerate().filter(|(_, leg)| leg.boards_origin) {
+        let path = Path {
+            legs: vec![index],
+            arrival: leg.end_date,
+            price: leg.price(),
+        };
+        reach(path, &mut frontier);
+    }
+
+    for _ in 0..query.max_transfers {
+        let mut next = Vec::new();
+        for path in &frontier {
+            let last = &legs[*path.legs.last().expect("paths have legs")];
+            let ready = path.arrival + query.min_connection;
+            let visited = |key: &str| {
+                legs[path.legs[0]].from_key == key || path.legs.iter().any(|&index| legs[index].to_key == key)
+            };
+
+            for &index in departures.get(last.to_key.as_str()).into_iter().flatten() {
+                let leg = &legs[index];
+                if leg.start_date < ready || leg.trip_id == last.trip_id || leg.boards_origin || visited(&leg.to_key) {
+                    continue;
+                }
+
+                let mut extended = path.clone();
+                extended.legs.push(index);
+                extended.arrival = leg.end_date;
+                extended.price += leg.price();
+                reach(extended, &mut next);
+            }
+        }
+
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    found
+}
+
+/// Sorts complete itineraries and keeps the first `limit` that no other beats
+/// on arrival, price and transfers at once.
+fn rank(mut paths: Vec<Path>, sort: JourneySort, limit: usize) -> Vec<Path> {
+    match sort {
+        JourneySort::Arrival => paths.sort_by_key(|path| (path.arrival, path.price, path.transfers())),
+        JourneySort::Price => paths.sort_by_key(|path| (path.price, path.arrival, path.transfers())),
+    }
+
+    // Sorted this way, whatever beats a path on all three counts comes
+    // before it.
+    let mut kept: Vec<Path> = Vec::new();
+    for path in paths {
+        let beaten = kept.iter().any(|other| {
+            other.arrival <= path.arrival && other.price <= path.price && other.transfers() <= path.transfers()
+        });
+        if !beaten {
+            kept.push(path);
+        }
+        if kept.len() == limit {
+            break;
+        }
+    }
+
+    kept
+}
+
+#[async_trait]
+impl super::JourneyService for JourneyServiceImpl {
+    async fn plan(&self, db: &DatabaseConnection, query: &JourneyQuery) -> Result<Vec<Journey>, JourneyError> {
+        let departure_until = query.departure_from + Duration::hours(self.window_hours.into());
+        let legs = LegRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            legs_query(),
+            [
+                contains_pattern(&Some(query.from_location.clone())).into(),
+                contains_pattern(&Some(query.to_location.clone())).into(),
+                query.departure_from.into(),
+                departure_until.into(),
+                TicketStatus::Cancelled.into_value().into(),
+                TicketStatus::Refunded.into_value().into(),
+                Utc::now().naive_utc().into(),
+            ],
+        ))
+        .all(db)
+        .await?;
+
+        let kept = rank(search(&legs, query), query.sort, query.limit);
+
+        Ok(kept
+            .into_iter()
+            .map(|path| Journey {
+                legs: path.legs.iter().map(|&index| legs[index].clone().into()).collect(),
+                price: path.price.try_into().unwrap_or(i32::MAX),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    const ORIGIN: &str = "ha noi";
+    const DESTINATION: &str = "da lat";
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 11, 1).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn leg(trip_id: i32, from: &str, to: &str, start: NaiveDateTime, end: NaiveDateTime, price: i32) -> LegRow {
+        LegRow {
+            trip_id,
+            route_id: trip_id,
+            bus_id: trip_id,
+            start_date: start,
+            end_date: end,
+            from_stop: 0,
+            to_stop: 1,
+            from_location: from.to_string(),
+            to_location: to.to_string(),
+            bus_name: None,
+            license_plate: format!("BUS-{trip_id}"),
+            seat_count: 30,
+            available_seats: 30,
+            min_price: Some(price),
+            max_price: Some(price),
+            from_key: from.to_string(),
+            to_key: to.to_string(),
+            boards_origin: from == ORIGIN,
+            reaches_destination: to == DESTINATION,
+        }
+    }
+
+    fn query(max_transfers: u32) -> JourneyQuery {
+        JourneyQuery {
+            from_location: ORIGIN.to_string(),
+            to_location: DESTINATION.to_string(),
+            departure_from: at(0, 0),
+            max_transfers,
+            min_connection: Duration::minutes(15),
+            sort: JourneySort::Arrival,
+            limit: 5,
+        }
+    }
+
+    fn path(legs: Vec<usize>, arrival: NaiveDateTime, price: i64) -> Path {
+        Path { legs, arrival, price }
+    }
+
+    fn trips(legs: &[LegRow], path: &Path) -> Vec<i32> {
+        path.legs.iter().map(|&index| legs[index].trip_id).collect()
+    }
+
+    #[test]
+    fn links_legs_that_leave_the_connection_time_free() {
+        let legs = [
+            leg(1, ORIGIN, "hue", at(8, 0), at(10, 0), 100),
+            leg(2, "hue", DESTINATION, at(10, 15), at(14, 0), 200),
+        ];
+
+        let found = search(&legs, &query(1));
+        assert_eq!(found.len(), 1);
+        assert_eq!(trips(&legs, &found[0]), [1, 2]);
+        assert_eq!((found[0].arrival, found[0].price, found[0].transfers()), (at(14, 0), 300, 1));
+    }
+
+    #[test]
+    fn skips_connections_shorter_than_the_minimum() {
+        let legs = [
+            leg(1, ORIGIN, "hue", at(8, 0), at(10, 0), 100),
+            leg(2, "hue", DESTINATION, at(10, 14), at(14, 0), 200),
+        ];
+
+        assert!(search(&legs, &query(1)).is_empty());
+    }
+
+    #[test]
+    fn stops_after_max_transfers() {
+        let legs = [
+            leg(1, ORIGIN, "hue", at(8, 0), at(10, 0), 100),
+            leg(2, "hue", "nha trang", at(11, 0), at(13, 0), 100),
+            leg(3, "nha trang", DESTINATION, at(14, 0), at(16, 0), 100),
+        ];
+
+        assert!(search(&legs, &query(0)).is_empty());
+        assert!(search(&legs, &query(1)).is_empty());
+
+        let found = search(&legs, &query(2));
+        assert_eq!(found.len(), 1);
+        assert_eq!(trips(&legs, &found[0]), [1, 2, 3]);
+    }
+
+    #[test]
+    fn does_not_extend_itineraries_beaten_at_the_same_stop() {
+        let legs = [
+            leg(1, ORIGIN, "hue", at(8, 0), at(10, 0), 100),
+            // Later and dearer to the same stop, so never extended.
+            leg(2, ORIGIN, "hue", at(8, 30), at(10, 30), 150),
+            leg(3, "hue", DESTINATION, at(11, 0), at(14, 0), 200),
+        ];
+
+        let found = search(&legs, &query(1));
+        assert_eq!(found.len(), 1);
+        assert_eq!(trips(&legs, &found[0]), [1, 3]);
+    }
+
+    #[test]
+    fn sorts_by_arrival_or_price() {
+        let fast = path(vec![0], at(12, 0), 500);
+        let cheap = path(vec![1, 2], at(15, 0), 300);
+
+        let by_arrival = rank(vec![cheap.clone(), fast.clone()], JourneySort::Arrival, 5);
+        assert_eq!(by_arrival.iter().map(|path| path.arrival).collect::<Vec<_>>(), [at(12, 0), at(15, 0)]);
+
+        let by_price = rank(vec![fast, cheap], JourneySort::Price, 5);
+        assert_eq!(by_price.iter().map(|path| path.price).collect::<Vec<_>>(), [300, 500]);
+    }
+
+    #[test]
+    fn drops_itineraries_beaten_on_every_count() {
+        let direct = path(vec![0], at(12, 0), 300);
+        let beaten = path(vec![1, 2], at(13, 0), 300);
+        let cheaper = path(vec![3, 4], at(13, 0), 250);
+
+        let kept = rank(vec![beaten, cheaper, direct], JourneySort::Arrival, 5);
+        assert_eq!(kept.iter().map(|path| path.legs.clone()).collect::<Vec<_>>(), [vec![0], vec![3, 4]]);
+    }
+
+    #[test]
+    fn keeps_at_most_limit_itineraries() {
+        let paths = (0..4).map(|hour| path(vec![hour], at(10 + hour as u32, 0), 400 - hour as i64 * 50)).collect();
+
+        assert_eq!(rank(paths, JourneySort::Arrival, 2).len(), 2);
+    }
+}
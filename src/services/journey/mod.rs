@@ -0,0 +1,56 @@
+mod errors;
+mod journey;
+
+pub use errors::JourneyError;
+pub use journey::JourneyServiceImpl;
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime};
+use sea_orm::DatabaseConnection;
+
+use crate::services::trip::TripAvailability;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JourneySort {
+    /// Earliest arrival first, then the cheapest.
+    Arrival,
+    /// Cheapest first, then the earliest arrival.
+    Price,
+}
+
+#[derive(Debug, Clone)]
+pub struct JourneyQuery {
+    /// Matched against the stops of every route ignoring case and accents.
+    pub from_location: String,
+    pub to_location: String,
+    /// Earliest departure of the first leg.
+    pub departure_from: NaiveDateTime,
+    pub max_transfers: u32,
+    /// Least time between arriving on one leg and leaving on the next.
+    pub min_connection: Duration,
+    pub sort: JourneySort,
+    pub limit: usize,
+}
+
+/// An itinerary of one or more legs, each a ride on a trip with seats left;
+/// the next leg boards where the previous one alights. `price` adds up the
+/// cheapest seat still for sale on every leg.
+#[derive(Debug, Clone)]
+pub struct Journey {
+    pub legs: Vec<TripAvailability>,
+    pub price: i32,
+}
+
+impl Journey {
+    pub fn transfers(&self) -> u32 {
+        self.legs.len().saturating_sub(1) as u32
+    }
+}
+
+#[async_trait]
+pub trait JourneyService: Send + Sync {
+    /// Itineraries over upcoming trips on active routes and buses with at
+    /// most `max_transfers` changes, leaving out any that another one beats
+    /// on arrival, price and transfers at once.
+    async fn plan(&self, db: &DatabaseConnection, query: &JourneyQuery) -> Result<Vec<Journey>, JourneyError>;
+}
@@ -3,11 +3,15 @@ use std::fmt;
 #[derive(Debug)]
 pub enum JWTServiceError {
     SignFailed,
+    InvalidToken,
 }
 
 impl fmt::Display for JWTServiceError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "SignFailed")
+        match self {
+            JWTServiceError::SignFailed => write!(f, "SignFailed"),
+            JWTServiceError::InvalidToken => write!(f, "InvalidToken"),
+        }
     }
 }
 
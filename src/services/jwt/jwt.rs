@@ -1,17 +1,17 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
-use libs::jwt::{build_claims, encode_token};
+use libs::jwt::{Claims, KeySet, build_claims};
 
 pub use super::JWTServiceError;
 
 pub struct JWTServiceImpl {
-    secret: String,
+    keys: Arc<KeySet>,
 }
 
 impl JWTServiceImpl {
-    pub fn new(secret: impl Into<String>) -> Self {
-        Self {
-            secret: secret.into(),
-        }
+    pub fn new(keys: Arc<KeySet>) -> Self {
+        Self { keys }
     }
 }
 
@@ -25,6 +25,14 @@ impl super::JWTService for JWTServiceImpl {
         policies: Vec<String>,
     ) -> Result<String, JWTServiceError> {
         let claims = build_claims(subject, ttl_seconds, roles, policies);
-        encode_token(&claims, self.secret.as_str()).map_err(|_| JWTServiceError::SignFailed)
+        self.keys.encode(&claims).map_err(|_| JWTServiceError::SignFailed)
+    }
+
+    async fn verify_token(&self, token: &str) -> Result<Claims, JWTServiceError> {
+        self.keys.decode(token).map_err(|_| JWTServiceError::InvalidToken)
+    }
+
+    fn key_set(&self) -> Arc<KeySet> {
+        self.keys.clone()
     }
 }
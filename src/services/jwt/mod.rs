@@ -4,7 +4,10 @@ mod errors;
 pub use jwt::JWTServiceImpl;
 pub use errors::JWTServiceError;
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
+use libs::jwt::{Claims, KeySet};
 
 #[async_trait]
 pub trait JWTService: Send + Sync {
@@ -15,4 +18,9 @@ pub trait JWTService: Send + Sync {
         roles: Vec<String>,
         policies: Vec<String>,
     ) -> Result<String, JWTServiceError>;
+
+    async fn verify_token(&self, token: &str) -> Result<Claims, JWTServiceError>;
+
+    /// Keys used by the middlewares and published on the JWKS endpoint.
+    fn key_set(&self) -> Arc<KeySet>;
 }
@@ -0,0 +1,16 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum LocationError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("location not found")]
+    LocationNotFound,
+    #[error("{0} is already the name or an alias of another location")]
+    NameTaken(String),
+    #[error("{0} is given more than once")]
+    DuplicateName(String),
+    #[error("a location cannot be merged into itself")]
+    MergeIntoItself,
+}
@@ -0,0 +1,329 @@
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr,
+    EntityTrait, FromQueryResult, QueryFilter, QueryOrder, QuerySelect, Statement, TransactionTrait,
+};
+
+use crate::entities::location::{
+    ActiveModel as LocationActiveModel, Column as LocationColumn, Entity as Location, Model as LocationModel,
+};
+use crate::entities::location_alias::{
+    ActiveModel as LocationAliasActiveModel, Column as LocationAliasColumn, Entity as LocationAlias,
+};
+use crate::entities::route::{Column as RouteColumn, Entity as Route};
+use crate::entities::route_stop::{Column as RouteStopColumn, Entity as RouteStopEntity};
+
+use super::{LocationDetails, LocationError, LocationInput, LocationSuggestion};
+
+/// Least `word_similarity` for a spelling that is not a prefix match to be
+/// offered.
+const MIN_SIMILARITY: f64 = 0.3;
+
+/// The best matching name or alias of every location, ranked 0 when it
+/// starts with the text, 1 when one of its words does and 2 otherwise.
+///
+/// $1 prefix pattern, $2 word prefix pattern, $3 text, $4 least similarity,
+/// $5 limit.
+const SUGGEST_QUERY: &str = r#"
+    SELECT l.location_id, l.name, l.province, l.latitude, l.longitude, m.term AS matched
+    FROM location l
+    CROSS JOIN LATERAL (
+        SELECT t.term,
+               CASE WHEN fold_location(t.term) LIKE fold_location($1) THEN 0
+                    WHEN fold_location(t.term) LIKE fold_location($2) THEN 1
+                    ELSE 2 END AS rank,
+               word_similarity(fold_location($3), fold_location(t.term)) AS score
+        FROM (
+            SELECT l.name AS term, TRUE AS is_name
+            UNION ALL
+            SELECT a.alias, FALSE FROM location_alias a WHERE a.location_id = l.location_id
+        ) t
+        ORDER BY rank, score DESC, t.is_name DESC, t.term
+        LIMIT 1
+    ) m
+    WHERE m.rank < 2 OR m.score >= $4
+    ORDER BY m.rank, m.score DESC, l.name
+    LIMIT $5
+"#;
+
+#[derive(FromQueryResult)]
+struct NameUse {
+    folded: String,
+    location_id: Option<i32>,
+}
+
+pub struct LocationServiceImpl;
+
+impl LocationServiceImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// The location named `name`, by its name or an alias, ignoring case and
+/// accents.
+pub async fn resolve<C: ConnectionTrait>(conn: &C, name: &str) -> Result<Option<LocationModel>, DbErr> {
+    Location::find()
+        .from_raw_sql(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT l.* FROM location l \
+             WHERE fold_location(l.name) = fold_location($1) \
+                OR EXISTS (SELECT 1 FROM location_alias a \
+                           WHERE a.location_id = l.location_id AND fold_location(a.alias) = fold_location($1)) \
+             LIMIT 1",
+            [name.trim().into()],
+        ))
+        .one(conn)
+        .await
+}
+
+/// Escapes `LIKE` wildcards so they match literally.
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// Fails with `DuplicateName` when two of `names` are the same ignoring case
+/// and accents, and with `NameTaken` when one is already a name or alias of
+/// a location other than `location_id`.
+async fn ensure_names_free<C: ConnectionTrait>(
+    conn: &C,
+    names: impl Iterator<Item = &str>,
+    location_id: Option<i32>,
+) -> Result<(), LocationError> {
+    let mut seen = Vec::new();
+    for name in names {
+        let usage = NameUse::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT fold_location($1) AS folded, \
+                    (SELECT l.location_id FROM location l \
+                     WHERE fold_location(l.name) = fold_location($1) \
+                        OR EXISTS (SELECT 1 FROM location_alias a \
+                                   WHERE a.location_id = l.location_id \
+                                     AND fold_location(a.alias) = fold_location($1)) \
+                     LIMIT 1) AS location_id",
+            [name.into()],
+        ))
+        .one(conn)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound("folded name".to_string()))?;
+
+        if seen.contains(&usage.folded) {
+            return Err(LocationError::DuplicateName(name.to_string()));
+        }
+        if usage.location_id.is_some() && usage.location_id != location_id {
+            return Err(LocationError::NameTaken(name.to_string()));
+        }
+        seen.push(usage.folded);
+    }
+
+    Ok(())
+}
+
+async fn aliases_of<C: ConnectionTrait>(conn: &C, location_id: i32) -> Result<Vec<String>, DbErr> {
+    Ok(LocationAlias::find()
+        .filter(LocationAliasColumn::LocationId.eq(location_id))
+        .order_by_asc(LocationAliasColumn::Alias)
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|alias| alias.alias)
+        .collect())
+}
+
+async fn replace_aliases<C: ConnectionTrait>(conn: &C, location_id: i32, aliases: Vec<String>) -> Result<(), DbErr> {
+    LocationAlias::delete_many()
+        .filter(LocationAliasColumn::LocationId.eq(location_id))
+        .exec(conn)
+        .await?;
+    if !aliases.is_empty() {
+        LocationAlias::insert_many(aliases.into_iter().map(|alias| LocationAliasActiveModel {
+            location_id: Set(location_id),
+            alias: Set(alias),
+        }))
+        .exec(conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Copies the location's name to the routes and stops at it.
+async fn rename_references<C: ConnectionTrait>(conn: &C, location: &LocationModel) -> Result<(), DbErr> {
+    Route::update_many()
+        .col_expr(RouteColumn::FromLocation, location.name.clone().into())
+        .filter(RouteColumn::FromLocationId.eq(location.location_id))
+        .exec(conn)
+        .await?;
+    Route::update_many()
+        .col_expr(RouteColumn::ToLocation, location.name.clone().into())
+        .filter(RouteColumn::ToLocationId.eq(location.location_id))
+        .exec(conn)
+        .await?;
+    RouteStopEntity::update_many()
+        .col_expr(RouteStopColumn::Location, location.name.clone().into())
+        .filter(RouteStopColumn::LocationId.eq(location.location_id))
+        .exec(conn)
+        .await?;
+
+    Ok(())
+}
+
+fn input_names(input: &LocationInput) -> impl Iterator<Item = &str> {
+    std::iter::once(input.name.as_str()).chain(input.aliases.iter().map(String::as_str))
+}
+
+#[async_trait]
+impl super::LocationService for LocationServiceImpl {
+    async fn suggest(
+        &self,
+        db: &DatabaseConnection,
+        text: &str,
+        limit: u64,
+    ) -> Result<Vec<LocationSuggestion>, LocationError> {
+        let text = text.trim();
+        let escaped = escape_like(text);
+
+        Ok(LocationSuggestion::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            SUGGEST_QUERY,
+            [
+                format!("{escaped}%").into(),
+                format!("% {escaped}%").into(),
+                text.into(),
+                MIN_SIMILARITY.into(),
+                (limit as i64).into(),
+            ],
+        ))
+        .all(db)
+        .await?)
+    }
+
+    async fn list_locations(&self, db: &DatabaseConnection) -> Result<Vec<LocationDetails>, LocationError> {
+        let locations = Location::find()
+            .find_with_related(LocationAlias)
+            .order_by_asc(LocationColumn::Name)
+            .all(db)
+            .await?;
+
+        Ok(locations
+            .into_iter()
+            .map(|(location, aliases)| {
+                let mut aliases: Vec<String> = aliases.into_iter().map(|alias| alias.alias).collect();
+                aliases.sort();
+                LocationDetails { location, aliases }
+            })
+            .collect())
+    }
+
+    async fn create_location(
+        &self,
+        db: &DatabaseConnection,
+        input: LocationInput,
+    ) -> Result<LocationDetails, LocationError> {
+        let txn = db.begin().await?;
+
+        ensure_names_free(&txn, input_names(&input), None).await?;
+        let location = LocationActiveModel {
+            name: Set(input.name),
+            province: Set(input.province),
+            latitude: Set(input.latitude),
+            longitude: Set(input.longitude),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        replace_aliases(&txn, location.location_id, input.aliases).await?;
+        let aliases = aliases_of(&txn, location.location_id).await?;
+        txn.commit().await?;
+
+        Ok(LocationDetails { location, aliases })
+    }
+
+    async fn update_location(
+        &self,
+        db: &DatabaseConnection,
+        location_id: i32,
+        input: LocationInput,
+    ) -> Result<LocationDetails, LocationError> {
+        let txn = db.begin().await?;
+
+        let location = Location::find_by_id(location_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(LocationError::LocationNotFound)?;
+        ensure_names_free(&txn, input_names(&input), Some(location_id)).await?;
+
+        let renamed = location.name != input.name;
+        let mut active: LocationActiveModel = location.into();
+        active.name = Set(input.name);
+        active.province = Set(input.province);
+        active.latitude = Set(input.latitude);
+        active.longitude = Set(input.longitude);
+        let location = active.update(&txn).await?;
+
+        if renamed {
+            rename_references(&txn, &location).await?;
+        }
+        replace_aliases(&txn, location_id, input.aliases).await?;
+        let aliases = aliases_of(&txn, location_id).await?;
+        txn.commit().await?;
+
+        Ok(LocationDetails { location, aliases })
+    }
+
+    async fn merge_location(
+        &self,
+        db: &DatabaseConnection,
+        location_id: i32,
+        into_location_id: i32,
+    ) -> Result<LocationDetails, LocationError> {
+        if location_id == into_location_id {
+            return Err(LocationError::MergeIntoItself);
+        }
+
+        let txn = db.begin().await?;
+
+        let location = Location::find_by_id(location_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(LocationError::LocationNotFound)?;
+        let into = Location::find_by_id(into_location_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(LocationError::LocationNotFound)?;
+
+        Route::update_many()
+            .col_expr(RouteColumn::FromLocationId, into.location_id.into())
+            .filter(RouteColumn::FromLocationId.eq(location_id))
+            .exec(&txn)
+            .await?;
+        Route::update_many()
+            .col_expr(RouteColumn::ToLocationId, into.location_id.into())
+            .filter(RouteColumn::ToLocationId.eq(location_id))
+            .exec(&txn)
+            .await?;
+        RouteStopEntity::update_many()
+            .col_expr(RouteStopColumn::LocationId, into.location_id.into())
+            .filter(RouteStopColumn::LocationId.eq(location_id))
+            .exec(&txn)
+            .await?;
+        rename_references(&txn, &into).await?;
+
+        // The merged names move over once their location is gone, since
+        // names and aliases are unique across the catalogue.
+        let mut aliases = aliases_of(&txn, into_location_id).await?;
+        aliases.push(location.name.clone());
+        aliases.extend(aliases_of(&txn, location_id).await?);
+        Location::delete_by_id(location_id).exec(&txn).await?;
+        replace_aliases(&txn, into_location_id, aliases).await?;
+
+        let aliases = aliases_of(&txn, into_location_id).await?;
+        txn.commit().await?;
+
+        Ok(LocationDetails { location: into, aliases })
+    }
+}
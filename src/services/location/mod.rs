@@ -0,0 +1,79 @@
+mod errors;
+mod location;
+
+pub use errors::LocationError;
+pub use location::{LocationServiceImpl, resolve};
+
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, FromQueryResult};
+
+use crate::entities::location::Model as LocationModel;
+
+/// Fields an admin sets on a catalogue location. Names and aliases must not
+/// match, ignoring case and accents, a name or alias of another location.
+#[derive(Debug, Clone)]
+pub struct LocationInput {
+    pub name: String,
+    pub province: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LocationDetails {
+    pub location: LocationModel,
+    pub aliases: Vec<String>,
+}
+
+/// A location offered for what a passenger typed; `matched` is the name or
+/// alias that matched best.
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct LocationSuggestion {
+    pub location_id: i32,
+    pub name: String,
+    pub province: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub matched: String,
+}
+
+#[async_trait]
+pub trait LocationService: Send + Sync {
+    /// Locations whose name or an alias starts with `text`, or has a word
+    /// that does, ignoring case and accents; then close spellings. Best
+    /// matches first.
+    async fn suggest(
+        &self,
+        db: &DatabaseConnection,
+        text: &str,
+        limit: u64,
+    ) -> Result<Vec<LocationSuggestion>, LocationError>;
+
+    /// The whole catalogue ordered by name.
+    async fn list_locations(&self, db: &DatabaseConnection) -> Result<Vec<LocationDetails>, LocationError>;
+
+    async fn create_location(
+        &self,
+        db: &DatabaseConnection,
+        input: LocationInput,
+    ) -> Result<LocationDetails, LocationError>;
+
+    /// Replaces the location's fields and aliases; a new name is copied to
+    /// the routes and stops at the location.
+    async fn update_location(
+        &self,
+        db: &DatabaseConnection,
+        location_id: i32,
+        input: LocationInput,
+    ) -> Result<LocationDetails, LocationError>;
+
+    /// Moves the routes and stops at `location_id` to `into_location_id`,
+    /// keeps its name and aliases as aliases there and deletes it.
+    async fn merge_location(
+        &self,
+        db: &DatabaseConnection,
+        location_id: i32,
+        into_location_id: i32,
+    ) -> Result<LocationDetails, LocationError>;
+}
@@ -0,0 +1,10 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum LoginThrottleError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("too many failed login attempts, retry after {retry_after} seconds")]
+    Locked { retry_after: u64 },
+}
@@ -0,0 +1,107 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime, Utc};
+
+use crate::services::auth::normalize_email;
+
+use super::{AttemptStore, LoginThrottleError};
+
+/// Limits and timings from `LOGIN_*`. A limit of 0 disables that side.
+#[derive(Debug, Clone)]
+pub struct LoginThrottlePolicy {
+    pub max_account_failures: u32,
+    pub max_ip_failures: u32,
+    pub failure_window: u64,
+    pub lockout: u64,
+}
+
+pub struct LoginThrottleServiceImpl {
+    store: Arc<dyn AttemptStore>,
+    policy: LoginThrottlePolicy,
+}
+
+impl LoginThrottleServiceImpl {
+    pub fn new(store: Arc<dyn AttemptStore>, policy: LoginThrottlePolicy) -> Self {
+        Self { store, policy }
+    }
+
+    fn window_start(&self, now: NaiveDateTime) -> NaiveDateTime {
+        now - Duration::seconds(self.policy.failure_window as i64)
+    }
+
+    /// Seconds left on the lockout of `key`, if any.
+    async fn locked_for(&self, key: &str, now: NaiveDateTime) -> Result<Option<u64>, LoginThrottleError> {
+        let locked_until = self.store.get(key).await?.and_then(|record| record.locked_until);
+
+        Ok(locked_until
+            .filter(|until| *until > now)
+            .map(|until| (until - now).num_seconds().max(1) as u64))
+    }
+
+    async fn count_failure(&self, key: &str, max: u32, now: NaiveDateTime) -> Result<(), LoginThrottleError> {
+        if max == 0 {
+            return Ok(());
+        }
+
+        let failures = self.store.record_failure(key, now, self.window_start(now)).await?;
+        if failures >= max {
+            self.store
+                .lock(key, now + Duration::seconds(self.policy.lockout as i64))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn account_key(username: &str) -> String {
+    format!("user:{}", normalize_email(username))
+}
+
+fn ip_key(ip: IpAddr) -> String {
+    format!("ip:{ip}")
+}
+
+#[async_trait]
+impl super::LoginThrottleService for LoginThrottleServiceImpl {
+    async fn check(&self, username: &str, ip: Option<IpAddr>) -> Result<(), LoginThrottleError> {
+        let now = Utc::now().naive_utc();
+
+        let mut retry_after = self.locked_for(&account_key(username), now).await?;
+        if let Some(ip) = ip {
+            retry_after = retry_after.max(self.locked_for(&ip_key(ip), now).await?);
+        }
+
+        match retry_after {
+            Some(retry_after) => Err(LoginThrottleError::Locked { retry_after }),
+            None => Ok(()),
+        }
+    }
+
+    async fn record_failure(&self, username: &str, ip: Option<IpAddr>) -> Result<(), LoginThrottleError> {
+        let now = Utc::now().naive_utc();
+
+        self.count_failure(&account_key(username), self.policy.max_account_failures, now)
+            .await?;
+        if let Some(ip) = ip {
+            self.count_failure(&ip_key(ip), self.policy.max_ip_failures, now).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn record_success(&self, username: &str) -> Result<(), LoginThrottleError> {
+        self.store.clear(&account_key(username)).await
+    }
+
+    async fn unlock(&self, username: &str) -> Result<(), LoginThrottleError> {
+        self.store.clear(&account_key(username)).await
+    }
+
+    async fn purge(&self) -> Result<(), LoginThrottleError> {
+        let now = Utc::now().naive_utc();
+        self.store.purge(now, self.window_start(now)).await
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+use super::{AttemptRecord, LoginThrottleError};
+
+#[derive(Default)]
+pub struct MemoryAttemptStore {
+    records: Mutex<HashMap<String, AttemptRecord>>,
+}
+
+impl MemoryAttemptStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl super::AttemptStore for MemoryAttemptStore {
+    async fn get(&self, key: &str) -> Result<Option<AttemptRecord>, LoginThrottleError> {
+        Ok(self.records.lock().unwrap().get(key).cloned())
+    }
+
+    async fn record_failure(
+        &self,
+        key: &str,
+        now: NaiveDateTime,
+        window_start: NaiveDateTime,
+    ) -> Result<u32, LoginThrottleError> {
+        let mut records = self.records.lock().unwrap();
+        let record = records.entry(key.to_string()).or_insert(AttemptRecord {
+            failures: 0,
+            window_started: now,
+            locked_until: None,
+        });
+
+        if record.window_started < window_start {
+            record.failures = 0;
+            record.window_started = now;
+        }
+        record.failures += 1;
+
+        Ok(record.failures)
+    }
+
+    async fn lock(&self, key: &str, until: NaiveDateTime) -> Result<(), LoginThrottleError> {
+        if let Some(record) = self.records.lock().unwrap().get_mut(key) {
+            record.failures = 0;
+            record.locked_until = Some(until);
+        }
+        Ok(())
+    }
+
+    async fn clear(&self, key: &str) -> Result<(), LoginThrottleError> {
+        self.records.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    async fn purge(&self, now: NaiveDateTime, window_start: NaiveDateTime) -> Result<(), LoginThrottleError> {
+        self.records.lock().unwrap().retain(|_, record| {
+            record.window_started >= window_start || record.locked_until.is_some_and(|until| until > now)
+        });
+        Ok(())
+    }
+}
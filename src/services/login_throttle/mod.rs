@@ -0,0 +1,62 @@
+mod errors;
+mod login_throttle;
+mod memory_store;
+mod postgres_store;
+
+pub use errors::LoginThrottleError;
+pub use login_throttle::{LoginThrottlePolicy, LoginThrottleServiceImpl};
+pub use memory_store::MemoryAttemptStore;
+pub use postgres_store::PostgresAttemptStore;
+
+use std::net::IpAddr;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttemptRecord {
+    pub failures: u32,
+    pub window_started: NaiveDateTime,
+    pub locked_until: Option<NaiveDateTime>,
+}
+
+/// Failure counters keyed by account or client IP. The in-memory store only
+/// sees its own node; the Postgres one is shared by every node.
+#[async_trait]
+pub trait AttemptStore: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<AttemptRecord>, LoginThrottleError>;
+
+    /// Counts one failure and returns the total in the current window. A
+    /// window that started before `window_start` is restarted at `now`.
+    async fn record_failure(
+        &self,
+        key: &str,
+        now: NaiveDateTime,
+        window_start: NaiveDateTime,
+    ) -> Result<u32, LoginThrottleError>;
+
+    /// Locks `key` until `until` and starts its count over.
+    async fn lock(&self, key: &str, until: NaiveDateTime) -> Result<(), LoginThrottleError>;
+
+    async fn clear(&self, key: &str) -> Result<(), LoginThrottleError>;
+
+    /// Drops records that are neither locked nor inside a live window.
+    async fn purge(&self, now: NaiveDateTime, window_start: NaiveDateTime) -> Result<(), LoginThrottleError>;
+}
+
+#[async_trait]
+pub trait LoginThrottleService: Send + Sync {
+    /// Fails with `Locked` while the account or the client IP is locked out.
+    async fn check(&self, username: &str, ip: Option<IpAddr>) -> Result<(), LoginThrottleError>;
+
+    async fn record_failure(&self, username: &str, ip: Option<IpAddr>) -> Result<(), LoginThrottleError>;
+
+    /// Resets the account's counter. The IP counter is left alone so one
+    /// valid account cannot be used to keep guessing others.
+    async fn record_success(&self, username: &str) -> Result<(), LoginThrottleError>;
+
+    /// Lifts an account lockout early.
+    async fn unlock(&self, username: &str) -> Result<(), LoginThrottleError>;
+
+    async fn purge(&self) -> Result<(), LoginThrottleError>;
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait, QueryFilter, Statement};
+
+use crate::entities::login_attempt::{Column as LoginAttemptColumn, Entity as LoginAttempt};
+
+use super::{AttemptRecord, LoginThrottleError};
+
+pub struct PostgresAttemptStore {
+    db: Arc<DatabaseConnection>,
+}
+
+impl PostgresAttemptStore {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl super::AttemptStore for PostgresAttemptStore {
+    async fn get(&self, key: &str) -> Result<Option<AttemptRecord>, LoginThrottleError> {
+        Ok(LoginAttempt::find_by_id(key).one(self.db.as_ref()).await?.map(|row| AttemptRecord {
+            failures: row.failures.max(0) as u32,
+            window_started: row.window_started,
+            locked_until: row.locked_until,
+        }))
+    }
+
+    async fn record_failure(
+        &self,
+        key: &str,
+        now: NaiveDateTime,
+        window_start: NaiveDateTime,
+    ) -> Result<u32, LoginThrottleError> {
+        // One upsert so concurrent failures on different nodes all count.
+        let row = self
+            .db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"INSERT INTO login_attempt (attempt_key, failures, window_started)
+                   VALUES ($1, 1, $2)
+                   ON CONFLICT (attempt_key) DO UPDATE SET
+                       failures = CASE WHEN login_attempt.window_started < $3 THEN 1
+                                       ELSE login_attempt.failures + 1 END,
+                       window_started = CASE WHEN login_attempt.window_started < $3 THEN $2
+                                             ELSE login_attempt.window_started END
+                   RETURNING failures"#,
+                [key.into(), now.into(), window_start.into()],
+            ))
+            .await?;
+
+        let failures = match row {
+            Some(row) => row.try_get::<i32>("", "failures")?,
+            None => 1,
+        };
+
+        Ok(failures.max(0) as u32)
+    }
+
+    async fn lock(&self, key: &str, until: NaiveDateTime) -> Result<(), LoginThrottleError> {
+        LoginAttempt::update_many()
+            .col_expr(LoginAttemptColumn::Failures, Expr::value(0))
+            .col_expr(LoginAttemptColumn::LockedUntil, Expr::value(until))
+            .filter(LoginAttemptColumn::AttemptKey.eq(key))
+            .exec(self.db.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn clear(&self, key: &str) -> Result<(), LoginThrottleError> {
+        LoginAttempt::delete_by_id(key).exec(self.db.as_ref()).await?;
+        Ok(())
+    }
+
+    async fn purge(&self, now: NaiveDateTime, window_start: NaiveDateTime) -> Result<(), LoginThrottleError> {
+        LoginAttempt::delete_many()
+            .filter(LoginAttemptColumn::WindowStarted.lt(window_start))
+            .filter(
+                LoginAttemptColumn::LockedUntil
+                    .is_null()
+                    .or(LoginAttemptColumn::LockedUntil.lte(now)),
+            )
+            .exec(self.db.as_ref())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MailerError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::{Mail, MailerError};
+
+/// Drops every mail as an `.eml` file into a directory.
+pub struct FileMailer {
+    from: String,
+    dir: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(from: String, dir: impl Into<PathBuf>) -> Self {
+        Self { from, dir: dir.into() }
+    }
+}
+
+#[async_trait]
+impl super::Mailer for FileMailer {
+    async fn send(&self, mail: Mail) -> Result<(), MailerError> {
+        let now = Utc::now();
+        let path = self.dir.join(format!(
+            "{}-{:08x}.eml",
+            now.format("%Y%m%dT%H%M%S%.3f"),
+            rand::random::<u32>()
+        ));
+        let contents = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            self.from,
+            mail.to,
+            mail.subject,
+            now.to_rfc2822(),
+            mail.body
+        );
+
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(path, contents).await?;
+        Ok(())
+    }
+}
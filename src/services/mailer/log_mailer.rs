@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use tracing::info;
+
+use super::{Mail, MailerError};
+
+/// Writes every mail to the log instead of delivering it.
+pub struct LogMailer {
+    from: String,
+}
+
+impl LogMailer {
+    pub fn new(from: String) -> Self {
+        Self { from }
+    }
+}
+
+#[async_trait]
+impl super::Mailer for LogMailer {
+    async fn send(&self, mail: Mail) -> Result<(), MailerError> {
+        info!(from = %self.from, to = %mail.to, subject = %mail.subject, "mail:\n{}", mail.body);
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+mod errors;
+mod file_mailer;
+mod log_mailer;
+
+pub use errors::MailerError;
+pub use file_mailer::FileMailer;
+pub use log_mailer::LogMailer;
+
+use async_trait::async_trait;
+
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Outgoing mail transport. Only local transports exist so far; an SMTP or
+/// provider-backed one plugs in here.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, mail: Mail) -> Result<(), MailerError>;
+}
@@ -0,0 +1,24 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+use crate::services::login_throttle::LoginThrottleError;
+
+#[derive(Debug, Error)]
+pub enum MfaError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("invalid or expired mfa token")]
+    InvalidChallenge,
+    #[error("invalid mfa code")]
+    InvalidCode,
+    #[error("two-factor authentication is not enrolled")]
+    NotEnrolled,
+    #[error("two-factor authentication is already enabled")]
+    AlreadyEnabled,
+    #[error("two-factor authentication is required for this role")]
+    Required,
+    #[error("too many wrong mfa codes, retry after {retry_after} seconds")]
+    Locked { retry_after: u64 },
+    #[error(transparent)]
+    Throttle(#[from] LoginThrottleError),
+}
@@ -0,0 +1,411 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    TransactionTrait,
+};
+
+use crate::entities::mfa_challenge::{
+    ActiveModel as MfaChallengeActiveModel, Column as MfaChallengeColumn, Entity as MfaChallenge,
+    Model as MfaChallengeModel,
+};
+use crate::entities::mfa_recovery_code::{
+    ActiveModel as MfaRecoveryCodeActiveModel, Column as MfaRecoveryCodeColumn, Entity as MfaRecoveryCode,
+};
+use crate::entities::role::Entity as Role;
+use crate::entities::user::{Entity as User, Model as UserModel};
+use crate::entities::user_totp::{
+    ActiveModel as UserTotpActiveModel, Column as UserTotpColumn, Entity as UserTotp, Model as UserTotpModel,
+};
+use crate::services::login_throttle::AttemptStore;
+use crate::services::opaque_token;
+
+use super::{MfaError, MfaStep, TotpEnrollment, VerifiedChallenge, totp};
+
+/// Wrong codes allowed per challenge before it is thrown away and the
+/// password has to be entered again.
+const MAX_CHALLENGE_ATTEMPTS: i32 = 5;
+/// Wrong codes allowed per account within the failure window before every
+/// code check is locked out, so new challenges and the account endpoints
+/// cannot be used to keep guessing.
+const MAX_CODE_FAILURES: u32 = 5;
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+pub struct MfaServiceImpl {
+    issuer: String,
+    challenge_ttl_seconds: u64,
+    attempt_store: Arc<dyn AttemptStore>,
+    failure_window: u64,
+    lockout: u64,
+}
+
+impl MfaServiceImpl {
+    /// Wrong codes are counted in `attempt_store` over `failure_window`
+    /// seconds and lock the account's code checks for `lockout` seconds.
+    pub fn new(
+        issuer: String,
+        challenge_ttl_seconds: u64,
+        attempt_store: Arc<dyn AttemptStore>,
+        failure_window: u64,
+        lockout: u64,
+    ) -> Self {
+        Self {
+            issuer,
+            challenge_ttl_seconds,
+            attempt_store,
+            failure_window,
+            lockout,
+        }
+    }
+
+    /// `accept_code` behind the account's failure counter: fails with
+    /// `Locked` while it is locked out, and counts a wrong code towards
+    /// `MAX_CODE_FAILURES`.
+    async fn check_code<C: ConnectionTrait>(
+        &self,
+        conn: &C,
+        totp: &UserTotpModel,
+        code: &str,
+        allow_recovery: bool,
+    ) -> Result<(), MfaError> {
+        let key = attempt_key(totp.user_id);
+        let now = Utc::now().naive_utc();
+
+        let locked_until = self.attempt_store.get(&key).await?.and_then(|record| record.locked_until);
+        if let Some(until) = locked_until.filter(|until| *until > now) {
+            return Err(MfaError::Locked {
+                retry_after: (until - now).num_seconds().max(1) as u64,
+            });
+        }
+
+        if accept_code(conn, totp, code, allow_recovery).await? {
+            self.attempt_store.clear(&key).await?;
+            return Ok(());
+        }
+
+        let failures = self.attempt_store.record_failure(&key, now, self.window_start(now)).await?;
+        if failures >= MAX_CODE_FAILURES {
+            self.attempt_store
+                .lock(&key, now + Duration::seconds(self.lockout as i64))
+                .await?;
+        }
+
+        Err(MfaError::InvalidCode)
+    }
+
+    fn window_start(&self, now: NaiveDateTime) -> NaiveDateTime {
+        now - Duration::seconds(self.failure_window as i64)
+    }
+}
+
+fn attempt_key(user_id: i32) -> String {
+    format!("mfa:{user_id}")
+}
+
+/// Ten characters in two groups, without look-alike characters.
+fn generate_recovery_code() -> String {
+    let chars: String = (0..10)
+        .map(|_| RECOVERY_CODE_ALPHABET[rand::random_range(0..RECOVERY_CODE_ALPHABET.len())] as char)
+        .collect();
+    format!("{}-{}", &chars[..5], &chars[5..])
+}
+
+/// Recovery codes are compared case-insensitively and without separators.
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    opaque_token::hash(&normalized)
+}
+
+async fn find_totp<C: ConnectionTrait>(conn: &C, user_id: i32) -> Result<Option<UserTotpModel>, MfaError> {
+    Ok(UserTotp::find_by_id(user_id).one(conn).await?)
+}
+
+async fn role_requires_mfa<C: ConnectionTrait>(conn: &C, role_id: i32) -> Result<bool, MfaError> {
+    Ok(Role::find_by_id(role_id)
+        .one(conn)
+        .await?
+        .is_some_and(|role| role.mfa_required))
+}
+
+async fn replace_recovery_codes<C: ConnectionTrait>(conn: &C, user_id: i32) -> Result<Vec<String>, MfaError> {
+    MfaRecoveryCode::delete_many()
+        .filter(MfaRecoveryCodeColumn::UserId.eq(user_id))
+        .exec(conn)
+        .await?;
+
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| generate_recovery_code()).collect();
+    MfaRecoveryCode::insert_many(codes.iter().map(|code| MfaRecoveryCodeActiveModel {
+        user_id: Set(user_id),
+        code_hash: Set(hash_recovery_code(code)),
+        used_at: Set(None),
+        ..Default::default()
+    }))
+    .exec(conn)
+    .await?;
+
+    Ok(codes)
+}
+
+/// Accepts a TOTP code, or a recovery code when `allow_recovery` is set.
+/// Both are consumed with a conditional update, so racing requests cannot
+/// use the same code twice.
+async fn accept_code<C: ConnectionTrait>(
+    conn: &C,
+    totp: &UserTotpModel,
+    code: &str,
+    allow_recovery: bool,
+) -> Result<bool, MfaError> {
+    let now = Utc::now();
+
+    if let Some(step) = totp::verify(&totp.secret, code, now.timestamp(), totp.last_used_step) {
+        let result = UserTotp::update_many()
+            .col_expr(UserTotpColumn::LastUsedStep, Expr::value(step))
+            .filter(UserTotpColumn::UserId.eq(totp.user_id))
+            .filter(
+                UserTotpColumn::LastUsedStep
+                    .is_null()
+                    .or(UserTotpColumn::LastUsedStep.lt(step)),
+            )
+            .exec(conn)
+            .await?;
+        return Ok(result.rows_affected == 1);
+    }
+
+    if !allow_recovery {
+        return Ok(false);
+    }
+
+    let result = MfaRecoveryCode::update_many()
+        .col_expr(MfaRecoveryCodeColumn::UsedAt, Expr::value(now.naive_utc()))
+        .filter(MfaRecoveryCodeColumn::UserId.eq(totp.user_id))
+        .filter(MfaRecoveryCodeColumn::CodeHash.eq(hash_recovery_code(code)))
+        .filter(MfaRecoveryCodeColumn::UsedAt.is_null())
+        .exec(conn)
+        .await?;
+
+    Ok(result.rows_affected == 1)
+}
+
+async fn find_challenge<C: ConnectionTrait>(conn: &C, token: &str) -> Result<MfaChallengeModel, MfaError> {
+    let challenge = MfaChallenge::find_by_id(opaque_token::hash(token))
+        .one(conn)
+        .await?
+        .ok_or(MfaError::InvalidChallenge)?;
+
+    if challenge.expires_at <= Utc::now().naive_utc() {
+        MfaChallenge::delete_by_id(challenge.token_hash).exec(conn).await?;
+        return Err(MfaError::InvalidChallenge);
+    }
+
+    Ok(challenge)
+}
+
+/// Counts a wrong code against the challenge and drops it once it runs out.
+async fn fail_challenge<C: ConnectionTrait>(conn: &C, challenge: &MfaChallengeModel) -> Result<(), MfaError> {
+    if challenge.attempts + 1 >= MAX_CHALLENGE_ATTEMPTS {
+        MfaChallenge::delete_by_id(challenge.token_hash.clone()).exec(conn).await?;
+    } else {
+        MfaChallenge::update_many()
+            .col_expr(MfaChallengeColumn::Attempts, Expr::col(MfaChallengeColumn::Attempts).add(1))
+            .filter(MfaChallengeColumn::TokenHash.eq(challenge.token_hash.clone()))
+            .exec(conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl super::MfaService for MfaServiceImpl {
+    async fn login_step(&self, db: &DatabaseConnection, user: &UserModel) -> Result<Option<MfaStep>, MfaError> {
+        if find_totp(db, user.user_id).await?.is_some_and(|totp| totp.confirmed_at.is_some()) {
+            return Ok(Some(MfaStep::Verify));
+        }
+
+        if role_requires_mfa(db, user.role_id).await? {
+            return Ok(Some(MfaStep::Enroll));
+        }
+
+        Ok(None)
+    }
+
+    async fn start_challenge(&self, db: &DatabaseConnection, user_id: i32, cookie: bool) -> Result<String, MfaError> {
+        let token = opaque_token::generate(32);
+        let now = Utc::now().naive_utc();
+
+        MfaChallenge::delete_many()
+            .filter(MfaChallengeColumn::UserId.eq(user_id))
+            .filter(MfaChallengeColumn::ExpiresAt.lte(now))
+            .exec(db)
+            .await?;
+
+        MfaChallengeActiveModel {
+            token_hash: Set(opaque_token::hash(&token)),
+            user_id: Set(user_id),
+            cookie: Set(cookie),
+            attempts: Set(0),
+            expires_at: Set(now + Duration::seconds(self.challenge_ttl_seconds as i64)),
+            created_at: Set(now),
+        }
+        .insert(db)
+        .await?;
+
+        Ok(token)
+    }
+
+    async fn enroll_challenge(&self, db: &DatabaseConnection, token: &str) -> Result<TotpEnrollment, MfaError> {
+        let challenge = find_challenge(db, token).await?;
+        let user = User::find_by_id(challenge.user_id)
+            .one(db)
+            .await?
+            .ok_or(MfaError::InvalidChallenge)?;
+
+        self.enroll(db, &user).await
+    }
+
+    async fn verify_challenge(
+        &self,
+        db: &DatabaseConnection,
+        token: &str,
+        code: &str,
+    ) -> Result<VerifiedChallenge, MfaError> {
+        let challenge = find_challenge(db, token).await?;
+        let totp = find_totp(db, challenge.user_id).await?.ok_or(MfaError::NotEnrolled)?;
+        let confirming = totp.confirmed_at.is_none();
+
+        if let Err(err) = self.check_code(db, &totp, code, !confirming).await {
+            if matches!(err, MfaError::InvalidCode) {
+                fail_challenge(db, &challenge).await?;
+            }
+            return Err(err);
+        }
+
+        let txn = db.begin().await?;
+
+        // Deleting the challenge is what consumes it; a concurrent request
+        // that got here first leaves nothing to delete.
+        let consumed = MfaChallenge::delete_by_id(challenge.token_hash)
+            .exec(&txn)
+            .await?
+            .rows_affected;
+        if consumed != 1 {
+            return Err(MfaError::InvalidChallenge);
+        }
+
+        let recovery_codes = if confirming {
+            let mut active: UserTotpActiveModel = totp.into();
+            active.confirmed_at = Set(Some(Utc::now().naive_utc()));
+            active.update(&txn).await?;
+            replace_recovery_codes(&txn, challenge.user_id).await?
+        } else {
+            Vec::new()
+        };
+
+        let user = User::find_by_id(challenge.user_id)
+            .one(&txn)
+            .await?
+            .ok_or(MfaError::InvalidChallenge)?;
+        txn.commit().await?;
+
+        Ok(VerifiedChallenge {
+            user,
+            cookie: challenge.cookie,
+            recovery_codes,
+        })
+    }
+
+    async fn enroll(&self, db: &DatabaseConnection, user: &UserModel) -> Result<TotpEnrollment, MfaError> {
+        let txn = db.begin().await?;
+
+        if let Some(totp) = find_totp(&txn, user.user_id).await? {
+            if totp.confirmed_at.is_some() {
+                return Err(MfaError::AlreadyEnabled);
+            }
+            UserTotp::delete_by_id(user.user_id).exec(&txn).await?;
+        }
+
+        let secret = totp::generate_secret();
+        UserTotpActiveModel {
+            user_id: Set(user.user_id),
+            secret: Set(secret.clone()),
+            confirmed_at: Set(None),
+            last_used_step: Set(None),
+            created_at: Set(Utc::now().naive_utc()),
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+
+        Ok(TotpEnrollment {
+            otpauth_uri: totp::otpauth_uri(&self.issuer, &user.email, &secret),
+            secret,
+        })
+    }
+
+    async fn confirm(&self, db: &DatabaseConnection, user_id: i32, code: &str) -> Result<Vec<String>, MfaError> {
+        let totp = find_totp(db, user_id).await?.ok_or(MfaError::NotEnrolled)?;
+        if totp.confirmed_at.is_some() {
+            return Err(MfaError::AlreadyEnabled);
+        }
+
+        self.check_code(db, &totp, code, false).await?;
+
+        let txn = db.begin().await?;
+        UserTotp::update_many()
+            .col_expr(UserTotpColumn::ConfirmedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(UserTotpColumn::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+        let codes = replace_recovery_codes(&txn, user_id).await?;
+        txn.commit().await?;
+
+        Ok(codes)
+    }
+
+    async fn regenerate_recovery_codes(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        code: &str,
+    ) -> Result<Vec<String>, MfaError> {
+        let totp = find_totp(db, user_id)
+            .await?
+            .filter(|totp| totp.confirmed_at.is_some())
+            .ok_or(MfaError::NotEnrolled)?;
+
+        self.check_code(db, &totp, code, false).await?;
+
+        replace_recovery_codes(db, user_id).await
+    }
+
+    async fn disable(&self, db: &DatabaseConnection, user: &UserModel, code: &str) -> Result<(), MfaError> {
+        if role_requires_mfa(db, user.role_id).await? {
+            return Err(MfaError::Required);
+        }
+
+        let totp = find_totp(db, user.user_id).await?.ok_or(MfaError::NotEnrolled)?;
+
+        // A pending enrollment never protected anything and is dropped as is.
+        if totp.confirmed_at.is_some() {
+            self.check_code(db, &totp, code, true).await?;
+        }
+
+        let txn = db.begin().await?;
+        UserTotp::delete_by_id(user.user_id).exec(&txn).await?;
+        MfaRecoveryCode::delete_many()
+            .filter(MfaRecoveryCodeColumn::UserId.eq(user.user_id))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+}
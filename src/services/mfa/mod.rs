@@ -0,0 +1,75 @@
+mod errors;
+mod mfa;
+pub mod totp;
+
+pub use errors::MfaError;
+pub use mfa::MfaServiceImpl;
+
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+
+use crate::entities::user::Model as UserModel;
+
+/// What a login still needs after the password was accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MfaStep {
+    /// The account has TOTP enabled and must present a code.
+    Verify,
+    /// The user's role requires TOTP but none is enabled yet.
+    Enroll,
+}
+
+/// A freshly generated secret, not usable until confirmed with a code.
+#[derive(Debug, Clone)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+/// A completed second step of a login.
+#[derive(Debug, Clone)]
+pub struct VerifiedChallenge {
+    pub user: UserModel,
+    /// The login started as `LoginCookie` and should finish with cookies.
+    pub cookie: bool,
+    /// Set only when the challenge also confirmed a new enrollment.
+    pub recovery_codes: Vec<String>,
+}
+
+#[async_trait]
+pub trait MfaService: Send + Sync {
+    async fn login_step(&self, db: &DatabaseConnection, user: &UserModel) -> Result<Option<MfaStep>, MfaError>;
+
+    /// Opens a short-lived challenge and returns its opaque token.
+    async fn start_challenge(&self, db: &DatabaseConnection, user_id: i32, cookie: bool) -> Result<String, MfaError>;
+
+    /// Enrolls the user behind a challenge, for roles that must enroll
+    /// before they can log in at all.
+    async fn enroll_challenge(&self, db: &DatabaseConnection, token: &str) -> Result<TotpEnrollment, MfaError>;
+
+    /// Accepts a TOTP or recovery code for the challenge and consumes it. A
+    /// TOTP code for a pending enrollment confirms the enrollment.
+    async fn verify_challenge(
+        &self,
+        db: &DatabaseConnection,
+        token: &str,
+        code: &str,
+    ) -> Result<VerifiedChallenge, MfaError>;
+
+    /// Generates a new secret, replacing any unconfirmed one.
+    async fn enroll(&self, db: &DatabaseConnection, user: &UserModel) -> Result<TotpEnrollment, MfaError>;
+
+    /// Enables the pending secret and returns fresh recovery codes.
+    async fn confirm(&self, db: &DatabaseConnection, user_id: i32, code: &str) -> Result<Vec<String>, MfaError>;
+
+    /// Replaces all recovery codes after checking a current code.
+    async fn regenerate_recovery_codes(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        code: &str,
+    ) -> Result<Vec<String>, MfaError>;
+
+    /// Fails with `Required` while the user's role enforces TOTP.
+    async fn disable(&self, db: &DatabaseConnection, user: &UserModel, code: &str) -> Result<(), MfaError>;
+}
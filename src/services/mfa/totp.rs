@@ -0,0 +1,163 @@
+//! RFC 6238 time-based one-time passwords: HMAC-SHA1, 6 digits, 30 second steps.
+
+use hmac::{Hmac, Mac};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use sha1::Sha1;
+
+const STEP_SECONDS: i64 = 30;
+const DIGITS: u32 = 6;
+/// Codes from one step either side are accepted to absorb clock drift.
+const SKEW_STEPS: i64 = 1;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+/// Everything but RFC 3986 unreserved characters is escaped in the URI label.
+const URI_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+/// A random 160-bit secret, base32 encoded as authenticator apps expect.
+pub fn generate_secret() -> String {
+    let bytes: Vec<u8> = (0..20).map(|_| rand::random::<u8>()).collect();
+    base32_encode(&bytes)
+}
+
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let issuer = utf8_percent_encode(issuer, URI_COMPONENT).to_string();
+    let account = utf8_percent_encode(account, URI_COMPONENT).to_string();
+
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={DIGITS}&period={STEP_SECONDS}"
+    )
+}
+
+/// Checks `code` against the steps around `unix_time` and returns the step it
+/// matched. Steps at or before `last_used_step` are refused so a code cannot
+/// be replayed.
+pub fn verify(secret: &str, code: &str, unix_time: i64, last_used_step: Option<i64>) -> Option<i64> {
+    let key = base32_decode(secret)?;
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let current = unix_time.div_euclid(STEP_SECONDS);
+    (current - SKEW_STEPS..=current + SKEW_STEPS)
+        .filter(|step| last_used_step.is_none_or(|last| *step > last))
+        .find(|step| hotp(&key, *step as u64) == code)
+}
+
+fn hotp(key: &[u8], counter: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let value = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]])
+        & 0x7fff_ffff;
+
+    format!("{:0width$}", value % 10u32.pow(DIGITS), width = DIGITS as usize)
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    out
+}
+
+fn base32_decode(value: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in value.trim_end_matches('=').bytes() {
+        let index = BASE32_ALPHABET.iter().position(|&a| a == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | index;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The RFC 6238 SHA-1 test key, "12345678901234567890".
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn matches_rfc_6238_vectors() {
+        // The RFC lists 8 digits; these are their last 6.
+        let vectors = [
+            (59, "287082"),
+            (1_111_111_109, "081804"),
+            (1_111_111_111, "050471"),
+            (1_234_567_890, "005924"),
+            (2_000_000_000, "279037"),
+            (20_000_000_000, "353130"),
+        ];
+        for (time, code) in vectors {
+            assert_eq!(verify(RFC_SECRET, code, time, None), Some(time / STEP_SECONDS), "at {time}");
+        }
+    }
+
+    #[test]
+    fn accepts_one_step_of_clock_skew() {
+        assert_eq!(verify(RFC_SECRET, "287082", 59 + 30, None), Some(1));
+        assert_eq!(verify(RFC_SECRET, "287082", 59 - 30, None), Some(1));
+        assert_eq!(verify(RFC_SECRET, "287082", 59 + 60, None), None);
+    }
+
+    #[test]
+    fn refuses_steps_already_used() {
+        assert_eq!(verify(RFC_SECRET, "287082", 59, Some(1)), None);
+        assert_eq!(verify(RFC_SECRET, "287082", 59, Some(2)), None);
+        assert_eq!(verify(RFC_SECRET, "287082", 59, Some(0)), Some(1));
+    }
+
+    #[test]
+    fn refuses_malformed_codes() {
+        assert_eq!(verify(RFC_SECRET, " 287082 ", 59, None), Some(1));
+        assert_eq!(verify(RFC_SECRET, "28708", 59, None), None);
+        assert_eq!(verify(RFC_SECRET, "28708a", 59, None), None);
+        assert_eq!(verify(RFC_SECRET, "94287082", 59, None), None);
+        assert_eq!(verify("not base32!", "287082", 59, None), None);
+    }
+
+    #[test]
+    fn base32_round_trips() {
+        assert_eq!(base32_encode(b"12345678901234567890"), RFC_SECRET);
+        assert_eq!(base32_decode(RFC_SECRET).as_deref(), Some(&b"12345678901234567890"[..]));
+
+        for len in 0..=20 {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 37 + 11) as u8).collect();
+            assert_eq!(base32_decode(&base32_encode(&bytes)), Some(bytes));
+        }
+    }
+
+    #[test]
+    fn base32_decode_ignores_case_and_padding() {
+        assert_eq!(base32_decode("mzxw6==="), Some(b"foo".to_vec()));
+        assert_eq!(base32_decode("MZXW6YQ="), Some(b"foob".to_vec()));
+    }
+
+    #[test]
+    fn generated_secrets_decode_to_160_bits() {
+        assert_eq!(base32_decode(&generate_secret()).map(|key| key.len()), Some(20));
+    }
+}
@@ -1,2 +1,22 @@
 pub mod auth;
+pub mod booking;
+pub mod bus;
+pub mod journey;
 pub mod jwt;
+pub mod location;
+pub mod login_throttle;
+pub mod mailer;
+pub mod mfa;
+pub mod opaque_token;
+pub mod password;
+pub mod password_reset;
+pub mod payment;
+pub mod pricing;
+pub mod refresh_token;
+pub mod registration;
+pub mod revocation;
+pub mod role;
+pub mod route;
+pub mod schedule;
+pub mod ticket;
+pub mod trip;
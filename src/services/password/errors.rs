@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PasswordServiceError {
+    #[error("invalid argon2 parameters: {0}")]
+    InvalidParams(argon2::Error),
+    #[error("password hash error: {0}")]
+    Hash(argon2::password_hash::Error),
+    #[error("password hashing task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
@@ -0,0 +1,31 @@
+mod errors;
+mod password;
+
+pub use errors::PasswordServiceError;
+pub use password::PasswordServiceImpl;
+
+use async_trait::async_trait;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordVerification {
+    /// Password matches and the stored hash already uses the configured parameters.
+    Valid,
+    /// Password matches but the stored value is legacy plaintext or a weaker hash.
+    NeedsRehash,
+    Invalid,
+}
+
+#[async_trait]
+pub trait PasswordService: Send + Sync {
+    async fn hash_password(&self, password: &str) -> Result<String, PasswordServiceError>;
+
+    async fn verify_password(
+        &self,
+        password: &str,
+        stored: &str,
+    ) -> Result<PasswordVerification, PasswordServiceError>;
+
+    /// Runs a verification against a throwaway hash so unknown accounts cost
+    /// the same as known ones.
+    async fn verify_dummy(&self, password: &str);
+}
@@ -0,0 +1,179 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+use super::{PasswordServiceError, PasswordVerification};
+
+const PHC_PREFIX: &str = "$argon2";
+
+pub struct PasswordServiceImpl {
+    argon2: Argon2<'static>,
+    dummy_hash: String,
+}
+
+impl PasswordServiceImpl {
+    pub fn new(memory_kib: u32, iterations: u32, parallelism: u32) -> Result<Self, PasswordServiceError> {
+        let params = Params::new(memory_kib, iterations, parallelism, None)
+            .map_err(PasswordServiceError::InvalidParams)?;
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+        let dummy_hash = hash_with(&argon2, "dummy-password")?;
+
+        Ok(Self { argon2, dummy_hash })
+    }
+
+    fn is_weaker(&self, hash: &PasswordHash<'_>) -> bool {
+        if hash.algorithm != Algorithm::Argon2id.ident() || hash.version != Some(Version::V0x13.into()) {
+            return true;
+        }
+
+        let Ok(stored) = Params::try_from(hash) else {
+            return true;
+        };
+        let current = self.argon2.params();
+
+        stored.m_cost() < current.m_cost()
+            || stored.t_cost() < current.t_cost()
+            || stored.p_cost() < current.p_cost()
+    }
+}
+
+fn hash_with(argon2: &Argon2<'_>, password: &str) -> Result<String, PasswordServiceError> {
+    let salt = SaltString::encode_b64(&rand::random::<[u8; 16]>()).map_err(PasswordServiceError::Hash)?;
+
+    argon2
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(PasswordServiceError::Hash)
+}
+
+fn verify_with(argon2: &Argon2<'_>, password: &str, stored: &str) -> Result<bool, PasswordServiceError> {
+    let hash = PasswordHash::new(stored).map_err(PasswordServiceError::Hash)?;
+
+    match argon2.verify_password(password.as_bytes(), &hash) {
+        Ok(()) => Ok(true),
+        Err(argon2::password_hash::Error::Password) => Ok(false),
+        Err(err) => Err(PasswordServiceError::Hash(err)),
+    }
+}
+
+// Digests both sides first so the comparison does not leak the stored length.
+fn legacy_matches(password: &str, stored: &str) -> bool {
+    let password = Sha256::digest(password.as_bytes());
+    let stored = Sha256::digest(stored.as_bytes());
+
+    password.ct_eq(&stored).into()
+}
+
+#[async_trait]
+impl super::PasswordService for PasswordServiceImpl {
+    async fn hash_password(&self, password: &str) -> Result<String, PasswordServiceError> {
+        let argon2 = self.argon2.clone();
+        let password = password.to_string();
+
+        tokio::task::spawn_blocking(move || hash_with(&argon2, &password)).await?
+    }
+
+    async fn verify_password(
+        &self,
+        password: &str,
+        stored: &str,
+    ) -> Result<PasswordVerification, PasswordServiceError> {
+        if !stored.starts_with(PHC_PREFIX) {
+            return Ok(if legacy_matches(password, stored) {
+                PasswordVerification::NeedsRehash
+            } else {
+                PasswordVerification::Invalid
+            });
+        }
+
+        let needs_rehash = self.is_weaker(&PasswordHash::new(stored).map_err(PasswordServiceError::Hash)?);
+
+        let argon2 = self.argon2.clone();
+        let password = password.to_string();
+        let stored = stored.to_string();
+        let matches = tokio::task::spawn_blocking(move || verify_with(&argon2, &password, &stored)).await??;
+
+        Ok(match (matches, needs_rehash) {
+            (false, _) => PasswordVerification::Invalid,
+            (true, true) => PasswordVerification::NeedsRehash,
+            (true, false) => PasswordVerification::Valid,
+        })
+    }
+
+    async fn verify_dummy(&self, password: &str) {
+        let argon2 = self.argon2.clone();
+        let password = password.to_string();
+        let stored = self.dummy_hash.clone();
+
+        let _ = tokio::task::spawn_blocking(move || verify_with(&argon2, &password, &stored)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::password::PasswordService;
+
+    // Small parameters keep the tests fast; only their ordering matters here.
+    fn service() -> PasswordServiceImpl {
+        PasswordServiceImpl::new(64, 2, 1).unwrap()
+    }
+
+    fn hash_using(algorithm: Algorithm, memory_kib: u32, iterations: u32, password: &str) -> String {
+        let params = Params::new(memory_kib, iterations, 1, None).unwrap();
+        hash_with(&Argon2::new(algorithm, Version::V0x13, params), password).unwrap()
+    }
+
+    #[tokio::test]
+    async fn fresh_hashes_verify_as_valid() {
+        let service = service();
+        let stored = service.hash_password("correct horse").await.unwrap();
+
+        assert!(stored.starts_with(PHC_PREFIX));
+        assert_eq!(service.verify_password("correct horse", &stored).await.unwrap(), PasswordVerification::Valid);
+        assert_eq!(service.verify_password("wrong horse", &stored).await.unwrap(), PasswordVerification::Invalid);
+    }
+
+    #[tokio::test]
+    async fn legacy_plaintext_needs_rehash_only_when_it_matches() {
+        let service = service();
+
+        assert_eq!(service.verify_password("123456", "123456").await.unwrap(), PasswordVerification::NeedsRehash);
+        assert_eq!(service.verify_password("1234567", "123456").await.unwrap(), PasswordVerification::Invalid);
+        assert_eq!(service.verify_password("", "123456").await.unwrap(), PasswordVerification::Invalid);
+    }
+
+    #[tokio::test]
+    async fn weaker_parameters_need_rehash() {
+        let service = service();
+
+        for stored in [
+            hash_using(Algorithm::Argon2id, 32, 2, "secret"),
+            hash_using(Algorithm::Argon2id, 64, 1, "secret"),
+            hash_using(Algorithm::Argon2i, 64, 2, "secret"),
+        ] {
+            assert_eq!(service.verify_password("secret", &stored).await.unwrap(), PasswordVerification::NeedsRehash);
+            assert_eq!(service.verify_password("other", &stored).await.unwrap(), PasswordVerification::Invalid);
+        }
+    }
+
+    #[tokio::test]
+    async fn stronger_parameters_stay_valid() {
+        let service = service();
+        let stored = hash_using(Algorithm::Argon2id, 128, 3, "secret");
+
+        assert_eq!(service.verify_password("secret", &stored).await.unwrap(), PasswordVerification::Valid);
+    }
+
+    #[tokio::test]
+    async fn malformed_phc_strings_never_match() {
+        let service = service();
+
+        for stored in ["$argon2id$not-a-hash", "$argon2id$", "$argon2id$v=19$m=64,t=2,p=1$$"] {
+            let result = service.verify_password("secret", stored).await;
+            assert!(matches!(result, Ok(PasswordVerification::Invalid) | Err(_)), "{stored}: {result:?}");
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+use crate::services::mailer::MailerError;
+use crate::services::password::PasswordServiceError;
+
+#[derive(Debug, Error)]
+pub enum PasswordResetError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("password error: {0}")]
+    Password(#[from] PasswordServiceError),
+    #[error("mailer error: {0}")]
+    Mailer(#[from] MailerError),
+    #[error("invalid reset token")]
+    InvalidToken,
+    #[error("reset token expired")]
+    Expired,
+}
@@ -0,0 +1,26 @@
+mod errors;
+mod password_reset;
+
+pub use errors::PasswordResetError;
+pub use password_reset::PasswordResetServiceImpl;
+
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+
+use crate::entities::user::Model as UserModel;
+
+#[async_trait]
+pub trait PasswordResetService: Send + Sync {
+    /// Mails a reset link if `email` has an account. Unknown addresses are
+    /// ignored so the caller cannot tell them apart.
+    async fn request_reset(&self, db: &DatabaseConnection, email: &str) -> Result<(), PasswordResetError>;
+
+    /// Consumes a reset token and stores `new_password`. Returns the updated
+    /// user so the caller can revoke its sessions.
+    async fn reset_password(
+        &self,
+        db: &DatabaseConnection,
+        token: &str,
+        new_password: &str,
+    ) -> Result<UserModel, PasswordResetError>;
+}
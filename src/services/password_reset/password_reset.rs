@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect,
+    TransactionTrait,
+};
+
+use crate::entities::password_reset::{
+    ActiveModel as PasswordResetActiveModel, Column as PasswordResetColumn, Entity as PasswordReset,
+};
+use crate::entities::user::{ActiveModel as UserActiveModel, Column as UserColumn, Entity as User, Model as UserModel};
+use crate::services::auth::normalize_email;
+use crate::services::mailer::{Mail, Mailer};
+use crate::services::opaque_token;
+use crate::services::password::PasswordService;
+
+use super::PasswordResetError;
+
+pub struct PasswordResetServiceImpl {
+    password_service: Arc<dyn PasswordService>,
+    mailer: Arc<dyn Mailer>,
+    reset_url: String,
+    ttl_seconds: u64,
+}
+
+impl PasswordResetServiceImpl {
+    pub fn new(
+        password_service: Arc<dyn PasswordService>,
+        mailer: Arc<dyn Mailer>,
+        reset_url: String,
+        ttl_seconds: u64,
+    ) -> Self {
+        Self {
+            password_service,
+            mailer,
+            reset_url,
+            ttl_seconds,
+        }
+    }
+}
+
+#[async_trait]
+impl super::PasswordResetService for PasswordResetServiceImpl {
+    async fn request_reset(&self, db: &DatabaseConnection, email: &str) -> Result<(), PasswordResetError> {
+        let Some(user) = User::find()
+            .filter(UserColumn::Email.eq(normalize_email(email)))
+            .one(db)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let token = opaque_token::generate(32);
+        let now = Utc::now().naive_utc();
+        let txn = db.begin().await?;
+
+        // Only the most recently mailed link stays usable.
+        PasswordReset::delete_many()
+            .filter(PasswordResetColumn::UserId.eq(user.user_id))
+            .exec(&txn)
+            .await?;
+
+        PasswordResetActiveModel {
+            token_hash: Set(opaque_token::hash(&token)),
+            user_id: Set(user.user_id),
+            expires_at: Set(now + Duration::seconds(self.ttl_seconds as i64)),
+            created_at: Set(now),
+        }
+        .insert(&txn)
+        .await?;
+
+        self.mailer
+            .send(Mail {
+                to: user.email,
+                subject: "Reset your password".to_string(),
+                body: format!(
+                    "A password reset was requested for your account.\n\n\
+                     Choose a new password here:\n\n{}?token={}\n\n\
+                     The link expires in {} minutes. If you did not ask for this, you can ignore this message.",
+                    self.reset_url,
+                    token,
+                    self.ttl_seconds / 60
+                ),
+            })
+            .await?;
+
+        txn.commit().await?;
+        Ok(())
+    }
+
+    async fn reset_password(
+        &self,
+        db: &DatabaseConnection,
+        token: &str,
+        new_password: &str,
+    ) -> Result<UserModel, PasswordResetError> {
+        let txn = db.begin().await?;
+
+        let reset = PasswordReset::find_by_id(opaque_token::hash(token))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(PasswordResetError::InvalidToken)?;
+
+        if reset.expires_at <= Utc::now().naive_utc() {
+            PasswordReset::delete_by_id(reset.token_hash).exec(&txn).await?;
+            txn.commit().await?;
+            return Err(PasswordResetError::Expired);
+        }
+
+        let user = User::find_by_id(reset.user_id)
+            .one(&txn)
+            .await?
+            .ok_or(PasswordResetError::InvalidToken)?;
+
+        // Following the mailed link proves the address, so a pending account
+        // is verified along the way.
+        let mut active: UserActiveModel = user.into();
+        active.password = Set(self.password_service.hash_password(new_password).await?);
+        active.email_verified = Set(true);
+        let user = active.update(&txn).await?;
+
+        PasswordReset::delete_many()
+            .filter(PasswordResetColumn::UserId.eq(user.user_id))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(user)
+    }
+}
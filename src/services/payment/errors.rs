@@ -0,0 +1,32 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PaymentError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("payment provider error: {0}")]
+    Provider(String),
+    #[error("payment not found")]
+    PaymentNotFound,
+    #[error("payment belongs to another user")]
+    NotPaymentOwner,
+    #[error("ticket listed more than once")]
+    DuplicateTickets,
+    #[error("tickets not found: {0:?}")]
+    TicketsNotFound(Vec<i32>),
+    #[error("tickets are not awaiting payment: {0:?}")]
+    TicketsNotPayable(Vec<i32>),
+    #[error("tickets already have a pending payment: {0:?}")]
+    PaymentPending(Vec<i32>),
+    #[error("invalid webhook signature")]
+    InvalidSignature,
+    #[error("invalid webhook payload: {0}")]
+    InvalidPayload(String),
+    #[error("payment amount is too large")]
+    AmountTooLarge,
+    #[error("webhook amount does not match the payment")]
+    AmountMismatch,
+    #[error("the payment provider cannot complete checkout locally")]
+    CheckoutUnavailable,
+}
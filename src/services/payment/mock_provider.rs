@@ -0,0 +1,250 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+use tracing::info;
+
+use crate::services::opaque_token;
+
+use super::{PaymentError, PaymentOutcome, ProviderEvent, ProviderIntent, SignedWebhook};
+
+/// Webhooks signed further than this many seconds from now are refused, so
+/// a captured request cannot be replayed later.
+const SIGNATURE_TOLERANCE: i64 = 300;
+
+const SUCCEEDED_EVENT: &str = "payment.succeeded";
+const FAILED_EVENT: &str = "payment.failed";
+
+#[derive(Serialize, Deserialize)]
+struct EventBody {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    data: EventData,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EventData {
+    provider_ref: String,
+    amount: i32,
+}
+
+/// Local gateway that takes no money; refunds are only logged. With
+/// `checkout` on, payments are completed by calling their checkout URL, which
+/// signs a webhook the way a real gateway would. Without it only webhooks
+/// signed with the secret complete them.
+pub struct MockProvider {
+    secret: String,
+    public_url: String,
+    checkout: bool,
+}
+
+impl MockProvider {
+    pub fn new(secret: String, public_url: String, checkout: bool) -> Self {
+        Self {
+            secret,
+            public_url,
+            checkout,
+        }
+    }
+
+    /// Hex HMAC-SHA256 of `{timestamp}.{payload}`.
+    fn signature(&self, timestamp: i64, payload: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes()).expect("HMAC accepts keys of any length");
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(payload);
+        format!("{:x}", mac.finalize().into_bytes())
+    }
+}
+
+/// Splits a `t=<unix seconds>,v1=<hex>` header.
+fn parse_signature(header: &str) -> Option<(i64, &str)> {
+    let mut timestamp = None;
+    let mut signature = None;
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signature = Some(value),
+            _ => {}
+        }
+    }
+
+    Some((timestamp?, signature?))
+}
+
+#[async_trait]
+impl super::PaymentProvider for MockProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn create_intent(&self, _amount: i32, _currency: &str) -> Result<ProviderIntent, PaymentError> {
+        let provider_ref = format!("mock_{}", opaque_token::generate(16));
+        let checkout_url = self
+            .checkout
+            .then(|| format!("{}/api/v1/payments/mock/{provider_ref}/complete", self.public_url));
+
+        Ok(ProviderIntent { provider_ref, checkout_url })
+    }
+
+    async fn refund(&self, provider_ref: &str, amount: i32, idempotency_key: &str) -> Result<(), PaymentError> {
+        info!(provider_ref, amount, idempotency_key, "mock refund");
+        Ok(())
+    }
+
+    fn verify_webhook(&self, signature: &str, payload: &[u8]) -> Result<ProviderEvent, PaymentError> {
+        let (timestamp, given) = parse_signature(signature).ok_or(PaymentError::InvalidSignature)?;
+        if (Utc::now().timestamp() - timestamp).abs() > SIGNATURE_TOLERANCE {
+            return Err(PaymentError::InvalidSignature);
+        }
+        let expected = self.signature(timestamp, payload);
+        if !bool::from(expected.as_bytes().ct_eq(given.as_bytes())) {
+            return Err(PaymentError::InvalidSignature);
+        }
+
+        let body: EventBody =
+            serde_json::from_slice(payload).map_err(|err| PaymentError::InvalidPayload(err.to_string()))?;
+        let outcome = match body.kind.as_str() {
+            SUCCEEDED_EVENT => PaymentOutcome::Succeeded,
+            FAILED_EVENT => PaymentOutcome::Failed,
+            other => return Err(PaymentError::InvalidPayload(format!("unknown event type {other}"))),
+        };
+
+        Ok(ProviderEvent {
+            event_id: body.id,
+            provider_ref: body.data.provider_ref,
+            outcome,
+            amount: body.data.amount,
+        })
+    }
+
+    fn simulate_checkout(&self, provider_ref: &str, amount: i32, outcome: PaymentOutcome) -> Option<SignedWebhook> {
+        if !self.checkout {
+            return None;
+        }
+        let kind = match outcome {
+            PaymentOutcome::Succeeded => SUCCEEDED_EVENT,
+            PaymentOutcome::Failed => FAILED_EVENT,
+        };
+        let body = EventBody {
+            id: format!("evt_{}", opaque_token::generate(16)),
+            kind: kind.to_string(),
+            data: EventData {
+                provider_ref: provider_ref.to_string(),
+                amount,
+            },
+        };
+        let payload = serde_json::to_vec(&body).ok()?;
+        let timestamp = Utc::now().timestamp();
+
+        Some(SignedWebhook {
+            signature: format!("t={timestamp},v1={}", self.signature(timestamp, &payload)),
+            payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::payment::PaymentProvider;
+
+    fn provider() -> MockProvider {
+        MockProvider::new("whsec_test".to_string(), "http://localhost".to_string(), true)
+    }
+
+    fn payload() -> Vec<u8> {
+        br#"{"id":"evt_1","type":"payment.succeeded","data":{"provider_ref":"mock_1","amount":1500}}"#.to_vec()
+    }
+
+    fn header(provider: &MockProvider, timestamp: i64, payload: &[u8]) -> String {
+        format!("t={timestamp},v1={}", provider.signature(timestamp, payload))
+    }
+
+    #[test]
+    fn accepts_a_freshly_signed_webhook() {
+        let provider = provider();
+        let payload = payload();
+
+        let event = provider
+            .verify_webhook(&header(&provider, Utc::now().timestamp(), &payload), &payload)
+            .unwrap();
+        assert_eq!(event.event_id, "evt_1");
+        assert_eq!(event.provider_ref, "mock_1");
+        assert_eq!(event.outcome, PaymentOutcome::Succeeded);
+        assert_eq!(event.amount, 1500);
+    }
+
+    #[test]
+    fn accepts_timestamps_inside_the_tolerance_window() {
+        let provider = provider();
+        let payload = payload();
+        let now = Utc::now().timestamp();
+
+        for timestamp in [now - SIGNATURE_TOLERANCE + 5, now + SIGNATURE_TOLERANCE - 5] {
+            assert!(provider.verify_webhook(&header(&provider, timestamp, &payload), &payload).is_ok());
+        }
+    }
+
+    #[test]
+    fn refuses_timestamps_outside_the_tolerance_window() {
+        let provider = provider();
+        let payload = payload();
+        let now = Utc::now().timestamp();
+
+        for timestamp in [now - SIGNATURE_TOLERANCE - 5, now + SIGNATURE_TOLERANCE + 5] {
+            let result = provider.verify_webhook(&header(&provider, timestamp, &payload), &payload);
+            assert!(matches!(result, Err(PaymentError::InvalidSignature)));
+        }
+    }
+
+    #[test]
+    fn refuses_a_bad_signature() {
+        let provider = provider();
+        let payload = payload();
+        let now = Utc::now().timestamp();
+        let other = MockProvider::new("another_secret".to_string(), "http://localhost".to_string(), true);
+
+        let forged = provider.verify_webhook(&header(&other, now, &payload), &payload);
+        assert!(matches!(forged, Err(PaymentError::InvalidSignature)));
+
+        let mut tampered = payload.clone();
+        tampered.extend_from_slice(b" ");
+        let tampered = provider.verify_webhook(&header(&provider, now, &payload), &tampered);
+        assert!(matches!(tampered, Err(PaymentError::InvalidSignature)));
+    }
+
+    #[test]
+    fn refuses_malformed_signature_headers() {
+        let provider = provider();
+        let payload = payload();
+        let now = Utc::now().timestamp();
+        let signature = provider.signature(now, &payload);
+
+        for header in ["", &format!("v1={signature}"), &format!("t={now}"), &format!("t=soon,v1={signature}")] {
+            let result = provider.verify_webhook(header, &payload);
+            assert!(matches!(result, Err(PaymentError::InvalidSignature)));
+        }
+    }
+
+    #[test]
+    fn simulated_checkout_round_trips_through_verification() {
+        let provider = provider();
+
+        let webhook = provider.simulate_checkout("mock_2", 700, PaymentOutcome::Failed).unwrap();
+        let event = provider.verify_webhook(&webhook.signature, &webhook.payload).unwrap();
+        assert_eq!(event.provider_ref, "mock_2");
+        assert_eq!(event.outcome, PaymentOutcome::Failed);
+        assert_eq!(event.amount, 700);
+    }
+
+    #[test]
+    fn checkout_is_not_simulated_when_disabled() {
+        let provider = MockProvider::new("whsec_test".to_string(), "http://localhost".to_string(), false);
+
+        assert!(provider.simulate_checkout("mock_3", 700, PaymentOutcome::Succeeded).is_none());
+    }
+}
@@ -0,0 +1,144 @@
+mod errors;
+mod mock_provider;
+mod payment;
+
+pub use errors::PaymentError;
+pub use mock_provider::MockProvider;
+pub use payment::PaymentServiceImpl;
+
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, DatabaseTransaction};
+
+use crate::entities::payment_intent::Model as PaymentIntentModel;
+use crate::entities::sea_orm_active_enums::PaymentStatus;
+
+/// What the provider returns for a new payment.
+#[derive(Debug, Clone)]
+pub struct ProviderIntent {
+    pub provider_ref: String,
+    /// Page where the customer completes the payment, if the provider has one.
+    pub checkout_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentOutcome {
+    Succeeded,
+    Failed,
+}
+
+/// A webhook event whose signature has been checked.
+#[derive(Debug, Clone)]
+pub struct ProviderEvent {
+    pub event_id: String,
+    pub provider_ref: String,
+    pub outcome: PaymentOutcome,
+    pub amount: i32,
+}
+
+/// A webhook request as the provider sends it: the signature header and the
+/// raw body it covers.
+#[derive(Debug, Clone)]
+pub struct SignedWebhook {
+    pub signature: String,
+    pub payload: Vec<u8>,
+}
+
+/// Payment gateway. Only a local mock exists so far; a real gateway plugs in
+/// here.
+#[async_trait]
+pub trait PaymentProvider: Send + Sync {
+    /// Stored with every intent and event so references from different
+    /// providers never collide.
+    fn name(&self) -> &'static str;
+
+    async fn create_intent(&self, amount: i32, currency: &str) -> Result<ProviderIntent, PaymentError>;
+
+    /// Gives `amount` of a completed payment back. A retry with the same
+    /// `idempotency_key` must not refund twice.
+    async fn refund(&self, provider_ref: &str, amount: i32, idempotency_key: &str) -> Result<(), PaymentError>;
+
+    /// Checks `signature` against the raw `payload` and parses the event,
+    /// failing with `InvalidSignature` or `InvalidPayload`.
+    fn verify_webhook(&self, signature: &str, payload: &[u8]) -> Result<ProviderEvent, PaymentError>;
+
+    /// The webhook the provider would send once the customer finishes
+    /// checkout, for providers that can complete payments locally.
+    fn simulate_checkout(&self, _provider_ref: &str, _amount: i32, _outcome: PaymentOutcome) -> Option<SignedWebhook> {
+        None
+    }
+}
+
+/// A payment intent with the tickets it pays for.
+#[derive(Debug, Clone)]
+pub struct PaymentDetails {
+    pub intent: PaymentIntentModel,
+    pub ticket_ids: Vec<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookReceipt {
+    pub payment: PaymentDetails,
+    /// The event had been applied before and changed nothing this time.
+    pub duplicate: bool,
+}
+
+/// The name of a payment status in the API.
+pub fn status_name(status: PaymentStatus) -> &'static str {
+    match status {
+        PaymentStatus::Pending => "pending",
+        PaymentStatus::Succeeded => "succeeded",
+        PaymentStatus::Failed => "failed",
+        PaymentStatus::Expired => "expired",
+    }
+}
+
+#[async_trait]
+pub trait PaymentService: Send + Sync {
+    /// Starts a payment for the user's booked tickets, all at once. Asking
+    /// again for exactly the tickets of a pending payment returns it; any
+    /// other overlap with one fails with `PaymentPending`.
+    async fn create_payment(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        ticket_ids: &[i32],
+    ) -> Result<PaymentDetails, PaymentError>;
+
+    /// One of the user's payments.
+    async fn payment(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        payment_intent_id: i32,
+    ) -> Result<PaymentDetails, PaymentError>;
+
+    /// Applies a provider webhook once per event. A successful payment marks
+    /// its booked tickets paid and refunds the share of tickets released in
+    /// the meantime; a failed one only ends a pending payment.
+    async fn handle_webhook(
+        &self,
+        db: &DatabaseConnection,
+        signature: &str,
+        payload: &[u8],
+    ) -> Result<WebhookReceipt, PaymentError>;
+
+    /// Completes checkout through the provider's local simulation and feeds
+    /// the resulting webhook to `handle_webhook`.
+    async fn complete_checkout(
+        &self,
+        db: &DatabaseConnection,
+        provider_ref: &str,
+        outcome: PaymentOutcome,
+    ) -> Result<WebhookReceipt, PaymentError>;
+
+    /// Cancels booked tickets left unpaid past the timeout on trips that
+    /// have not departed, and expires the payments waiting on them. Returns
+    /// how many tickets were released.
+    async fn release_unpaid(&self, db: &DatabaseConnection) -> Result<u64, PaymentError>;
+
+    /// Gives `amount` of a cancelled ticket's fare back through the provider
+    /// and adds it to the `refunded_amount` of the payment that paid for it,
+    /// inside the caller's transaction. Tickets no payment went through for,
+    /// such as those paid at the counter, are left to be refunded by hand.
+    async fn refund_ticket(&self, txn: &DatabaseTransaction, ticket_id: i32, amount: i32) -> Result<(), PaymentError>;
+}
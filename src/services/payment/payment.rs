@@ -0,0 +1,475 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    DatabaseTransaction, DbBackend, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Statement, TransactionTrait, sea_query::OnConflict,
+    sea_query::Expr,
+};
+
+use crate::entities::payment_event::{
+    ActiveModel as PaymentEventActiveModel, Column as PaymentEventColumn, Entity as PaymentEvent,
+};
+use crate::entities::payment_intent::{
+    ActiveModel as PaymentIntentActiveModel, Column as PaymentIntentColumn, Entity as PaymentIntent,
+    Model as PaymentIntentModel,
+};
+use crate::entities::payment_intent_ticket::{
+    ActiveModel as PaymentIntentTicketActiveModel, Column as PaymentIntentTicketColumn, Entity as PaymentIntentTicket,
+};
+use crate::entities::sea_orm_active_enums::{PaymentStatus, TicketStatus};
+use crate::entities::ticket::{Column as TicketColumn, Entity as Ticket, Model as TicketModel};
+use crate::services::ticket::record_transition;
+
+use super::{PaymentDetails, PaymentError, PaymentOutcome, PaymentProvider, WebhookReceipt};
+
+/// Booked tickets past their payment deadline on trips still to depart,
+/// skipping any a payment is settling right now. $1 booked status,
+/// $2 booked before, $3 now.
+const UNPAID_TICKETS_QUERY: &str = r#"
+    SELECT k.* FROM ticket k
+    JOIN trip t ON t.trip_id = k.trip_id
+    WHERE k.status = $1 AND k.booked_date <= $2 AND t.start_date > $3
+    ORDER BY k.ticket_id
+    FOR UPDATE OF k SKIP LOCKED
+"#;
+
+pub struct PaymentServiceImpl {
+    provider: Arc<dyn PaymentProvider>,
+    currency: String,
+    timeout: Option<Duration>,
+}
+
+impl PaymentServiceImpl {
+    /// `timeout` is in seconds; 0 never releases unpaid tickets.
+    pub fn new(provider: Arc<dyn PaymentProvider>, currency: String, timeout: u64) -> Self {
+        Self {
+            provider,
+            currency,
+            timeout: (timeout > 0).then(|| Duration::seconds(timeout as i64)),
+        }
+    }
+
+    /// When a ticket booked at `booked_date` is released unpaid.
+    fn deadline(&self, booked_date: NaiveDateTime) -> Option<NaiveDateTime> {
+        self.timeout.map(|timeout| booked_date + timeout)
+    }
+}
+
+async fn ticket_ids_of<C: ConnectionTrait>(conn: &C, payment_intent_id: i32) -> Result<Vec<i32>, PaymentError> {
+    Ok(PaymentIntentTicket::find()
+        .select_only()
+        .column(PaymentIntentTicketColumn::TicketId)
+        .filter(PaymentIntentTicketColumn::PaymentIntentId.eq(payment_intent_id))
+        .order_by_asc(PaymentIntentTicketColumn::TicketId)
+        .into_tuple()
+        .all(conn)
+        .await?)
+}
+
+async fn details<C: ConnectionTrait>(conn: &C, intent: PaymentIntentModel) -> Result<PaymentDetails, PaymentError> {
+    let ticket_ids = ticket_ids_of(conn, intent.payment_intent_id).await?;
+
+    Ok(PaymentDetails { intent, ticket_ids })
+}
+
+/// Pending payments that cover any of the tickets, with the tickets each
+/// covers.
+async fn pending_payments<C: ConnectionTrait>(
+    conn: &C,
+    ticket_ids: &[i32],
+) -> Result<Vec<PaymentDetails>, PaymentError> {
+    let intents = PaymentIntent::find()
+        .filter(PaymentIntentColumn::Status.eq(PaymentStatus::Pending))
+        .filter(
+            PaymentIntentColumn::PaymentIntentId.in_subquery(
+                sea_orm::sea_query::Query::select()
+                    .column(PaymentIntentTicketColumn::PaymentIntentId)
+                    .from(PaymentIntentTicket)
+                    .and_where(PaymentIntentTicketColumn::TicketId.is_in(ticket_ids.iter().copied()))
+                    .to_owned(),
+            ),
+        )
+        .order_by_asc(PaymentIntentColumn::PaymentIntentId)
+        .all(conn)
+        .await?;
+
+    let mut pending = Vec::with_capacity(intents.len());
+    for intent in intents {
+        pending.push(details(conn, intent).await?);
+    }
+
+    Ok(pending)
+}
+
+/// The combined price of `tickets`, summed as `i64` so a large order cannot
+/// overflow; a total the amount column cannot hold fails with `AmountTooLarge`.
+fn total_price(tickets: &[TicketModel]) -> Result<i32, PaymentError> {
+    let total: i64 = tickets.iter().map(|ticket| i64::from(ticket.price)).sum();
+
+    i32::try_from(total).map_err(|_| PaymentError::AmountTooLarge)
+}
+
+/// Moves booked tickets to `to`, recording a system change for each.
+async fn settle_tickets<C: ConnectionTrait>(
+    conn: &C,
+    tickets: &[TicketModel],
+    to: TicketStatus,
+    now: NaiveDateTime,
+) -> Result<(), PaymentError> {
+    if tickets.is_empty() {
+        return Ok(());
+    }
+
+    let mut update = Ticket::update_many()
+        .col_expr(TicketColumn::Status, Expr::value(to.into_value()))
+        .filter(TicketColumn::TicketId.is_in(tickets.iter().map(|ticket| ticket.ticket_id)));
+    // Like a cancellation, releasing an unpaid ticket refunds nothing.
+    if to == TicketStatus::Cancelled {
+        update = update.col_expr(TicketColumn::RefundAmount, Expr::value(0));
+    }
+    update.exec(conn).await?;
+
+    for ticket in tickets {
+        record_transition(conn, ticket.ticket_id, Some(ticket.status), to, None, now).await?;
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl super::PaymentService for PaymentServiceImpl {
+    async fn create_payment(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        ticket_ids: &[i32],
+    ) -> Result<PaymentDetails, PaymentError> {
+        let mut ticket_ids = ticket_ids.to_vec();
+        ticket_ids.sort_unstable();
+        if ticket_ids.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(PaymentError::DuplicateTickets);
+        }
+
+        let txn = db.begin().await?;
+        let now = Utc::now().naive_utc();
+
+        let tickets = Ticket::find()
+            .filter(TicketColumn::TicketId.is_in(ticket_ids.iter().copied()))
+            .filter(TicketColumn::UserId.eq(user_id))
+            .order_by_asc(TicketColumn::TicketId)
+            .lock_exclusive()
+            .all(&txn)
+            .await?;
+
+        let missing: Vec<i32> = ticket_ids
+            .iter()
+            .copied()
+            .filter(|ticket_id| !tickets.iter().any(|ticket| ticket.ticket_id == *ticket_id))
+            .collect();
+        if !missing.is_empty() {
+            return Err(PaymentError::TicketsNotFound(missing));
+        }
+
+        let unpayable: Vec<i32> = tickets
+            .iter()
+            .filter(|ticket| {
+                ticket.status != TicketStatus::Booked
+                    || self.deadline(ticket.booked_date).is_some_and(|deadline| deadline <= now)
+            })
+            .map(|ticket| ticket.ticket_id)
+            .collect();
+        if !unpayable.is_empty() {
+            return Err(PaymentError::TicketsNotPayable(unpayable));
+        }
+
+        let pending = pending_payments(&txn, &ticket_ids).await?;
+        if let Some(payment) = pending.iter().find(|payment| payment.ticket_ids == ticket_ids) {
+            return Ok(payment.clone());
+        }
+        if !pending.is_empty() {
+            let mut taken: Vec<i32> = pending
+                .iter()
+                .flat_map(|payment| payment.ticket_ids.iter().copied())
+                .filter(|ticket_id| ticket_ids.contains(ticket_id))
+                .collect();
+            taken.sort_unstable();
+            taken.dedup();
+            return Err(PaymentError::PaymentPending(taken));
+        }
+
+        let amount = total_price(&tickets)?;
+        let expires_at = tickets.iter().filter_map(|ticket| self.deadline(ticket.booked_date)).min();
+
+        // The tickets stay locked while the provider answers, so a concurrent
+        // request cannot start a second payment for them.
+        let created = self.provider.create_intent(amount, &self.currency).await?;
+
+        let intent = PaymentIntentActiveModel {
+            user_id: Set(user_id),
+            provider: Set(self.provider.name().to_string()),
+            provider_ref: Set(created.provider_ref),
+            amount: Set(amount),
+            currency: Set(self.currency.clone()),
+            status: Set(PaymentStatus::Pending),
+            checkout_url: Set(created.checkout_url),
+            refunded_amount: Set(0),
+            created_at: Set(now),
+            expires_at: Set(expires_at),
+            completed_at: Set(None),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        PaymentIntentTicket::insert_many(ticket_ids.iter().map(|ticket_id| PaymentIntentTicketActiveModel {
+            payment_intent_id: Set(intent.payment_intent_id),
+            ticket_id: Set(*ticket_id),
+        }))
+        .exec(&txn)
+        .await?;
+
+        txn.commit().await?;
+
+        Ok(PaymentDetails { intent, ticket_ids })
+    }
+
+    async fn payment(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        payment_intent_id: i32,
+    ) -> Result<PaymentDetails, PaymentError> {
+        let intent = PaymentIntent::find_by_id(payment_intent_id)
+            .one(db)
+            .await?
+            .ok_or(PaymentError::PaymentNotFound)?;
+        if intent.user_id != user_id {
+            return Err(PaymentError::NotPaymentOwner);
+        }
+
+        details(db, intent).await
+    }
+
+    async fn handle_webhook(
+        &self,
+        db: &DatabaseConnection,
+        signature: &str,
+        payload: &[u8],
+    ) -> Result<WebhookReceipt, PaymentError> {
+        let event = self.provider.verify_webhook(signature, payload)?;
+        let provider = self.provider.name();
+
+        let txn = db.begin().await?;
+        let now = Utc::now().naive_utc();
+
+        let intent = PaymentIntent::find()
+            .filter(PaymentIntentColumn::Provider.eq(provider))
+            .filter(PaymentIntentColumn::ProviderRef.eq(event.provider_ref.as_str()))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(PaymentError::PaymentNotFound)?;
+        if event.amount != intent.amount {
+            return Err(PaymentError::AmountMismatch);
+        }
+
+        // Providers deliver at least once; the event key turns redeliveries
+        // into no-ops.
+        let recorded = PaymentEvent::insert(PaymentEventActiveModel {
+            provider: Set(provider.to_string()),
+            event_id: Set(event.event_id.clone()),
+            payment_intent_id: Set(intent.payment_intent_id),
+            received_at: Set(now),
+        })
+        .on_conflict(
+            OnConflict::columns([PaymentEventColumn::Provider, PaymentEventColumn::EventId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(&txn)
+        .await?;
+        if recorded == 0 {
+            let payment = details(&txn, intent).await?;
+            txn.commit().await?;
+            return Ok(WebhookReceipt { payment, duplicate: true });
+        }
+
+        let intent = match (event.outcome, intent.status) {
+            (PaymentOutcome::Failed, PaymentStatus::Pending) => {
+                let mut active: PaymentIntentActiveModel = intent.into();
+                active.status = Set(PaymentStatus::Failed);
+                active.completed_at = Set(Some(now));
+                active.update(&txn).await?
+            }
+            // Money arrived, even if the payment had expired or failed before.
+            (PaymentOutcome::Succeeded, status) if status != PaymentStatus::Succeeded => {
+                let ticket_ids = ticket_ids_of(&txn, intent.payment_intent_id).await?;
+                let tickets = Ticket::find()
+                    .filter(TicketColumn::TicketId.is_in(ticket_ids))
+                    .order_by_asc(TicketColumn::TicketId)
+                    .lock_exclusive()
+                    .all(&txn)
+                    .await?;
+                let (payable, released): (Vec<TicketModel>, Vec<TicketModel>) =
+                    tickets.into_iter().partition(|ticket| ticket.status == TicketStatus::Booked);
+
+                settle_tickets(&txn, &payable, TicketStatus::Paid, now).await?;
+
+                // Tickets released or paid otherwise meanwhile are not charged.
+                let refunded_amount = total_price(&released)?;
+                if refunded_amount > 0 {
+                    self.provider
+                        .refund(&intent.provider_ref, refunded_amount, &event.event_id)
+                        .await?;
+                }
+
+                let mut active: PaymentIntentActiveModel = intent.into();
+                active.status = Set(PaymentStatus::Succeeded);
+                active.refunded_amount = Set(refunded_amount);
+                active.completed_at = Set(Some(now));
+                active.update(&txn).await?
+            }
+            _ => intent,
+        };
+
+        let payment = details(&txn, intent).await?;
+        txn.commit().await?;
+
+        Ok(WebhookReceipt { payment, duplicate: false })
+    }
+
+    async fn complete_checkout(
+        &self,
+        db: &DatabaseConnection,
+        provider_ref: &str,
+        outcome: PaymentOutcome,
+    ) -> Result<WebhookReceipt, PaymentError> {
+        let intent = PaymentIntent::find()
+            .filter(PaymentIntentColumn::Provider.eq(self.provider.name()))
+            .filter(PaymentIntentColumn::ProviderRef.eq(provider_ref))
+            .one(db)
+            .await?
+            .ok_or(PaymentError::PaymentNotFound)?;
+
+        let webhook = self.provider
+            .simulate_checkout(provider_ref, intent.amount, outcome)
+            .ok_or(PaymentError::CheckoutUnavailable)?;
+
+        self.handle_webhook(db, &webhook.signature, &webhook.payload).await
+    }
+
+    async fn release_unpaid(&self, db: &DatabaseConnection) -> Result<u64, PaymentError> {
+        let Some(timeout) = self.timeout else {
+            return Ok(0);
+        };
+        let now = Utc::now().naive_utc();
+
+        let txn = db.begin().await?;
+        let tickets = Ticket::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                UNPAID_TICKETS_QUERY,
+                [
+                    TicketStatus::Booked.into_value().into(),
+                    (now - timeout).into(),
+                    now.into(),
+                ],
+            ))
+            .all(&txn)
+            .await?;
+        settle_tickets(&txn, &tickets, TicketStatus::Cancelled, now).await?;
+        txn.commit().await?;
+
+        // Run apart from the tickets so it never holds them while waiting
+        // on a payment a webhook is settling.
+        PaymentIntent::update_many()
+            .col_expr(PaymentIntentColumn::Status, Expr::value(PaymentStatus::Expired.into_value()))
+            .filter(PaymentIntentColumn::Status.eq(PaymentStatus::Pending))
+            .filter(PaymentIntentColumn::ExpiresAt.lte(now))
+            .exec(db)
+            .await?;
+
+        Ok(tickets.len() as u64)
+    }
+
+    async fn refund_ticket(&self, txn: &DatabaseTransaction, ticket_id: i32, amount: i32) -> Result<(), PaymentError> {
+        if amount <= 0 {
+            return Ok(());
+        }
+
+        // A ticket is paid by the first payment that succeeded for it; later
+        // ones covering it already refunded their share.
+        let Some(intent) = PaymentIntent::find()
+            .filter(PaymentIntentColumn::Status.eq(PaymentStatus::Succeeded))
+            .filter(
+                PaymentIntentColumn::PaymentIntentId.in_subquery(
+                    sea_orm::sea_query::Query::select()
+                        .column(PaymentIntentTicketColumn::PaymentIntentId)
+                        .from(PaymentIntentTicket)
+                        .and_where(PaymentIntentTicketColumn::TicketId.eq(ticket_id))
+                        .to_owned(),
+                ),
+            )
+            .order_by_asc(PaymentIntentColumn::CompletedAt)
+            .order_by_asc(PaymentIntentColumn::PaymentIntentId)
+            .lock_exclusive()
+            .one(txn)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        // A ticket is refunded at most once, so its id keeps retries of a
+        // transaction that failed after the refund from paying out twice.
+        self.provider
+            .refund(&intent.provider_ref, amount, &format!("ticket_{ticket_id}"))
+            .await?;
+
+        let refunded_amount = intent.refunded_amount + amount;
+        let mut active: PaymentIntentActiveModel = intent.into();
+        active.refunded_amount = Set(refunded_amount);
+        active.update(txn).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticket(ticket_id: i32, price: i32) -> TicketModel {
+        TicketModel {
+            ticket_id,
+            status: TicketStatus::Booked,
+            price,
+            trip_id: 1,
+            seat_id: ticket_id,
+            booked_date: NaiveDateTime::default(),
+            user_id: 1,
+            from_location: None,
+            to_location: None,
+            refund_amount: None,
+            from_stop: 0,
+            to_stop: 1,
+            discount: 0,
+            promo_redemption_id: None,
+        }
+    }
+
+    #[test]
+    fn total_price_adds_ticket_prices() {
+        assert_eq!(total_price(&[]).unwrap(), 0);
+        assert_eq!(total_price(&[ticket(1, 1500), ticket(2, 2500)]).unwrap(), 4000);
+        assert_eq!(total_price(&[ticket(1, i32::MAX - 1), ticket(2, 1)]).unwrap(), i32::MAX);
+    }
+
+    #[test]
+    fn total_price_refuses_totals_beyond_the_amount_column() {
+        let tickets = [ticket(1, i32::MAX), ticket(2, 1)];
+
+        assert!(matches!(total_price(&tickets), Err(PaymentError::AmountTooLarge)));
+    }
+}
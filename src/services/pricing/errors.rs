@@ -0,0 +1,28 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PricingError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("promo code not found")]
+    PromoNotFound,
+    #[error("promo code {0} is not valid yet")]
+    PromoNotStarted(String),
+    #[error("promo code {0} has expired")]
+    PromoExpired(String),
+    #[error("promo code {0} does not apply to this route")]
+    PromoNotForRoute(String),
+    #[error("promo code {code} needs a subtotal of at least {min_spend}")]
+    MinSpendNotMet { code: String, min_spend: i32 },
+    #[error("promo code {0} has been used up")]
+    PromoExhausted(String),
+    #[error("promo code {0} has already been used as often as allowed per customer")]
+    PromoLimitReached(String),
+    #[error("promo code already exists: {0}")]
+    CodeTaken(String),
+    #[error("routes not found: {0:?}")]
+    UnknownRoutes(Vec<i32>),
+    #[error("price is too large")]
+    AmountTooLarge,
+}
@@ -0,0 +1,144 @@
+mod errors;
+mod pricing;
+
+pub use errors::PricingError;
+pub use pricing::{PricingServiceImpl, normalize_code};
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sea_orm::{DatabaseConnection, DatabaseTransaction};
+
+use crate::entities::promo_code::Model as PromoCodeModel;
+use crate::entities::sea_orm_active_enums::PromoKind;
+
+/// A seat to price: its surcharge comes on top of the segment fare.
+#[derive(Debug, Clone)]
+pub struct SeatCharge {
+    pub seat_id: i32,
+    pub seat_name: String,
+    pub surcharge: i32,
+}
+
+/// Seats to price on a leg of `route_id` whose fare is `fare`, for `user_id`
+/// with `promo_code` when one is given.
+#[derive(Debug, Clone)]
+pub struct PriceRequest<'a> {
+    pub user_id: i32,
+    pub route_id: i32,
+    pub fare: i32,
+    pub seats: &'a [SeatCharge],
+    pub promo_code: Option<&'a str>,
+}
+
+/// One ticket of a quote; `price` is `fare + surcharge - discount`.
+#[derive(Debug, Clone)]
+pub struct PriceLine {
+    pub seat_id: i32,
+    pub seat_name: String,
+    pub fare: i32,
+    pub surcharge: i32,
+    pub discount: i32,
+    pub price: i32,
+}
+
+/// The price of a set of seats. A promo code's discount is spread over the
+/// lines in proportion to their price, so each ticket records what it cost.
+#[derive(Debug, Clone)]
+pub struct Quote {
+    pub lines: Vec<PriceLine>,
+    pub subtotal: i32,
+    pub discount: i32,
+    pub total: i32,
+    pub promo: Option<PromoCodeModel>,
+}
+
+/// A promo code as admins define it. `value` is a percentage for
+/// `Percentage` codes and an amount for `Fixed` ones; limits and validity
+/// bounds left out are unlimited. No `route_ids` means every route.
+#[derive(Debug, Clone)]
+pub struct PromoInput {
+    pub code: String,
+    pub kind: PromoKind,
+    pub value: i32,
+    pub max_discount: Option<i32>,
+    pub min_spend: i32,
+    pub valid_from: Option<NaiveDateTime>,
+    pub valid_until: Option<NaiveDateTime>,
+    pub max_uses: Option<i32>,
+    pub max_uses_per_user: Option<i32>,
+    pub route_ids: Vec<i32>,
+    pub active: bool,
+}
+
+/// A promo code with its routes and how many bookings count towards its
+/// usage limit.
+#[derive(Debug, Clone)]
+pub struct PromoDetails {
+    pub promo: PromoCodeModel,
+    pub route_ids: Vec<i32>,
+    pub uses: i64,
+}
+
+/// The name of a promo kind in the API.
+pub fn kind_name(kind: PromoKind) -> &'static str {
+    match kind {
+        PromoKind::Percentage => "percentage",
+        PromoKind::Fixed => "fixed",
+    }
+}
+
+pub fn parse_kind(name: &str) -> Option<PromoKind> {
+    match name {
+        "percentage" => Some(PromoKind::Percentage),
+        "fixed" => Some(PromoKind::Fixed),
+        _ => None,
+    }
+}
+
+#[async_trait]
+pub trait PricingService: Send + Sync {
+    /// Prices the request at `now`, taking off its promo code when the user
+    /// may use it.
+    async fn quote(
+        &self,
+        db: &DatabaseConnection,
+        request: &PriceRequest<'_>,
+        now: NaiveDateTime,
+    ) -> Result<Quote, PricingError>;
+
+    /// Prices the request as `quote` does for a booking made in `txn`. The
+    /// promo code stays locked until the transaction ends, so bookings made
+    /// at the same time cannot go past its usage limits.
+    async fn price(
+        &self,
+        txn: &DatabaseTransaction,
+        request: &PriceRequest<'_>,
+        now: NaiveDateTime,
+    ) -> Result<Quote, PricingError>;
+
+    /// Records that `user_id` used the quote's promo code and returns the
+    /// redemption the tickets should point at, or `None` without a promo
+    /// code.
+    async fn redeem(
+        &self,
+        txn: &DatabaseTransaction,
+        quote: &Quote,
+        user_id: i32,
+        now: NaiveDateTime,
+    ) -> Result<Option<i32>, PricingError>;
+
+    /// Every promo code, newest first.
+    async fn list_promo_codes(&self, db: &DatabaseConnection) -> Result<Vec<PromoDetails>, PricingError>;
+
+    /// Adds a promo code; codes are unique ignoring case.
+    async fn create_promo_code(&self, db: &DatabaseConnection, input: PromoInput) -> Result<PromoDetails, PricingError>;
+
+    /// Replaces a promo code's rules. Bookings already made keep their
+    /// discount.
+    async fn update_promo_code(
+        &self,
+        db: &DatabaseConnection,
+        promo_code_id: i32,
+        input: PromoInput,
+    ) -> Result<PromoDetails, PricingError>;
+}
@@ -0,0 +1,524 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    DatabaseTransaction, DbBackend, DbErr, EntityTrait, FromQueryResult, QueryFilter, QueryOrder, QuerySelect, Statement, TransactionTrait,
+};
+
+use crate::entities::promo_code::{
+    ActiveModel as PromoCodeActiveModel, Column as PromoCodeColumn, Entity as PromoCode, Model as PromoCodeModel,
+};
+use crate::entities::promo_code_route::{
+    ActiveModel as PromoCodeRouteActiveModel, Column as PromoCodeRouteColumn, Entity as PromoCodeRoute,
+};
+use crate::entities::promo_redemption::ActiveModel as PromoRedemptionActiveModel;
+use crate::entities::route::{Column as RouteColumn, Entity as Route};
+use crate::entities::sea_orm_active_enums::{PromoKind, TicketStatus};
+
+use super::{PriceLine, PriceRequest, PricingError, PromoDetails, PromoInput, Quote};
+
+/// Redemptions of promo code $1 that still have a ticket which was not
+/// cancelled or refunded; `by_user` counts those of user $2.
+///
+/// $3, $4 the cancelled and refunded statuses.
+const USAGE_QUERY: &str = r#"
+    SELECT COUNT(*) AS total, COUNT(*) FILTER (WHERE r.user_id = $2) AS by_user
+    FROM promo_redemption r
+    WHERE r.promo_code_id = $1
+      AND EXISTS (SELECT 1 FROM ticket k
+                  WHERE k.promo_redemption_id = r.promo_redemption_id AND k.status NOT IN ($3, $4))
+"#;
+
+/// The same count as `USAGE_QUERY` for every promo code.
+const USES_QUERY: &str = r#"
+    SELECT r.promo_code_id, COUNT(*) AS uses
+    FROM promo_redemption r
+    WHERE EXISTS (SELECT 1 FROM ticket k
+                  WHERE k.promo_redemption_id = r.promo_redemption_id AND k.status NOT IN ($1, $2))
+    GROUP BY r.promo_code_id
+"#;
+
+/// `USES_QUERY` for promo code $3 alone.
+const PROMO_USES_QUERY: &str = r#"
+    SELECT r.promo_code_id, COUNT(*) AS uses
+    FROM promo_redemption r
+    WHERE r.promo_code_id = $3
+      AND EXISTS (SELECT 1 FROM ticket k
+                  WHERE k.promo_redemption_id = r.promo_redemption_id AND k.status NOT IN ($1, $2))
+    GROUP BY r.promo_code_id
+"#;
+
+#[derive(FromQueryResult)]
+struct Usage {
+    total: i64,
+    by_user: i64,
+}
+
+#[derive(FromQueryResult)]
+struct PromoUses {
+    promo_code_id: i32,
+    uses: i64,
+}
+
+pub struct PricingServiceImpl;
+
+impl PricingServiceImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Promo codes are stored upper case, so customers may type them any way.
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+/// Prices `request` at `now`; with `lock` its promo code stays locked until
+/// the transaction ends.
+async fn price_request<C: ConnectionTrait>(
+    conn: &C,
+    request: &PriceRequest<'_>,
+    now: NaiveDateTime,
+    lock: bool,
+) -> Result<Quote, PricingError> {
+    let fare = request.fare;
+    let amounts: Vec<i64> = request
+        .seats
+        .iter()
+        .map(|seat| fare as i64 + seat.surcharge as i64)
+        .collect();
+    let subtotal: i64 = amounts.iter().sum();
+
+    let (promo, discount) = match request.promo_code.map(normalize_code).filter(|code| !code.is_empty()) {
+        Some(code) => {
+            let promo =
+                applicable_promo(conn, &code, request.user_id, request.route_id, subtotal, now, lock).await?;
+            let discount = discount_for(&promo, subtotal);
+            (Some(promo), discount)
+        }
+        None => (None, 0),
+    };
+
+    let shares = split_discount(discount, &amounts);
+    let lines = request
+        .seats
+        .iter()
+        .zip(amounts.iter().zip(shares))
+        .map(|(seat, (amount, share))| {
+            Ok(PriceLine {
+                seat_id: seat.seat_id,
+                seat_name: seat.seat_name.clone(),
+                fare,
+                surcharge: seat.surcharge,
+                discount: to_amount(share)?,
+                price: to_amount(amount - share)?,
+            })
+        })
+        .collect::<Result<_, PricingError>>()?;
+
+    Ok(Quote {
+        lines,
+        subtotal: to_amount(subtotal)?,
+        discount: to_amount(discount)?,
+        total: to_amount(subtotal - discount)?,
+        promo,
+    })
+}
+
+/// Amounts are summed as `i64` and stored as `i32`; one that does not fit
+/// fails with `AmountTooLarge`.
+fn to_amount(amount: i64) -> Result<i32, PricingError> {
+    i32::try_from(amount).map_err(|_| PricingError::AmountTooLarge)
+}
+
+/// The active promo code `code`, checked against every rule for this
+/// booking. Rules are checked in a fixed order so the error names the first
+/// one broken.
+async fn applicable_promo<C: ConnectionTrait>(
+    conn: &C,
+    code: &str,
+    user_id: i32,
+    route_id: i32,
+    subtotal: i64,
+    now: NaiveDateTime,
+    lock: bool,
+) -> Result<PromoCodeModel, PricingError> {
+    let mut query = PromoCode::find()
+        .filter(PromoCodeColumn::Code.eq(code))
+        .filter(PromoCodeColumn::Active.eq(true));
+    if lock {
+        query = query.lock_exclusive();
+    }
+    let promo = query.one(conn).await?.ok_or(PricingError::PromoNotFound)?;
+
+    if promo.valid_from.is_some_and(|from| now < from) {
+        return Err(PricingError::PromoNotStarted(promo.code));
+    }
+    if promo.valid_until.is_some_and(|until| now >= until) {
+        return Err(PricingError::PromoExpired(promo.code));
+    }
+
+    let route_ids = route_ids_of(conn, promo.promo_code_id).await?;
+    if !route_ids.is_empty() && !route_ids.contains(&route_id) {
+        return Err(PricingError::PromoNotForRoute(promo.code));
+    }
+    if subtotal < promo.min_spend as i64 {
+        return Err(PricingError::MinSpendNotMet {
+            code: promo.code,
+            min_spend: promo.min_spend,
+        });
+    }
+
+    if promo.max_uses.is_some() || promo.max_uses_per_user.is_some() {
+        let usage = Usage::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            USAGE_QUERY,
+            [
+                promo.promo_code_id.into(),
+                user_id.into(),
+                TicketStatus::Cancelled.into_value().into(),
+                TicketStatus::Refunded.into_value().into(),
+            ],
+        ))
+        .one(conn)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound("promo usage".to_string()))?;
+
+        if promo.max_uses.is_some_and(|max| usage.total >= max as i64) {
+            return Err(PricingError::PromoExhausted(promo.code));
+        }
+        if promo.max_uses_per_user.is_some_and(|max| usage.by_user >= max as i64) {
+            return Err(PricingError::PromoLimitReached(promo.code));
+        }
+    }
+
+    Ok(promo)
+}
+
+/// What the promo code takes off `subtotal`: a percentage capped at
+/// `max_discount`, or a fixed amount. Never more than the subtotal.
+fn discount_for(promo: &PromoCodeModel, subtotal: i64) -> i64 {
+    let discount = match promo.kind {
+        PromoKind::Percentage => {
+            let discount = subtotal * promo.value as i64 / 100;
+            promo.max_discount.map_or(discount, |max| discount.min(max as i64))
+        }
+        PromoKind::Fixed => promo.value as i64,
+    };
+    discount.clamp(0, subtotal)
+}
+
+/// Shares `discount` out over `amounts` in proportion to each. What rounding
+/// leaves over goes a unit at a time to the dearest amounts, earliest first.
+fn split_discount(discount: i64, amounts: &[i64]) -> Vec<i64> {
+    let total: i64 = amounts.iter().sum();
+    if discount == 0 || total == 0 {
+        return vec![0; amounts.len()];
+    }
+
+    let mut shares: Vec<i64> = amounts.iter().map(|amount| amount * discount / total).collect();
+    let rest = discount - shares.iter().sum::<i64>();
+
+    let mut order: Vec<usize> = (0..amounts.len()).collect();
+    order.sort_by_key(|&index| (std::cmp::Reverse(amounts[index]), index));
+    for index in order.into_iter().take(rest as usize) {
+        shares[index] += 1;
+    }
+
+    shares
+}
+
+async fn route_ids_of<C: ConnectionTrait>(conn: &C, promo_code_id: i32) -> Result<Vec<i32>, DbErr> {
+    Ok(PromoCodeRoute::find()
+        .filter(PromoCodeRouteColumn::PromoCodeId.eq(promo_code_id))
+        .order_by_asc(PromoCodeRouteColumn::RouteId)
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|route| route.route_id)
+        .collect())
+}
+
+async fn uses_of<C: ConnectionTrait>(conn: &C, promo_code_id: i32) -> Result<i64, DbErr> {
+    Ok(PromoUses::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        PROMO_USES_QUERY,
+        [
+            TicketStatus::Cancelled.into_value().into(),
+            TicketStatus::Refunded.into_value().into(),
+            promo_code_id.into(),
+        ],
+    ))
+    .one(conn)
+    .await?
+    .map_or(0, |uses| uses.uses))
+}
+
+async fn uses_by_promo<C: ConnectionTrait>(conn: &C) -> Result<HashMap<i32, i64>, DbErr> {
+    Ok(PromoUses::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        USES_QUERY,
+        [
+            TicketStatus::Cancelled.into_value().into(),
+            TicketStatus::Refunded.into_value().into(),
+        ],
+    ))
+    .all(conn)
+    .await?
+    .into_iter()
+    .map(|uses| (uses.promo_code_id, uses.uses))
+    .collect())
+}
+
+/// Fails with `UnknownRoutes` naming the routes in `route_ids` that do not
+/// exist.
+async fn ensure_routes_exist<C: ConnectionTrait>(conn: &C, route_ids: &[i32]) -> Result<(), PricingError> {
+    if route_ids.is_empty() {
+        return Ok(());
+    }
+
+    let found: Vec<i32> = Route::find()
+        .filter(RouteColumn::RouteId.is_in(route_ids.iter().copied()))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|route| route.route_id)
+        .collect();
+    let missing: Vec<i32> = route_ids.iter().copied().filter(|id| !found.contains(id)).collect();
+    if !missing.is_empty() {
+        return Err(PricingError::UnknownRoutes(missing));
+    }
+
+    Ok(())
+}
+
+async fn replace_routes<C: ConnectionTrait>(conn: &C, promo_code_id: i32, route_ids: &[i32]) -> Result<(), DbErr> {
+    PromoCodeRoute::delete_many()
+        .filter(PromoCodeRouteColumn::PromoCodeId.eq(promo_code_id))
+        .exec(conn)
+        .await?;
+    if !route_ids.is_empty() {
+        PromoCodeRoute::insert_many(route_ids.iter().map(|&route_id| PromoCodeRouteActiveModel {
+            promo_code_id: Set(promo_code_id),
+            route_id: Set(route_id),
+        }))
+        .exec(conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Copies `input` onto `active`, leaving the code's id and creation time.
+fn apply_input(active: &mut PromoCodeActiveModel, input: &PromoInput, code: String) {
+    active.code = Set(code);
+    active.kind = Set(input.kind);
+    active.value = Set(input.value);
+    active.max_discount = Set(input.max_discount);
+    active.min_spend = Set(input.min_spend);
+    active.valid_from = Set(input.valid_from);
+    active.valid_until = Set(input.valid_until);
+    active.max_uses = Set(input.max_uses);
+    active.max_uses_per_user = Set(input.max_uses_per_user);
+    active.active = Set(input.active);
+}
+
+/// Fails with `CodeTaken` when another promo code than `promo_code_id` is
+/// already called `code`.
+async fn ensure_code_free<C: ConnectionTrait>(
+    conn: &C,
+    code: &str,
+    promo_code_id: Option<i32>,
+) -> Result<(), PricingError> {
+    let existing = PromoCode::find()
+        .filter(PromoCodeColumn::Code.eq(code))
+        .one(conn)
+        .await?;
+    match existing {
+        Some(existing) if Some(existing.promo_code_id) != promo_code_id => Err(PricingError::CodeTaken(code.to_string())),
+        _ => Ok(()),
+    }
+}
+
+async fn details<C: ConnectionTrait>(conn: &C, promo: PromoCodeModel) -> Result<PromoDetails, DbErr> {
+    let route_ids = route_ids_of(conn, promo.promo_code_id).await?;
+    let uses = uses_of(conn, promo.promo_code_id).await?;
+
+    Ok(PromoDetails { promo, route_ids, uses })
+}
+
+#[async_trait]
+impl super::PricingService for PricingServiceImpl {
+    async fn quote(
+        &self,
+        db: &DatabaseConnection,
+        request: &PriceRequest<'_>,
+        now: NaiveDateTime,
+    ) -> Result<Quote, PricingError> {
+        price_request(db, request, now, false).await
+    }
+
+    async fn price(
+        &self,
+        txn: &DatabaseTransaction,
+        request: &PriceRequest<'_>,
+        now: NaiveDateTime,
+    ) -> Result<Quote, PricingError> {
+        price_request(txn, request, now, true).await
+    }
+
+    async fn redeem(
+        &self,
+        txn: &DatabaseTransaction,
+        quote: &Quote,
+        user_id: i32,
+        now: NaiveDateTime,
+    ) -> Result<Option<i32>, PricingError> {
+        let Some(promo) = &quote.promo else {
+            return Ok(None);
+        };
+
+        let redemption = PromoRedemptionActiveModel {
+            promo_code_id: Set(promo.promo_code_id),
+            user_id: Set(user_id),
+            discount: Set(quote.discount),
+            redeemed_at: Set(now),
+            ..Default::default()
+        }
+        .insert(txn)
+        .await?;
+
+        Ok(Some(redemption.promo_redemption_id))
+    }
+
+    async fn list_promo_codes(&self, db: &DatabaseConnection) -> Result<Vec<PromoDetails>, PricingError> {
+        let promos = PromoCode::find()
+            .find_with_related(PromoCodeRoute)
+            .order_by_desc(PromoCodeColumn::CreatedAt)
+            .order_by_desc(PromoCodeColumn::PromoCodeId)
+            .all(db)
+            .await?;
+        let uses = uses_by_promo(db).await?;
+
+        Ok(promos
+            .into_iter()
+            .map(|(promo, routes)| {
+                let mut route_ids: Vec<i32> = routes.into_iter().map(|route| route.route_id).collect();
+                route_ids.sort();
+                let uses = uses.get(&promo.promo_code_id).copied().unwrap_or(0);
+                PromoDetails { promo, route_ids, uses }
+            })
+            .collect())
+    }
+
+    async fn create_promo_code(&self, db: &DatabaseConnection, input: PromoInput) -> Result<PromoDetails, PricingError> {
+        let code = normalize_code(&input.code);
+        let txn = db.begin().await?;
+
+        ensure_code_free(&txn, &code, None).await?;
+        ensure_routes_exist(&txn, &input.route_ids).await?;
+
+        let mut active = PromoCodeActiveModel {
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        apply_input(&mut active, &input, code);
+        let promo = active.insert(&txn).await?;
+        replace_routes(&txn, promo.promo_code_id, &input.route_ids).await?;
+
+        let details = details(&txn, promo).await?;
+        txn.commit().await?;
+
+        Ok(details)
+    }
+
+    async fn update_promo_code(
+        &self,
+        db: &DatabaseConnection,
+        promo_code_id: i32,
+        input: PromoInput,
+    ) -> Result<PromoDetails, PricingError> {
+        let code = normalize_code(&input.code);
+        let txn = db.begin().await?;
+
+        let promo = PromoCode::find_by_id(promo_code_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(PricingError::PromoNotFound)?;
+        ensure_code_free(&txn, &code, Some(promo_code_id)).await?;
+        ensure_routes_exist(&txn, &input.route_ids).await?;
+
+        let mut active: PromoCodeActiveModel = promo.into();
+        apply_input(&mut active, &input, code);
+        let promo = active.update(&txn).await?;
+        replace_routes(&txn, promo_code_id, &input.route_ids).await?;
+
+        let details = details(&txn, promo).await?;
+        txn.commit().await?;
+
+        Ok(details)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+
+    fn promo(kind: PromoKind, value: i32, max_discount: Option<i32>) -> PromoCodeModel {
+        PromoCodeModel {
+            promo_code_id: 1,
+            code: "SAVE".to_string(),
+            kind,
+            value,
+            max_discount,
+            min_spend: 0,
+            valid_from: None,
+            valid_until: None,
+            max_uses: None,
+            max_uses_per_user: None,
+            active: true,
+            created_at: NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn percentage_discount_is_capped_at_max_discount() {
+        assert_eq!(discount_for(&promo(PromoKind::Percentage, 10, None), 250_000), 25_000);
+        assert_eq!(discount_for(&promo(PromoKind::Percentage, 10, Some(20_000)), 250_000), 20_000);
+    }
+
+    #[test]
+    fn discount_never_exceeds_subtotal() {
+        assert_eq!(discount_for(&promo(PromoKind::Fixed, 50_000, None), 30_000), 30_000);
+        assert_eq!(discount_for(&promo(PromoKind::Fixed, 50_000, None), 80_000), 50_000);
+        assert_eq!(discount_for(&promo(PromoKind::Percentage, 150, None), 80_000), 80_000);
+    }
+
+    #[test]
+    fn split_discount_is_proportional() {
+        assert_eq!(split_discount(30_000, &[100_000, 200_000]), vec![10_000, 20_000]);
+    }
+
+    #[test]
+    fn split_discount_gives_remainder_to_dearest_lines_first() {
+        assert_eq!(split_discount(10, &[100, 100, 100]), vec![4, 3, 3]);
+        assert_eq!(split_discount(2, &[100, 300, 300]), vec![0, 1, 1]);
+    }
+
+    #[test]
+    fn split_discount_without_discount_or_amounts_is_zero() {
+        assert_eq!(split_discount(0, &[100, 200]), vec![0, 0]);
+        assert_eq!(split_discount(10, &[0, 0]), vec![0, 0]);
+    }
+
+    #[test]
+    fn amounts_past_i32_are_refused() {
+        assert_eq!(to_amount(i32::MAX as i64).ok(), Some(i32::MAX));
+        assert!(matches!(to_amount(i32::MAX as i64 + 1), Err(PricingError::AmountTooLarge)));
+    }
+}
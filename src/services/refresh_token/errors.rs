@@ -0,0 +1,14 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RefreshTokenError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("invalid refresh token")]
+    Invalid,
+    #[error("refresh token expired")]
+    Expired,
+    #[error("refresh token reused")]
+    Reused,
+}
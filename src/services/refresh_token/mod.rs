@@ -0,0 +1,28 @@
+mod errors;
+mod refresh_token;
+
+pub use errors::RefreshTokenError;
+pub use refresh_token::RefreshTokenServiceImpl;
+
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+
+use crate::entities::user::Model as UserModel;
+
+#[async_trait]
+pub trait RefreshTokenService: Send + Sync {
+    /// Starts a new token family for the user and returns the opaque token.
+    async fn issue(&self, db: &DatabaseConnection, user_id: i32) -> Result<String, RefreshTokenError>;
+
+    /// Exchanges a token for its successor in the same family. Presenting a
+    /// token that was already rotated revokes the whole family.
+    async fn rotate(
+        &self,
+        db: &DatabaseConnection,
+        token: &str,
+    ) -> Result<(UserModel, String), RefreshTokenError>;
+
+    async fn revoke_family(&self, db: &DatabaseConnection, token: &str) -> Result<(), RefreshTokenError>;
+
+    async fn revoke_all_for_user(&self, db: &DatabaseConnection, user_id: i32) -> Result<(), RefreshTokenError>;
+}
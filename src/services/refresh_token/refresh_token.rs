@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, QuerySelect, TransactionTrait,
+};
+
+use crate::entities::refresh_token::{
+    ActiveModel as RefreshTokenActiveModel, Column as RefreshTokenColumn, Entity as RefreshToken,
+};
+use crate::entities::user::{Entity as User, Model as UserModel};
+use crate::services::opaque_token;
+
+use super::RefreshTokenError;
+
+pub struct RefreshTokenServiceImpl {
+    ttl_seconds: u64,
+}
+
+impl RefreshTokenServiceImpl {
+    pub fn new(ttl_seconds: u64) -> Self {
+        Self { ttl_seconds }
+    }
+
+    async fn insert<C: ConnectionTrait>(
+        &self,
+        conn: &C,
+        family_id: String,
+        user_id: i32,
+    ) -> Result<String, RefreshTokenError> {
+        let token = opaque_token::generate(32);
+        let now = Utc::now().naive_utc();
+
+        RefreshTokenActiveModel {
+            family_id: Set(family_id),
+            user_id: Set(user_id),
+            token_hash: Set(opaque_token::hash(&token)),
+            expires_at: Set(now + Duration::seconds(self.ttl_seconds as i64)),
+            created_at: Set(now),
+            rotated_at: Set(None),
+            revoked: Set(false),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?;
+
+        Ok(token)
+    }
+}
+
+async fn revoke_family_by_id<C: ConnectionTrait>(conn: &C, family_id: &str) -> Result<(), RefreshTokenError> {
+    RefreshToken::update_many()
+        .col_expr(RefreshTokenColumn::Revoked, Expr::value(true))
+        .filter(RefreshTokenColumn::FamilyId.eq(family_id))
+        .exec(conn)
+        .await?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl super::RefreshTokenService for RefreshTokenServiceImpl {
+    async fn issue(&self, db: &DatabaseConnection, user_id: i32) -> Result<String, RefreshTokenError> {
+        self.insert(db, opaque_token::generate(16), user_id).await
+    }
+
+    async fn rotate(
+        &self,
+        db: &DatabaseConnection,
+        token: &str,
+    ) -> Result<(UserModel, String), RefreshTokenError> {
+        let txn = db.begin().await?;
+
+        // Row lock so two concurrent uses of the same token serialize; the
+        // loser then sees it as rotated and trips reuse detection.
+        let current = RefreshToken::find()
+            .filter(RefreshTokenColumn::TokenHash.eq(opaque_token::hash(token)))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(RefreshTokenError::Invalid)?;
+
+        if current.revoked || current.rotated_at.is_some() {
+            revoke_family_by_id(&txn, &current.family_id).await?;
+            txn.commit().await?;
+            return Err(RefreshTokenError::Reused);
+        }
+
+        let now = Utc::now().naive_utc();
+        if current.expires_at <= now {
+            return Err(RefreshTokenError::Expired);
+        }
+
+        let user = User::find_by_id(current.user_id)
+            .one(&txn)
+            .await?
+            .ok_or(RefreshTokenError::Invalid)?;
+
+        let family_id = current.family_id.clone();
+        let mut active: RefreshTokenActiveModel = current.into();
+        active.rotated_at = Set(Some(now));
+        active.update(&txn).await?;
+
+        let next = self.insert(&txn, family_id, user.user_id).await?;
+        txn.commit().await?;
+
+        Ok((user, next))
+    }
+
+    async fn revoke_family(&self, db: &DatabaseConnection, token: &str) -> Result<(), RefreshTokenError> {
+        let current = RefreshToken::find()
+            .filter(RefreshTokenColumn::TokenHash.eq(opaque_token::hash(token)))
+            .one(db)
+            .await?;
+
+        if let Some(current) = current {
+            revoke_family_by_id(db, &current.family_id).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn revoke_all_for_user(&self, db: &DatabaseConnection, user_id: i32) -> Result<(), RefreshTokenError> {
+        RefreshToken::update_many()
+            .col_expr(RefreshTokenColumn::Revoked, Expr::value(true))
+            .filter(RefreshTokenColumn::UserId.eq(user_id))
+            .filter(RefreshTokenColumn::Revoked.eq(false))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+use crate::services::mailer::MailerError;
+use crate::services::password::PasswordServiceError;
+
+#[derive(Debug, Error)]
+pub enum RegistrationError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("password error: {0}")]
+    Password(#[from] PasswordServiceError),
+    #[error("mailer error: {0}")]
+    Mailer(#[from] MailerError),
+    #[error("invalid verification token")]
+    InvalidToken,
+    #[error("verification token expired")]
+    Expired,
+}
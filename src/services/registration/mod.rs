@@ -0,0 +1,31 @@
+mod errors;
+mod registration;
+
+pub use errors::RegistrationError;
+pub use registration::RegistrationServiceImpl;
+
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+
+/// Role given to self-registered accounts.
+pub const DEFAULT_ROLE_ID: i32 = 0;
+
+#[derive(Debug, Clone)]
+pub struct NewUser {
+    pub email: String,
+    pub password: String,
+    pub fullname: String,
+    pub phone: String,
+    pub address: String,
+}
+
+#[async_trait]
+pub trait RegistrationService: Send + Sync {
+    /// Creates an unverified account and mails its verification link. An
+    /// address that already has an account is sent a notice instead, so the
+    /// outcome looks the same to the caller either way.
+    async fn register(&self, db: &DatabaseConnection, user: NewUser) -> Result<(), RegistrationError>;
+
+    /// Consumes a verification token and marks the account as verified.
+    async fn verify_email(&self, db: &DatabaseConnection, token: &str) -> Result<(), RegistrationError>;
+}
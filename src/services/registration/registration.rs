@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect,
+    SqlErr, TransactionTrait,
+};
+
+use crate::entities::email_verification::{
+    ActiveModel as EmailVerificationActiveModel, Column as EmailVerificationColumn, Entity as EmailVerification,
+};
+use crate::entities::user::{ActiveModel as UserActiveModel, Column as UserColumn, Entity as User, Model as UserModel};
+use crate::services::auth::normalize_email;
+use crate::services::mailer::{Mail, Mailer};
+use crate::services::opaque_token;
+use crate::services::password::PasswordService;
+
+use super::{DEFAULT_ROLE_ID, NewUser, RegistrationError};
+
+pub struct RegistrationServiceImpl {
+    password_service: Arc<dyn PasswordService>,
+    mailer: Arc<dyn Mailer>,
+    public_url: String,
+    ttl_seconds: u64,
+}
+
+impl RegistrationServiceImpl {
+    pub fn new(
+        password_service: Arc<dyn PasswordService>,
+        mailer: Arc<dyn Mailer>,
+        public_url: String,
+        ttl_seconds: u64,
+    ) -> Self {
+        Self {
+            password_service,
+            mailer,
+            public_url,
+            ttl_seconds,
+        }
+    }
+
+    async fn send_already_registered(&self, email: String) -> Result<(), RegistrationError> {
+        self.mailer
+            .send(Mail {
+                to: email,
+                subject: "You already have an account".to_string(),
+                body: format!(
+                    "Someone tried to register a new account with this address, which already has one.\n\n\
+                     If that was you, sign in instead. Otherwise you can ignore this message.\n\n{}",
+                    self.public_url
+                ),
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Stores a fresh verification token for the user and mails its link.
+    async fn send_verification<C: ConnectionTrait>(&self, conn: &C, user: &UserModel) -> Result<(), RegistrationError> {
+        let token = opaque_token::generate(32);
+        let now = Utc::now().naive_utc();
+
+        EmailVerificationActiveModel {
+            token_hash: Set(opaque_token::hash(&token)),
+            user_id: Set(user.user_id),
+            expires_at: Set(now + Duration::seconds(self.ttl_seconds as i64)),
+            created_at: Set(now),
+        }
+        .insert(conn)
+        .await?;
+
+        self.mailer
+            .send(Mail {
+                to: user.email.clone(),
+                subject: "Verify your email address".to_string(),
+                body: format!(
+                    "Welcome, {}!\n\nConfirm your email address to activate your account:\n\n\
+                     {}/api/v1/auth/verify-email?token={}\n\nThe link expires in {} hours.",
+                    user.fullname,
+                    self.public_url,
+                    token,
+                    self.ttl_seconds / 3600
+                ),
+            })
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl super::RegistrationService for RegistrationServiceImpl {
+    async fn register(&self, db: &DatabaseConnection, user: NewUser) -> Result<(), RegistrationError> {
+        let email = normalize_email(&user.email);
+        // Hashed up front so both outcomes take about as long.
+        let password = self.password_service.hash_password(&user.password).await?;
+
+        let existing = User::find()
+            .filter(UserColumn::Email.eq(&email))
+            .one(db)
+            .await?;
+        match existing {
+            // A repeated registration of a pending account re-sends its link.
+            Some(user) if !user.email_verified => return self.send_verification(db, &user).await,
+            Some(_) => return self.send_already_registered(email).await,
+            None => {}
+        }
+
+        let txn = db.begin().await?;
+
+        let inserted = UserActiveModel {
+            email: Set(email.clone()),
+            fullname: Set(user.fullname),
+            phone: Set(user.phone),
+            address: Set(user.address),
+            role_id: Set(DEFAULT_ROLE_ID),
+            password: Set(password),
+            email_verified: Set(false),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await;
+
+        let created = match inserted {
+            Ok(created) => created,
+            // Lost a race against a concurrent registration of the same address.
+            Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                txn.rollback().await?;
+                return self.send_already_registered(email).await;
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        // Sent before committing so a mail failure leaves no account behind
+        // that could never be verified.
+        self.send_verification(&txn, &created).await?;
+
+        txn.commit().await?;
+        Ok(())
+    }
+
+    async fn verify_email(&self, db: &DatabaseConnection, token: &str) -> Result<(), RegistrationError> {
+        let txn = db.begin().await?;
+
+        let verification = EmailVerification::find_by_id(opaque_token::hash(token))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(RegistrationError::InvalidToken)?;
+
+        if verification.expires_at <= Utc::now().naive_utc() {
+            EmailVerification::delete_by_id(verification.token_hash).exec(&txn).await?;
+            txn.commit().await?;
+            return Err(RegistrationError::Expired);
+        }
+
+        User::update_many()
+            .col_expr(UserColumn::EmailVerified, Expr::value(true))
+            .filter(UserColumn::UserId.eq(verification.user_id))
+            .exec(&txn)
+            .await?;
+
+        EmailVerification::delete_many()
+            .filter(EmailVerificationColumn::UserId.eq(verification.user_id))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(())
+    }
+}
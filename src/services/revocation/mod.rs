@@ -0,0 +1,29 @@
+mod errors;
+mod revocation;
+
+pub use errors::RevocationError;
+pub use revocation::RevocationServiceImpl;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use libs::jwt::Claims;
+use libs::revocation::RevocationStore;
+use sea_orm::DatabaseConnection;
+
+#[async_trait]
+pub trait RevocationService: Send + Sync {
+    /// Revokes a single access token until its natural expiry.
+    async fn revoke_token(&self, db: &DatabaseConnection, claims: &Claims) -> Result<(), RevocationError>;
+
+    /// Revokes every access token issued to `sub` so far, including earlier in
+    /// the current second; returns once that second has passed.
+    async fn revoke_subject(&self, db: &DatabaseConnection, sub: &str) -> Result<(), RevocationError>;
+
+    /// Reloads the in-memory cache from Postgres and drops expired rows, so
+    /// revocations made on other nodes are picked up.
+    async fn sync(&self, db: &DatabaseConnection) -> Result<(), RevocationError>;
+
+    /// The cache consulted by the JWT middlewares.
+    fn store(&self) -> Arc<dyn RevocationStore>;
+}
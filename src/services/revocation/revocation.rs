@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use libs::jwt::Claims;
+use libs::revocation::{MemoryRevocationStore, RevocationStore};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+use crate::entities::revoked_token::{
+    ActiveModel as RevokedTokenActiveModel, Column as RevokedTokenColumn, Entity as RevokedToken,
+};
+use crate::entities::subject_revocation::{
+    ActiveModel as SubjectRevocationActiveModel, Column as SubjectRevocationColumn, Entity as SubjectRevocation,
+};
+
+use super::RevocationError;
+
+pub struct RevocationServiceImpl {
+    cache: Arc<MemoryRevocationStore>,
+}
+
+impl RevocationServiceImpl {
+    pub fn new() -> Self {
+        Self {
+            cache: Arc::new(MemoryRevocationStore::new()),
+        }
+    }
+}
+
+fn to_datetime(seconds: usize) -> NaiveDateTime {
+    DateTime::from_timestamp(seconds as i64, 0)
+        .unwrap_or_default()
+        .naive_utc()
+}
+
+fn to_seconds(datetime: NaiveDateTime) -> usize {
+    datetime.and_utc().timestamp().max(0) as usize
+}
+
+/// `iat` only has whole seconds, so a revoke-all cutoff is rounded up to the
+/// next second: tokens issued earlier in the same second are then rejected too.
+fn cutoff_seconds(revoked_before: NaiveDateTime) -> usize {
+    let seconds = to_seconds(revoked_before);
+    if revoked_before.and_utc().timestamp_subsec_nanos() > 0 { seconds + 1 } else { seconds }
+}
+
+#[async_trait]
+impl super::RevocationService for RevocationServiceImpl {
+    async fn revoke_token(&self, db: &DatabaseConnection, claims: &Claims) -> Result<(), RevocationError> {
+        RevokedToken::insert(RevokedTokenActiveModel {
+            jti: Set(claims.jti.clone()),
+            sub: Set(claims.sub.clone()),
+            expires_at: Set(to_datetime(claims.exp)),
+            revoked_at: Set(Utc::now().naive_utc()),
+        })
+        .on_conflict(OnConflict::column(RevokedTokenColumn::Jti).do_nothing().to_owned())
+        .do_nothing()
+        .exec(db)
+        .await?;
+
+        self.cache.revoke_token(claims.jti.clone(), claims.exp);
+        Ok(())
+    }
+
+    async fn revoke_subject(&self, db: &DatabaseConnection, sub: &str) -> Result<(), RevocationError> {
+        let now = Utc::now().naive_utc();
+
+        SubjectRevocation::insert(SubjectRevocationActiveModel {
+            sub: Set(sub.to_string()),
+            revoked_before: Set(now),
+        })
+        .on_conflict(
+            OnConflict::column(SubjectRevocationColumn::Sub)
+                .update_column(SubjectRevocationColumn::RevokedBefore)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+        let cutoff = cutoff_seconds(now);
+        self.cache.revoke_subject(sub, cutoff);
+
+        // Waits out the rounded-up second so tokens issued once this returns
+        // carry an `iat` at or past the cutoff and stay valid.
+        let remaining = to_datetime(cutoff) - Utc::now().naive_utc();
+        tokio::time::sleep(remaining.to_std().unwrap_or_default()).await;
+        Ok(())
+    }
+
+    async fn sync(&self, db: &DatabaseConnection) -> Result<(), RevocationError> {
+        let now = Utc::now().naive_utc();
+
+        RevokedToken::delete_many()
+            .filter(RevokedTokenColumn::ExpiresAt.lte(now))
+            .exec(db)
+            .await?;
+
+        // Revocations only ever accumulate, so merging is enough and cannot
+        // drop an entry written locally while the query was in flight.
+        for row in RevokedToken::find().all(db).await? {
+            self.cache.revoke_token(row.jti, to_seconds(row.expires_at));
+        }
+
+        for row in SubjectRevocation::find().all(db).await? {
+            self.cache.revoke_subject(row.sub, cutoff_seconds(row.revoked_before));
+        }
+
+        self.cache.purge_expired(to_seconds(now));
+        Ok(())
+    }
+
+    fn store(&self) -> Arc<dyn RevocationStore> {
+        self.cache.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libs::jwt::build_claims;
+
+    fn at(seconds: i64, millis: u32) -> NaiveDateTime {
+        DateTime::from_timestamp(seconds, millis * 1_000_000).unwrap().naive_utc()
+    }
+
+    #[test]
+    fn cutoff_rounds_partial_seconds_up() {
+        assert_eq!(cutoff_seconds(at(1_700_000_000, 250)), 1_700_000_001);
+        assert_eq!(cutoff_seconds(at(1_700_000_000, 999)), 1_700_000_001);
+        assert_eq!(cutoff_seconds(at(1_700_000_000, 0)), 1_700_000_000);
+    }
+
+    #[test]
+    fn token_issued_in_the_revocation_second_is_revoked() {
+        let store = MemoryRevocationStore::new();
+        let claims = build_claims("user@example.com".to_string(), 900, Vec::new(), Vec::new());
+
+        // Revoked later in the second the token was issued in.
+        store.revoke_subject(claims.sub.clone(), cutoff_seconds(at(claims.iat as i64, 500)));
+        assert!(store.is_revoked(&claims));
+    }
+
+    #[test]
+    fn token_issued_from_the_cutoff_on_stays_valid() {
+        let store = MemoryRevocationStore::new();
+        let mut claims = build_claims("user@example.com".to_string(), 900, Vec::new(), Vec::new());
+        let revoked_before = at(claims.iat as i64, 500);
+        store.revoke_subject(claims.sub.clone(), cutoff_seconds(revoked_before));
+
+        claims.iat = cutoff_seconds(revoked_before);
+        assert!(!store.is_revoked(&claims));
+    }
+}
@@ -0,0 +1,20 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RoleError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("role not found")]
+    RoleNotFound,
+    #[error("policy not found")]
+    PolicyNotFound,
+    #[error("user not found")]
+    UserNotFound,
+    #[error("unknown policies: {}", .0.join(", "))]
+    UnknownPolicies(Vec<String>),
+    #[error("name already taken: {0}")]
+    NameTaken(String),
+    #[error("role is still assigned to users")]
+    RoleInUse,
+}
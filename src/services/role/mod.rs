@@ -0,0 +1,77 @@
+mod errors;
+mod role;
+
+pub use errors::RoleError;
+pub use role::RoleServiceImpl;
+
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+
+use crate::entities::policy::Model as PolicyModel;
+use crate::entities::role::Model as RoleModel;
+
+/// Roles and policies signed into a user's access token.
+#[derive(Debug, Clone)]
+pub struct UserAccess {
+    pub roles: Vec<String>,
+    pub policies: Vec<String>,
+}
+
+/// A role together with the names of the policies it grants.
+#[derive(Debug, Clone)]
+pub struct RoleDetails {
+    pub role: RoleModel,
+    pub policies: Vec<String>,
+}
+
+#[async_trait]
+pub trait RoleService: Send + Sync {
+    /// Resolves the roles and policies granted by `role_id`.
+    async fn resolve(&self, db: &DatabaseConnection, role_id: i32) -> Result<UserAccess, RoleError>;
+
+    async fn list_roles(&self, db: &DatabaseConnection) -> Result<Vec<RoleDetails>, RoleError>;
+
+    async fn create_role(
+        &self,
+        db: &DatabaseConnection,
+        name: &str,
+        policies: &[String],
+        mfa_required: bool,
+    ) -> Result<RoleDetails, RoleError>;
+
+    /// Renames the role and replaces its policies. `mfa_required` is only
+    /// changed when given.
+    async fn update_role(
+        &self,
+        db: &DatabaseConnection,
+        role_id: i32,
+        name: &str,
+        policies: &[String],
+        mfa_required: Option<bool>,
+    ) -> Result<RoleDetails, RoleError>;
+
+    /// Fails with `RoleInUse` while any user still has the role.
+    async fn delete_role(&self, db: &DatabaseConnection, role_id: i32) -> Result<(), RoleError>;
+
+    async fn assign_role(&self, db: &DatabaseConnection, user_id: i32, role_id: i32) -> Result<(), RoleError>;
+
+    async fn list_policies(&self, db: &DatabaseConnection) -> Result<Vec<PolicyModel>, RoleError>;
+
+    async fn create_policy(
+        &self,
+        db: &DatabaseConnection,
+        name: &str,
+        description: &str,
+    ) -> Result<PolicyModel, RoleError>;
+
+    async fn update_policy(
+        &self,
+        db: &DatabaseConnection,
+        policy_id: i32,
+        name: &str,
+        description: &str,
+    ) -> Result<PolicyModel, RoleError>;
+
+    /// Also removes the policy from every role that granted it.
+    async fn delete_policy(&self, db: &DatabaseConnection, policy_id: i32) -> Result<(), RoleError>;
+}
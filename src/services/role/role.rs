@@ -0,0 +1,293 @@
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+
+use crate::entities::policy::{
+    ActiveModel as PolicyActiveModel, Column as PolicyColumn, Entity as Policy, Model as PolicyModel,
+};
+use crate::entities::role::{ActiveModel as RoleActiveModel, Column as RoleColumn, Entity as Role};
+use crate::entities::role_policy::{
+    ActiveModel as RolePolicyActiveModel, Column as RolePolicyColumn, Entity as RolePolicy,
+};
+use crate::entities::user::{Column as UserColumn, Entity as User};
+
+use super::{RoleDetails, RoleError, UserAccess};
+
+pub struct RoleServiceImpl;
+
+impl RoleServiceImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+async fn policy_names<C: ConnectionTrait>(conn: &C, role_id: i32) -> Result<Vec<String>, RoleError> {
+    Ok(Policy::find()
+        .inner_join(RolePolicy)
+        .filter(RolePolicyColumn::RoleId.eq(role_id))
+        .order_by_asc(PolicyColumn::Name)
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|policy| policy.name)
+        .collect())
+}
+
+/// Looks up policy ids by name, failing with every name that does not exist.
+async fn policy_ids<C: ConnectionTrait>(conn: &C, names: &[String]) -> Result<Vec<i32>, RoleError> {
+    let wanted: BTreeSet<&str> = names.iter().map(String::as_str).collect();
+    if wanted.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let found = Policy::find()
+        .filter(PolicyColumn::Name.is_in(wanted.iter().copied()))
+        .all(conn)
+        .await?;
+
+    let unknown: Vec<String> = wanted
+        .iter()
+        .filter(|name| !found.iter().any(|policy| policy.name == **name))
+        .map(|name| name.to_string())
+        .collect();
+    if !unknown.is_empty() {
+        return Err(RoleError::UnknownPolicies(unknown));
+    }
+
+    Ok(found.into_iter().map(|policy| policy.policy_id).collect())
+}
+
+async fn replace_policies<C: ConnectionTrait>(conn: &C, role_id: i32, policy_ids: Vec<i32>) -> Result<(), RoleError> {
+    RolePolicy::delete_many()
+        .filter(RolePolicyColumn::RoleId.eq(role_id))
+        .exec(conn)
+        .await?;
+
+    if policy_ids.is_empty() {
+        return Ok(());
+    }
+
+    RolePolicy::insert_many(policy_ids.into_iter().map(|policy_id| RolePolicyActiveModel {
+        role_id: Set(role_id),
+        policy_id: Set(policy_id),
+    }))
+    .exec(conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn ensure_role_name_free<C: ConnectionTrait>(conn: &C, name: &str, role_id: Option<i32>) -> Result<(), RoleError> {
+    let mut query = Role::find().filter(RoleColumn::Name.eq(name));
+    if let Some(role_id) = role_id {
+        query = query.filter(RoleColumn::RoleId.ne(role_id));
+    }
+
+    match query.one(conn).await? {
+        Some(_) => Err(RoleError::NameTaken(name.to_string())),
+        None => Ok(()),
+    }
+}
+
+async fn ensure_policy_name_free<C: ConnectionTrait>(conn: &C, name: &str, policy_id: Option<i32>) -> Result<(), RoleError> {
+    let mut query = Policy::find().filter(PolicyColumn::Name.eq(name));
+    if let Some(policy_id) = policy_id {
+        query = query.filter(PolicyColumn::PolicyId.ne(policy_id));
+    }
+
+    match query.one(conn).await? {
+        Some(_) => Err(RoleError::NameTaken(name.to_string())),
+        None => Ok(()),
+    }
+}
+
+#[async_trait]
+impl super::RoleService for RoleServiceImpl {
+    async fn resolve(&self, db: &DatabaseConnection, role_id: i32) -> Result<UserAccess, RoleError> {
+        let role = Role::find_by_id(role_id)
+            .one(db)
+            .await?
+            .ok_or(RoleError::RoleNotFound)?;
+
+        Ok(UserAccess {
+            roles: vec![role.name],
+            policies: policy_names(db, role_id).await?,
+        })
+    }
+
+    async fn list_roles(&self, db: &DatabaseConnection) -> Result<Vec<RoleDetails>, RoleError> {
+        let roles = Role::find()
+            .find_with_related(Policy)
+            .order_by_asc(RoleColumn::RoleId)
+            .all(db)
+            .await?;
+
+        Ok(roles
+            .into_iter()
+            .map(|(role, policies)| {
+                let mut policies: Vec<String> = policies.into_iter().map(|policy| policy.name).collect();
+                policies.sort();
+                RoleDetails { role, policies }
+            })
+            .collect())
+    }
+
+    async fn create_role(
+        &self,
+        db: &DatabaseConnection,
+        name: &str,
+        policies: &[String],
+        mfa_required: bool,
+    ) -> Result<RoleDetails, RoleError> {
+        let txn = db.begin().await?;
+
+        ensure_role_name_free(&txn, name, None).await?;
+        let policy_ids = policy_ids(&txn, policies).await?;
+
+        let role = RoleActiveModel {
+            name: Set(name.to_string()),
+            mfa_required: Set(mfa_required),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        replace_policies(&txn, role.role_id, policy_ids).await?;
+        let policies = policy_names(&txn, role.role_id).await?;
+        txn.commit().await?;
+
+        Ok(RoleDetails { role, policies })
+    }
+
+    async fn update_role(
+        &self,
+        db: &DatabaseConnection,
+        role_id: i32,
+        name: &str,
+        policies: &[String],
+        mfa_required: Option<bool>,
+    ) -> Result<RoleDetails, RoleError> {
+        let txn = db.begin().await?;
+
+        let role = Role::find_by_id(role_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(RoleError::RoleNotFound)?;
+
+        ensure_role_name_free(&txn, name, Some(role_id)).await?;
+        let policy_ids = policy_ids(&txn, policies).await?;
+
+        let mut active: RoleActiveModel = role.into();
+        active.name = Set(name.to_string());
+        if let Some(mfa_required) = mfa_required {
+            active.mfa_required = Set(mfa_required);
+        }
+        let role = active.update(&txn).await?;
+
+        replace_policies(&txn, role_id, policy_ids).await?;
+        let policies = policy_names(&txn, role_id).await?;
+        txn.commit().await?;
+
+        Ok(RoleDetails { role, policies })
+    }
+
+    async fn delete_role(&self, db: &DatabaseConnection, role_id: i32) -> Result<(), RoleError> {
+        let txn = db.begin().await?;
+
+        Role::find_by_id(role_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(RoleError::RoleNotFound)?;
+
+        let users = User::find()
+            .filter(UserColumn::RoleId.eq(role_id))
+            .count(&txn)
+            .await?;
+        if users > 0 {
+            return Err(RoleError::RoleInUse);
+        }
+
+        Role::delete_by_id(role_id).exec(&txn).await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn assign_role(&self, db: &DatabaseConnection, user_id: i32, role_id: i32) -> Result<(), RoleError> {
+        Role::find_by_id(role_id)
+            .one(db)
+            .await?
+            .ok_or(RoleError::RoleNotFound)?;
+
+        let result = User::update_many()
+            .col_expr(UserColumn::RoleId, Expr::value(role_id))
+            .filter(UserColumn::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Err(RoleError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn list_policies(&self, db: &DatabaseConnection) -> Result<Vec<PolicyModel>, RoleError> {
+        Ok(Policy::find().order_by_asc(PolicyColumn::Name).all(db).await?)
+    }
+
+    async fn create_policy(
+        &self,
+        db: &DatabaseConnection,
+        name: &str,
+        description: &str,
+    ) -> Result<PolicyModel, RoleError> {
+        ensure_policy_name_free(db, name, None).await?;
+
+        Ok(PolicyActiveModel {
+            name: Set(name.to_string()),
+            description: Set(description.to_string()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?)
+    }
+
+    async fn update_policy(
+        &self,
+        db: &DatabaseConnection,
+        policy_id: i32,
+        name: &str,
+        description: &str,
+    ) -> Result<PolicyModel, RoleError> {
+        let policy = Policy::find_by_id(policy_id)
+            .one(db)
+            .await?
+            .ok_or(RoleError::PolicyNotFound)?;
+
+        ensure_policy_name_free(db, name, Some(policy_id)).await?;
+
+        let mut active: PolicyActiveModel = policy.into();
+        active.name = Set(name.to_string());
+        active.description = Set(description.to_string());
+
+        Ok(active.update(db).await?)
+    }
+
+    async fn delete_policy(&self, db: &DatabaseConnection, policy_id: i32) -> Result<(), RoleError> {
+        let result = Policy::delete_by_id(policy_id).exec(db).await?;
+
+        if result.rows_affected == 0 {
+            return Err(RoleError::PolicyNotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RouteError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("route not found")]
+    RouteNotFound,
+    #[error("no location named {0} in the catalogue")]
+    UnknownLocation(String),
+    #[error("route still has {0} upcoming trip(s)")]
+    HasUpcomingTrips(u64),
+    #[error("stop fares add up to {0}, leaving nothing of base_price for the last leg")]
+    StopFaresExceedPrice(i32),
+    #[error("stop fares add up to more than a fare can hold")]
+    StopFaresTooLarge,
+    #[error("route stops more than once at {0}")]
+    DuplicateStop(String),
+    #[error("stops cannot change while upcoming trips on the route have tickets or holds")]
+    StopsInUse,
+}
@@ -0,0 +1,108 @@
+mod errors;
+mod route;
+
+pub use errors::RouteError;
+pub use route::{RouteServiceImpl, itinerary};
+
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, FromQueryResult};
+
+use crate::entities::route::Model as RouteModel;
+
+/// Fields an admin sets when creating or editing a route. Locations are
+/// looked up in the catalogue by name or alias, and the route keeps their
+/// canonical names.
+#[derive(Debug, Clone)]
+pub struct RouteInput {
+    pub from_location: String,
+    pub to_location: String,
+    pub base_price: i32,
+}
+
+/// An intermediate stop an admin sets on a route, at a catalogue location.
+/// `fare` is the leg from the previous stop.
+#[derive(Debug, Clone)]
+pub struct StopInput {
+    pub location: String,
+    pub offset_minutes: i32,
+    pub fare: i32,
+}
+
+/// A stop of a route as listed by the `route_itinerary` view: the origin is
+/// stop 0 and the destination the last one.
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct RouteStop {
+    pub stop_order: i32,
+    pub location: String,
+    /// Minutes after departure; `None` at the destination, which is reached
+    /// at the trip's end.
+    pub offset_minutes: Option<i32>,
+    /// Fare of the leg from the previous stop.
+    pub fare: i32,
+    pub fare_from_origin: i32,
+}
+
+/// The part of a trip a passenger rides, between two stops of its route.
+/// Unset ends mean the origin and the destination.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Segment {
+    pub from_stop: Option<i32>,
+    pub to_stop: Option<i32>,
+}
+
+impl Segment {
+    /// The boarding and alighting stops in `stops`, or `None` when they are
+    /// not on the route or not in travel order.
+    pub fn resolve<'a>(&self, stops: &'a [RouteStop]) -> Option<(&'a RouteStop, &'a RouteStop)> {
+        let last = stops.last()?.stop_order;
+        let from_stop = self.from_stop.unwrap_or(0);
+        let to_stop = self.to_stop.unwrap_or(last);
+        if from_stop >= to_stop {
+            return None;
+        }
+
+        let find = |stop_order: i32| stops.iter().find(|stop| stop.stop_order == stop_order);
+
+        Some((find(from_stop)?, find(to_stop)?))
+    }
+}
+
+#[async_trait]
+pub trait RouteService: Send + Sync {
+    /// Routes ordered by id; soft-deleted ones only when `include_deleted`.
+    async fn list_routes(&self, db: &DatabaseConnection, include_deleted: bool) -> Result<Vec<RouteModel>, RouteError>;
+
+    /// Finds the route whether or not it is deleted.
+    async fn get_route(&self, db: &DatabaseConnection, route_id: i32) -> Result<RouteModel, RouteError>;
+
+    /// Fails with `UnknownLocation` when a location is not in the catalogue.
+    async fn create_route(&self, db: &DatabaseConnection, input: RouteInput) -> Result<RouteModel, RouteError>;
+
+    /// Fails with `StopFaresExceedPrice` when the new base price leaves
+    /// nothing for the last leg after the stops' fares.
+    async fn update_route(
+        &self,
+        db: &DatabaseConnection,
+        route_id: i32,
+        input: RouteInput,
+    ) -> Result<RouteModel, RouteError>;
+
+    /// Marks the route deleted. Fails with `HasUpcomingTrips` while trips on
+    /// it have not departed, unless `force` is set.
+    async fn delete_route(&self, db: &DatabaseConnection, route_id: i32, force: bool) -> Result<(), RouteError>;
+
+    async fn restore_route(&self, db: &DatabaseConnection, route_id: i32) -> Result<RouteModel, RouteError>;
+
+    /// Every stop of the route, ends included, in travel order.
+    async fn route_stops(&self, db: &DatabaseConnection, route_id: i32) -> Result<Vec<RouteStop>, RouteError>;
+
+    /// Replaces the stops between the route's ends; an empty list makes it
+    /// direct again. Tickets refer to stops by position, so this fails with
+    /// `StopsInUse` while upcoming trips on the route have tickets or holds.
+    async fn set_route_stops(
+        &self,
+        db: &DatabaseConnection,
+        route_id: i32,
+        stops: Vec<StopInput>,
+    ) -> Result<Vec<RouteStop>, RouteError>;
+}
@@ -0,0 +1,328 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr,
+    EntityTrait, FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, Statement,
+    TransactionTrait, sea_query::JoinType,
+};
+
+use crate::entities::location::Model as LocationModel;
+use crate::entities::route::{
+    ActiveModel as RouteActiveModel, Column as RouteColumn, Entity as Route, Model as RouteModel,
+};
+use crate::entities::route_stop::{
+    ActiveModel as RouteStopActiveModel, Column as RouteStopColumn, Entity as RouteStopEntity,
+};
+use crate::entities::seat_hold::{Column as SeatHoldColumn, Entity as SeatHold, Relation as SeatHoldRelation};
+use crate::entities::ticket::{Column as TicketColumn, Entity as Ticket, Relation as TicketRelation};
+use crate::entities::trip::{Column as TripColumn, Entity as Trip};
+use crate::services::location::resolve;
+use crate::services::ticket::SEAT_RELEASING_STATUSES;
+
+use super::{RouteError, RouteInput, RouteStop, StopInput};
+
+/// A stop placed at a catalogue location.
+struct PlacedStop {
+    location_id: i32,
+    location: String,
+    offset_minutes: i32,
+    fare: i32,
+}
+
+pub struct RouteServiceImpl;
+
+impl RouteServiceImpl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Every stop of the route, ends included, in travel order; empty when the
+/// route does not exist.
+pub async fn itinerary<C: ConnectionTrait>(conn: &C, route_id: i32) -> Result<Vec<RouteStop>, DbErr> {
+    RouteStop::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "SELECT stop_order, location, offset_minutes, fare, fare_from_origin FROM route_itinerary \
+         WHERE route_id = $1 ORDER BY stop_order",
+        [route_id.into()],
+    ))
+    .all(conn)
+    .await
+}
+
+/// The catalogue location named `name`, by its name or an alias.
+async fn locate<C: ConnectionTrait>(conn: &C, name: &str) -> Result<LocationModel, RouteError> {
+    resolve(conn, name)
+        .await?
+        .ok_or_else(|| RouteError::UnknownLocation(name.to_string()))
+}
+
+/// The catalogue locations a route described by `input` runs between,
+/// refusing one that ends where it starts.
+async fn locate_ends<C: ConnectionTrait>(
+    conn: &C,
+    input: &RouteInput,
+) -> Result<(LocationModel, LocationModel), RouteError> {
+    let from = locate(conn, &input.from_location).await?;
+    let to = locate(conn, &input.to_location).await?;
+    if from.location_id == to.location_id {
+        return Err(RouteError::DuplicateStop(to.name));
+    }
+
+    Ok((from, to))
+}
+
+async fn place_stops<C: ConnectionTrait>(conn: &C, stops: Vec<StopInput>) -> Result<Vec<PlacedStop>, RouteError> {
+    let mut placed = Vec::with_capacity(stops.len());
+    for stop in stops {
+        let location = locate(conn, &stop.location).await?;
+        placed.push(PlacedStop {
+            location_id: location.location_id,
+            location: location.name,
+            offset_minutes: stop.offset_minutes,
+            fare: stop.fare,
+        });
+    }
+
+    Ok(placed)
+}
+
+/// Refuses a stop list that visits a location twice, ends included, or whose
+/// fares leave nothing for the last leg.
+fn check_stops(route: &RouteModel, stops: &[PlacedStop]) -> Result<(), RouteError> {
+    let mut seen = vec![route.from_location_id, route.to_location_id];
+    for stop in stops {
+        if seen.contains(&stop.location_id) {
+            return Err(RouteError::DuplicateStop(stop.location.clone()));
+        }
+        seen.push(stop.location_id);
+    }
+
+    check_fares(route.base_price, total_stop_fares(stops)?)
+}
+
+/// Sums the stop fares as `i64` so a long list cannot overflow; a total no
+/// fare can hold fails with `StopFaresTooLarge`.
+fn total_stop_fares(stops: &[PlacedStop]) -> Result<i32, RouteError> {
+    let total: i64 = stops.iter().map(|stop| i64::from(stop.fare)).sum();
+
+    i32::try_from(total).map_err(|_| RouteError::StopFaresTooLarge)
+}
+
+fn check_fares(base_price: i32, stop_fares: i32) -> Result<(), RouteError> {
+    if stop_fares > 0 && stop_fares >= base_price {
+        return Err(RouteError::StopFaresExceedPrice(stop_fares));
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl super::RouteService for RouteServiceImpl {
+    async fn list_routes(&self, db: &DatabaseConnection, include_deleted: bool) -> Result<Vec<RouteModel>, RouteError> {
+        let mut query = Route::find();
+        if !include_deleted {
+            query = query.filter(RouteColumn::Deleted.eq(false));
+        }
+
+        Ok(query.order_by_asc(RouteColumn::RouteId).all(db).await?)
+    }
+
+    async fn get_route(&self, db: &DatabaseConnection, route_id: i32) -> Result<RouteModel, RouteError> {
+        Route::find_by_id(route_id)
+            .one(db)
+            .await?
+            .ok_or(RouteError::RouteNotFound)
+    }
+
+    async fn create_route(&self, db: &DatabaseConnection, input: RouteInput) -> Result<RouteModel, RouteError> {
+        let (from, to) = locate_ends(db, &input).await?;
+
+        Ok(RouteActiveModel {
+            from_location: Set(from.name),
+            to_location: Set(to.name),
+            from_location_id: Set(from.location_id),
+            to_location_id: Set(to.location_id),
+            base_price: Set(input.base_price),
+            deleted: Set(false),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?)
+    }
+
+    async fn update_route(
+        &self,
+        db: &DatabaseConnection,
+        route_id: i32,
+        input: RouteInput,
+    ) -> Result<RouteModel, RouteError> {
+        let txn = db.begin().await?;
+
+        let route = Route::find_by_id(route_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(RouteError::RouteNotFound)?;
+        let stops: Vec<PlacedStop> = RouteStopEntity::find()
+            .filter(RouteStopColumn::RouteId.eq(route_id))
+            .order_by_asc(RouteStopColumn::StopOrder)
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|stop| PlacedStop {
+                location_id: stop.location_id,
+                location: stop.location,
+                offset_minutes: stop.offset_minutes,
+                fare: stop.fare,
+            })
+            .collect();
+
+        let (from, to) = locate_ends(&txn, &input).await?;
+        let route = RouteModel {
+            from_location: from.name,
+            to_location: to.name,
+            from_location_id: from.location_id,
+            to_location_id: to.location_id,
+            base_price: input.base_price,
+            ..route
+        };
+        check_stops(&route, &stops)?;
+
+        let route = RouteActiveModel::from(route).reset_all().update(&txn).await?;
+        txn.commit().await?;
+
+        Ok(route)
+    }
+
+    async fn delete_route(&self, db: &DatabaseConnection, route_id: i32, force: bool) -> Result<(), RouteError> {
+        let txn = db.begin().await?;
+
+        let route = Route::find_by_id(route_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(RouteError::RouteNotFound)?;
+
+        if !force {
+            let upcoming = Trip::find()
+                .filter(TripColumn::RouteId.eq(route_id))
+                .filter(TripColumn::StartDate.gt(Utc::now().naive_utc()))
+                .count(&txn)
+                .await?;
+            if upcoming > 0 {
+                return Err(RouteError::HasUpcomingTrips(upcoming));
+            }
+        }
+
+        let mut active: RouteActiveModel = route.into();
+        active.deleted = Set(true);
+        active.update(&txn).await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    async fn restore_route(&self, db: &DatabaseConnection, route_id: i32) -> Result<RouteModel, RouteError> {
+        let route = self.get_route(db, route_id).await?;
+
+        let mut active: RouteActiveModel = route.into();
+        active.deleted = Set(false);
+
+        Ok(active.update(db).await?)
+    }
+
+    async fn route_stops(&self, db: &DatabaseConnection, route_id: i32) -> Result<Vec<RouteStop>, RouteError> {
+        self.get_route(db, route_id).await?;
+
+        Ok(itinerary(db, route_id).await?)
+    }
+
+    async fn set_route_stops(
+        &self,
+        db: &DatabaseConnection,
+        route_id: i32,
+        stops: Vec<StopInput>,
+    ) -> Result<Vec<RouteStop>, RouteError> {
+        let txn = db.begin().await?;
+        let now = Utc::now().naive_utc();
+
+        let route = Route::find_by_id(route_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(RouteError::RouteNotFound)?;
+        let stops = place_stops(&txn, stops).await?;
+        check_stops(&route, &stops)?;
+
+        let tickets = Ticket::find()
+            .join(JoinType::InnerJoin, TicketRelation::Trip.def())
+            .filter(TripColumn::RouteId.eq(route_id))
+            .filter(TripColumn::Cancelled.eq(false))
+            .filter(TripColumn::StartDate.gt(now))
+            .filter(TicketColumn::Status.is_not_in(SEAT_RELEASING_STATUSES))
+            .count(&txn)
+            .await?;
+        let holds = SeatHold::find()
+            .join(JoinType::InnerJoin, SeatHoldRelation::Trip.def())
+            .filter(TripColumn::RouteId.eq(route_id))
+            .filter(TripColumn::Cancelled.eq(false))
+            .filter(TripColumn::StartDate.gt(now))
+            .filter(SeatHoldColumn::ExpiresAt.gt(now))
+            .count(&txn)
+            .await?;
+        if tickets > 0 || holds > 0 {
+            return Err(RouteError::StopsInUse);
+        }
+
+        RouteStopEntity::delete_many()
+            .filter(RouteStopColumn::RouteId.eq(route_id))
+            .exec(&txn)
+            .await?;
+        if !stops.is_empty() {
+            RouteStopEntity::insert_many(stops.into_iter().zip(1..).map(|(stop, stop_order)| RouteStopActiveModel {
+                route_id: Set(route_id),
+                stop_order: Set(stop_order),
+                location: Set(stop.location),
+                location_id: Set(stop.location_id),
+                offset_minutes: Set(stop.offset_minutes),
+                fare: Set(stop.fare),
+            }))
+            .exec(&txn)
+            .await?;
+        }
+
+        let stops = itinerary(&txn, route_id).await?;
+        txn.commit().await?;
+
+        Ok(stops)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stop(fare: i32) -> PlacedStop {
+        PlacedStop {
+            location_id: fare,
+            location: format!("stop {fare}"),
+            offset_minutes: 60,
+            fare,
+        }
+    }
+
+    #[test]
+    fn stop_fares_add_up() {
+        assert_eq!(total_stop_fares(&[]).unwrap(), 0);
+        assert_eq!(total_stop_fares(&[stop(100), stop(250)]).unwrap(), 350);
+        assert_eq!(total_stop_fares(&[stop(i32::MAX - 1), stop(1)]).unwrap(), i32::MAX);
+    }
+
+    #[test]
+    fn stop_fares_beyond_a_fare_are_refused() {
+        let stops = [stop(i32::MAX), stop(1)];
+
+        assert!(matches!(total_stop_fares(&stops), Err(RouteError::StopFaresTooLarge)));
+    }
+}
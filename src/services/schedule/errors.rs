@@ -0,0 +1,18 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ScheduleError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("schedule not found")]
+    ScheduleNotFound,
+    #[error("route not found")]
+    RouteNotFound,
+    #[error("bus not found")]
+    BusNotFound,
+    #[error("route is deleted")]
+    RouteDeleted,
+    #[error("bus is deleted")]
+    BusDeleted,
+}
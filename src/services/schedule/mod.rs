@@ -0,0 +1,89 @@
+mod errors;
+mod schedule;
+
+pub use errors::ScheduleError;
+pub use schedule::ScheduleServiceImpl;
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use sea_orm::DatabaseConnection;
+
+use crate::entities::schedule::Model as ScheduleModel;
+use crate::entities::trip::Model as TripModel;
+
+/// A recurring timetable. `days_of_week` is a bit mask from Monday (1) to
+/// Sunday (64); the schedule runs on those days in `valid_from..=valid_until`
+/// except on `exceptions`.
+#[derive(Debug, Clone)]
+pub struct ScheduleInput {
+    pub route_id: i32,
+    pub bus_id: i32,
+    pub departure_time: NaiveTime,
+    pub duration_minutes: i32,
+    pub days_of_week: i32,
+    pub valid_from: NaiveDate,
+    pub valid_until: Option<NaiveDate>,
+    pub exceptions: Vec<NaiveDate>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduleDetails {
+    pub schedule: ScheduleModel,
+    pub exceptions: Vec<NaiveDate>,
+}
+
+/// A departure a schedule would add. `conflicts` lists the trips already
+/// keeping the bus busy at that time; it is only added when empty.
+#[derive(Debug, Clone)]
+pub struct PlannedDeparture {
+    pub start_date: NaiveDateTime,
+    pub end_date: NaiveDateTime,
+    pub conflicts: Vec<i32>,
+}
+
+/// A departure the generator skipped because the bus was busy.
+#[derive(Debug, Clone)]
+pub struct GenerationConflict {
+    pub schedule_id: i32,
+    pub start_date: NaiveDateTime,
+    pub trip_ids: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GenerationReport {
+    pub created: Vec<TripModel>,
+    pub conflicts: Vec<GenerationConflict>,
+}
+
+#[async_trait]
+pub trait ScheduleService: Send + Sync {
+    async fn list_schedules(&self, db: &DatabaseConnection) -> Result<Vec<ScheduleDetails>, ScheduleError>;
+
+    /// Saves the schedule; its trips appear on the next generator run.
+    async fn create_schedule(
+        &self,
+        db: &DatabaseConnection,
+        input: ScheduleInput,
+    ) -> Result<ScheduleDetails, ScheduleError>;
+
+    /// Stops generating trips for the schedule. Trips already generated stay.
+    async fn deactivate_schedule(&self, db: &DatabaseConnection, schedule_id: i32) -> Result<(), ScheduleError>;
+
+    /// The departures `input` would generate over the next `days` days, or
+    /// up to the horizon, without saving anything.
+    async fn preview(
+        &self,
+        db: &DatabaseConnection,
+        input: &ScheduleInput,
+        days: Option<u32>,
+    ) -> Result<Vec<PlannedDeparture>, ScheduleError>;
+
+    /// Adds the missing trips of one active schedule, or of all of them, up
+    /// to the horizon. Departures that already have a trip are skipped, so
+    /// running it again adds nothing new.
+    async fn generate(
+        &self,
+        db: &DatabaseConnection,
+        schedule_id: Option<i32>,
+    ) -> Result<GenerationReport, ScheduleError>;
+}
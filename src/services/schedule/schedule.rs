@@ -0,0 +1,310 @@
+use async_trait::async_trait;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+
+use crate::entities::bus::Entity as Bus;
+use crate::entities::route::Entity as Route;
+use crate::entities::schedule::{
+    ActiveModel as ScheduleActiveModel, Column as ScheduleColumn, Entity as Schedule, Model as ScheduleModel,
+};
+use crate::entities::schedule_exception::{
+    ActiveModel as ScheduleExceptionActiveModel, Column as ScheduleExceptionColumn, Entity as ScheduleException,
+};
+use crate::entities::trip::{ActiveModel as TripActiveModel, Column as TripColumn, Entity as Trip};
+
+use super::{
+    GenerationConflict, GenerationReport, PlannedDeparture, ScheduleDetails, ScheduleError, ScheduleInput,
+};
+
+pub struct ScheduleServiceImpl {
+    horizon_days: u32,
+}
+
+impl ScheduleServiceImpl {
+    pub fn new(horizon_days: u32) -> Self {
+        Self { horizon_days }
+    }
+}
+
+/// Departures of the schedule that start after `now` and fall on days in
+/// `from..=until`.
+fn departures(
+    input: &ScheduleInput,
+    from: NaiveDate,
+    until: NaiveDate,
+    now: NaiveDateTime,
+) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let first = from.max(input.valid_from);
+    let last = input.valid_until.map_or(until, |valid_until| until.min(valid_until));
+    let duration = Duration::minutes(input.duration_minutes.into());
+
+    first
+        .iter_days()
+        .take_while(|day| *day <= last)
+        .filter(|day| input.days_of_week & (1 << day.weekday().num_days_from_monday()) != 0)
+        .filter(|day| !input.exceptions.contains(day))
+        .map(|day| day.and_time(input.departure_time))
+        .filter(|start| *start > now)
+        .map(|start| (start, start + duration))
+        .collect()
+}
+
+fn input_of(details: &ScheduleDetails) -> ScheduleInput {
+    let schedule = &details.schedule;
+
+    ScheduleInput {
+        route_id: schedule.route_id,
+        bus_id: schedule.bus_id,
+        departure_time: schedule.departure_time,
+        duration_minutes: schedule.duration_minutes,
+        days_of_week: schedule.days_of_week,
+        valid_from: schedule.valid_from,
+        valid_until: schedule.valid_until,
+        exceptions: details.exceptions.clone(),
+    }
+}
+
+/// Checks the route and bus exist and are not deleted. With `lock` the bus
+/// row is locked so trips for it are scheduled one at a time.
+async fn check_route_and_bus<C: ConnectionTrait>(
+    conn: &C,
+    route_id: i32,
+    bus_id: i32,
+    lock: bool,
+) -> Result<(), ScheduleError> {
+    let route = Route::find_by_id(route_id)
+        .one(conn)
+        .await?
+        .ok_or(ScheduleError::RouteNotFound)?;
+    if route.deleted {
+        return Err(ScheduleError::RouteDeleted);
+    }
+
+    let mut query = Bus::find_by_id(bus_id);
+    if lock {
+        query = query.lock_exclusive();
+    }
+    let bus = query.one(conn).await?.ok_or(ScheduleError::BusNotFound)?;
+    if bus.deleted {
+        return Err(ScheduleError::BusDeleted);
+    }
+
+    Ok(())
+}
+
+/// Trips that are not cancelled and keep the bus busy during the window.
+async fn busy_trips<C: ConnectionTrait>(
+    conn: &C,
+    bus_id: i32,
+    start_date: NaiveDateTime,
+    end_date: NaiveDateTime,
+) -> Result<Vec<i32>, ScheduleError> {
+    Ok(Trip::find()
+        .select_only()
+        .column(TripColumn::TripId)
+        .filter(TripColumn::BusId.eq(bus_id))
+        .filter(TripColumn::Cancelled.eq(false))
+        .filter(TripColumn::StartDate.lt(end_date))
+        .filter(TripColumn::EndDate.gt(start_date))
+        .order_by_asc(TripColumn::TripId)
+        .into_tuple()
+        .all(conn)
+        .await?)
+}
+
+async fn exceptions_of<C: ConnectionTrait>(conn: &C, schedule_id: i32) -> Result<Vec<NaiveDate>, ScheduleError> {
+    Ok(ScheduleException::find()
+        .select_only()
+        .column(ScheduleExceptionColumn::ServiceDate)
+        .filter(ScheduleExceptionColumn::ScheduleId.eq(schedule_id))
+        .order_by_asc(ScheduleExceptionColumn::ServiceDate)
+        .into_tuple()
+        .all(conn)
+        .await?)
+}
+
+impl ScheduleServiceImpl {
+    /// Days covered by the generator, starting today.
+    fn window(&self, days: u32) -> (NaiveDate, NaiveDate, NaiveDateTime) {
+        let now = Utc::now().naive_utc();
+        let today = now.date();
+
+        (today, today + Duration::days(days.into()), now)
+    }
+
+    /// Generates the trips of one schedule in its own transaction. Schedules
+    /// whose route or bus has been deleted generate nothing.
+    async fn generate_schedule(
+        &self,
+        db: &DatabaseConnection,
+        schedule: ScheduleModel,
+        report: &mut GenerationReport,
+    ) -> Result<(), ScheduleError> {
+        let txn = db.begin().await?;
+
+        match check_route_and_bus(&txn, schedule.route_id, schedule.bus_id, true).await {
+            Ok(()) => {}
+            Err(ScheduleError::Database(err)) => return Err(err.into()),
+            Err(_) => return Ok(()),
+        }
+
+        let schedule_id = schedule.schedule_id;
+        let exceptions = exceptions_of(&txn, schedule_id).await?;
+        let details = ScheduleDetails { schedule, exceptions };
+        let (from, until, now) = self.window(self.horizon_days);
+
+        for (start_date, end_date) in departures(&input_of(&details), from, until, now) {
+            let existing = Trip::find()
+                .filter(TripColumn::ScheduleId.eq(schedule_id))
+                .filter(TripColumn::StartDate.eq(start_date))
+                .one(&txn)
+                .await?;
+            if existing.is_some() {
+                continue;
+            }
+
+            let trip_ids = busy_trips(&txn, details.schedule.bus_id, start_date, end_date).await?;
+            if !trip_ids.is_empty() {
+                report.conflicts.push(GenerationConflict { schedule_id, start_date, trip_ids });
+                continue;
+            }
+
+            let trip = TripActiveModel {
+                route_id: Set(details.schedule.route_id),
+                bus_id: Set(details.schedule.bus_id),
+                start_date: Set(start_date),
+                end_date: Set(end_date),
+                cancelled: Set(false),
+                schedule_id: Set(Some(schedule_id)),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+            report.created.push(trip);
+        }
+
+        txn.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl super::ScheduleService for ScheduleServiceImpl {
+    async fn list_schedules(&self, db: &DatabaseConnection) -> Result<Vec<ScheduleDetails>, ScheduleError> {
+        let schedules = Schedule::find()
+            .find_with_related(ScheduleException)
+            .order_by_asc(ScheduleColumn::ScheduleId)
+            .all(db)
+            .await?;
+
+        Ok(schedules
+            .into_iter()
+            .map(|(schedule, exceptions)| {
+                let mut exceptions: Vec<NaiveDate> =
+                    exceptions.into_iter().map(|exception| exception.service_date).collect();
+                exceptions.sort();
+                ScheduleDetails { schedule, exceptions }
+            })
+            .collect())
+    }
+
+    async fn create_schedule(
+        &self,
+        db: &DatabaseConnection,
+        input: ScheduleInput,
+    ) -> Result<ScheduleDetails, ScheduleError> {
+        let txn = db.begin().await?;
+
+        check_route_and_bus(&txn, input.route_id, input.bus_id, false).await?;
+        let schedule = ScheduleActiveModel {
+            route_id: Set(input.route_id),
+            bus_id: Set(input.bus_id),
+            departure_time: Set(input.departure_time),
+            duration_minutes: Set(input.duration_minutes),
+            days_of_week: Set(input.days_of_week),
+            valid_from: Set(input.valid_from),
+            valid_until: Set(input.valid_until),
+            active: Set(true),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        let mut exceptions = input.exceptions;
+        exceptions.sort();
+        exceptions.dedup();
+        if !exceptions.is_empty() {
+            ScheduleException::insert_many(exceptions.iter().map(|service_date| ScheduleExceptionActiveModel {
+                schedule_id: Set(schedule.schedule_id),
+                service_date: Set(*service_date),
+            }))
+            .exec(&txn)
+            .await?;
+        }
+        txn.commit().await?;
+
+        Ok(ScheduleDetails { schedule, exceptions })
+    }
+
+    async fn deactivate_schedule(&self, db: &DatabaseConnection, schedule_id: i32) -> Result<(), ScheduleError> {
+        let schedule = Schedule::find_by_id(schedule_id)
+            .one(db)
+            .await?
+            .ok_or(ScheduleError::ScheduleNotFound)?;
+
+        let mut active: ScheduleActiveModel = schedule.into();
+        active.active = Set(false);
+        active.update(db).await?;
+
+        Ok(())
+    }
+
+    async fn preview(
+        &self,
+        db: &DatabaseConnection,
+        input: &ScheduleInput,
+        days: Option<u32>,
+    ) -> Result<Vec<PlannedDeparture>, ScheduleError> {
+        check_route_and_bus(db, input.route_id, input.bus_id, false).await?;
+
+        let (from, until, now) = self.window(days.unwrap_or(self.horizon_days));
+        let mut planned = Vec::new();
+        for (start_date, end_date) in departures(input, from, until, now) {
+            planned.push(PlannedDeparture {
+                start_date,
+                end_date,
+                conflicts: busy_trips(db, input.bus_id, start_date, end_date).await?,
+            });
+        }
+
+        Ok(planned)
+    }
+
+    async fn generate(
+        &self,
+        db: &DatabaseConnection,
+        schedule_id: Option<i32>,
+    ) -> Result<GenerationReport, ScheduleError> {
+        let mut query = Schedule::find().filter(ScheduleColumn::Active.eq(true));
+        if let Some(schedule_id) = schedule_id {
+            Schedule::find_by_id(schedule_id)
+                .one(db)
+                .await?
+                .ok_or(ScheduleError::ScheduleNotFound)?;
+            query = query.filter(ScheduleColumn::ScheduleId.eq(schedule_id));
+        }
+        let schedules = query.order_by_asc(ScheduleColumn::ScheduleId).all(db).await?;
+
+        let mut report = GenerationReport::default();
+        for schedule in schedules {
+            self.generate_schedule(db, schedule, &mut report).await?;
+        }
+
+        Ok(report)
+    }
+}
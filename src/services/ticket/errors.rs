@@ -0,0 +1,29 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+use crate::entities::sea_orm_active_enums::TicketStatus;
+use crate::services::payment::PaymentError;
+
+use super::status_name;
+
+#[derive(Debug, Error)]
+pub enum TicketError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("ticket not found")]
+    TicketNotFound,
+    #[error("ticket belongs to another user")]
+    NotTicketOwner,
+    #[error("trip has already departed")]
+    TripDeparted,
+    #[error("route not found")]
+    RouteNotFound,
+    #[error("ticket cannot go from {} to {}", status_name(*.from), status_name(*.to))]
+    IllegalTransition { from: TicketStatus, to: TicketStatus },
+    #[error("tickets are {} only through the cancellation policy", status_name(*.0))]
+    CancelRequired(TicketStatus),
+    #[error("refund amount is too large")]
+    AmountTooLarge,
+    #[error(transparent)]
+    Payment(#[from] PaymentError),
+}
@@ -0,0 +1,245 @@
+mod errors;
+mod ticket;
+
+pub use errors::TicketError;
+pub use ticket::TicketServiceImpl;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ConnectionTrait, DatabaseConnection, DbErr};
+
+use crate::entities::sea_orm_active_enums::TicketStatus;
+use crate::entities::ticket::Model as TicketModel;
+use crate::entities::ticket_status_history::{
+    ActiveModel as TicketStatusHistoryActiveModel, Model as TicketStatusHistoryModel,
+};
+
+/// A ticket together with the name of its seat.
+#[derive(Debug, Clone)]
+pub struct TicketDetails {
+    pub ticket: TicketModel,
+    pub seat_name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketFilter {
+    All,
+    /// Live tickets whose trip has not departed.
+    Upcoming,
+    /// Live tickets whose trip has departed.
+    Past,
+    /// Cancelled and refunded tickets.
+    Cancelled,
+}
+
+/// A ticket with the trip, route and bus it is for.
+#[derive(Debug, Clone)]
+pub struct UserTicket {
+    pub details: TicketDetails,
+    pub route_id: i32,
+    pub route_from_location: String,
+    pub route_to_location: String,
+    pub departure: NaiveDateTime,
+    pub arrival: NaiveDateTime,
+    pub bus_id: i32,
+    pub bus_name: Option<String>,
+    pub license_plate: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct TicketPage {
+    pub tickets: Vec<UserTicket>,
+    pub total: u64,
+}
+
+/// While at least `min_hours_before` hours remain before departure, a
+/// cancellation refunds `refund_percent` of the fare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefundTier {
+    pub min_hours_before: i32,
+    pub refund_percent: i32,
+}
+
+/// Statuses whose ticket no longer occupies its seat.
+pub const SEAT_RELEASING_STATUSES: [TicketStatus; 2] = [TicketStatus::Cancelled, TicketStatus::Refunded];
+
+/// Every move a ticket may make. Checked-in, cancelled, refunded and no-show
+/// tickets are final; a paid ticket is refunded rather than cancelled when
+/// money goes back.
+const TRANSITIONS: &[(TicketStatus, TicketStatus)] = &[
+    (TicketStatus::Held, TicketStatus::Booked),
+    (TicketStatus::Held, TicketStatus::Cancelled),
+    (TicketStatus::Booked, TicketStatus::Paid),
+    (TicketStatus::Booked, TicketStatus::Cancelled),
+    (TicketStatus::Booked, TicketStatus::NoShow),
+    (TicketStatus::Paid, TicketStatus::CheckedIn),
+    (TicketStatus::Paid, TicketStatus::Cancelled),
+    (TicketStatus::Paid, TicketStatus::Refunded),
+    (TicketStatus::Paid, TicketStatus::NoShow),
+];
+
+pub fn can_transition(from: TicketStatus, to: TicketStatus) -> bool {
+    TRANSITIONS.contains(&(from, to))
+}
+
+/// The name of a status in the API.
+pub fn status_name(status: TicketStatus) -> &'static str {
+    match status {
+        TicketStatus::Held => "held",
+        TicketStatus::Booked => "booked",
+        TicketStatus::Paid => "paid",
+        TicketStatus::CheckedIn => "checked_in",
+        TicketStatus::Cancelled => "cancelled",
+        TicketStatus::Refunded => "refunded",
+        TicketStatus::NoShow => "no_show",
+    }
+}
+
+pub fn parse_status(name: &str) -> Option<TicketStatus> {
+    match name {
+        "held" => Some(TicketStatus::Held),
+        "booked" => Some(TicketStatus::Booked),
+        "paid" => Some(TicketStatus::Paid),
+        "checked_in" => Some(TicketStatus::CheckedIn),
+        "cancelled" => Some(TicketStatus::Cancelled),
+        "refunded" => Some(TicketStatus::Refunded),
+        "no_show" => Some(TicketStatus::NoShow),
+        _ => None,
+    }
+}
+
+/// Appends a history row; `from` is `None` for a new ticket and `actor_id`
+/// for changes the system makes on its own. Run it in the transaction that
+/// changes the status.
+pub async fn record_transition<C: ConnectionTrait>(
+    conn: &C,
+    ticket_id: i32,
+    from: Option<TicketStatus>,
+    to: TicketStatus,
+    actor_id: Option<i32>,
+    now: NaiveDateTime,
+) -> Result<(), DbErr> {
+    TicketStatusHistoryActiveModel {
+        ticket_id: Set(ticket_id),
+        from_status: Set(from),
+        to_status: Set(to),
+        actor_id: Set(actor_id),
+        changed_at: Set(now),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    Ok(())
+}
+
+#[async_trait]
+pub trait TicketService: Send + Sync {
+    /// Moves the ticket to `to` if the transition table allows it, recording
+    /// who did it. Cancelled and refunded are refused; those go through
+    /// `cancel` so the refund policy always applies.
+    async fn transition(
+        &self,
+        db: &DatabaseConnection,
+        ticket_id: i32,
+        to: TicketStatus,
+        actor_id: Option<i32>,
+    ) -> Result<TicketDetails, TicketError>;
+
+    /// Cancels the ticket for its owner, or anyone when `staff` is set, as
+    /// long as the trip has not departed. A paid ticket is refunded by the
+    /// route's refund tiers and ends `Refunded`, or `Cancelled` when nothing
+    /// is refunded; `refund_amount` records the amount either way.
+    async fn cancel(
+        &self,
+        db: &DatabaseConnection,
+        ticket_id: i32,
+        actor_id: i32,
+        staff: bool,
+    ) -> Result<TicketDetails, TicketError>;
+
+    /// The route's refund tiers, highest `min_hours_before` first.
+    async fn refund_policy(&self, db: &DatabaseConnection, route_id: i32) -> Result<Vec<RefundTier>, TicketError>;
+
+    /// Replaces the route's refund tiers.
+    async fn set_refund_policy(
+        &self,
+        db: &DatabaseConnection,
+        route_id: i32,
+        tiers: &[RefundTier],
+    ) -> Result<Vec<RefundTier>, TicketError>;
+
+    /// One page of the user's tickets; upcoming ones soonest first, all
+    /// others most recent departure first. `page` is 1-based.
+    async fn list_for_user(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        filter: TicketFilter,
+        page: u64,
+        page_size: u64,
+    ) -> Result<TicketPage, TicketError>;
+
+    /// The status changes of a ticket, oldest first.
+    async fn history(
+        &self,
+        db: &DatabaseConnection,
+        ticket_id: i32,
+    ) -> Result<Vec<TicketStatusHistoryModel>, TicketError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::Iterable;
+
+    use super::*;
+
+    #[test]
+    fn allows_the_documented_moves() {
+        assert!(can_transition(TicketStatus::Held, TicketStatus::Booked));
+        assert!(can_transition(TicketStatus::Booked, TicketStatus::Paid));
+        assert!(can_transition(TicketStatus::Booked, TicketStatus::Cancelled));
+        assert!(can_transition(TicketStatus::Paid, TicketStatus::CheckedIn));
+        assert!(can_transition(TicketStatus::Paid, TicketStatus::Refunded));
+        assert!(can_transition(TicketStatus::Paid, TicketStatus::NoShow));
+    }
+
+    #[test]
+    fn refuses_moves_backwards_or_skipping_payment() {
+        assert!(!can_transition(TicketStatus::Paid, TicketStatus::Booked));
+        assert!(!can_transition(TicketStatus::Booked, TicketStatus::Held));
+        assert!(!can_transition(TicketStatus::Booked, TicketStatus::CheckedIn));
+        assert!(!can_transition(TicketStatus::Booked, TicketStatus::Refunded));
+        assert!(!can_transition(TicketStatus::Held, TicketStatus::Paid));
+    }
+
+    #[test]
+    fn final_statuses_cannot_move() {
+        let finals = [
+            TicketStatus::CheckedIn,
+            TicketStatus::Cancelled,
+            TicketStatus::Refunded,
+            TicketStatus::NoShow,
+        ];
+        for from in finals {
+            for to in TicketStatus::iter() {
+                assert!(!can_transition(from, to), "{from:?} -> {to:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn no_status_moves_to_itself() {
+        for status in TicketStatus::iter() {
+            assert!(!can_transition(status, status), "{status:?}");
+        }
+    }
+
+    #[test]
+    fn status_names_round_trip() {
+        for status in TicketStatus::iter() {
+            assert_eq!(parse_status(status_name(status)), Some(status));
+        }
+        assert_eq!(parse_status("unknown"), None);
+    }
+}
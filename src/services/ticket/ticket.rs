@@ -0,0 +1,395 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend,
+    EntityTrait, FromQueryResult, QueryFilter, QueryOrder, QuerySelect, QueryResult, Statement, TransactionTrait,
+};
+
+use crate::entities::route::Entity as Route;
+use crate::entities::route_refund_tier::{
+    ActiveModel as RouteRefundTierActiveModel, Column as RouteRefundTierColumn, Entity as RouteRefundTier,
+};
+use crate::entities::sea_orm_active_enums::TicketStatus;
+use crate::entities::seat::Entity as Seat;
+use crate::entities::ticket::{ActiveModel as TicketActiveModel, Entity as Ticket, Model as TicketModel};
+use crate::entities::ticket_status_history::{
+    Column as TicketStatusHistoryColumn, Entity as TicketStatusHistory, Model as TicketStatusHistoryModel,
+};
+use crate::entities::trip::Entity as Trip;
+use crate::services::payment::PaymentService;
+
+use super::{
+    RefundTier, SEAT_RELEASING_STATUSES, TicketDetails, TicketError, TicketFilter, TicketPage, UserTicket,
+    can_transition, record_transition,
+};
+
+/// A user's tickets with their trip, route, seat and bus. $1 user_id,
+/// $2 now, $3 and $4 the statuses that release a seat; the filter goes last.
+const USER_TICKETS_QUERY: &str = r#"
+    SELECT k.*, s.name AS seat_name, t.route_id, t.start_date, t.end_date, t.bus_id,
+           r.from_location AS route_from_location, r.to_location AS route_to_location,
+           b.name AS bus_name, b.license_plate
+    FROM ticket k
+    JOIN seat s ON s.seat_id = k.seat_id
+    JOIN trip t ON t.trip_id = k.trip_id
+    JOIN route r ON r.route_id = t.route_id
+    JOIN bus b ON b.bus_id = t.bus_id
+    WHERE k.user_id = $1
+"#;
+
+pub struct TicketServiceImpl {
+    payment_service: Arc<dyn PaymentService>,
+}
+
+impl TicketServiceImpl {
+    pub fn new(payment_service: Arc<dyn PaymentService>) -> Self {
+        Self { payment_service }
+    }
+}
+
+async fn refund_tiers<C: ConnectionTrait>(conn: &C, route_id: i32) -> Result<Vec<RefundTier>, TicketError> {
+    Ok(RouteRefundTier::find()
+        .filter(RouteRefundTierColumn::RouteId.eq(route_id))
+        .order_by_desc(RouteRefundTierColumn::MinHoursBefore)
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|tier| RefundTier {
+            min_hours_before: tier.min_hours_before,
+            refund_percent: tier.refund_percent,
+        })
+        .collect())
+}
+
+/// The percentage of the tier with the highest `min_hours_before` that the
+/// time left before departure still reaches, or 0.
+fn refund_percent(tiers: &[RefundTier], departure: NaiveDateTime, now: NaiveDateTime) -> i32 {
+    let seconds_left = (departure - now).num_seconds();
+
+    tiers
+        .iter()
+        .filter(|tier| seconds_left >= i64::from(tier.min_hours_before) * 3600)
+        .max_by_key(|tier| tier.min_hours_before)
+        .map_or(0, |tier| tier.refund_percent)
+}
+
+/// `percent` of `price`, computed as `i64` so large fares cannot overflow and
+/// narrowed back like the pricing engine's amounts.
+fn refund_amount(price: i32, percent: i32) -> Result<i32, TicketError> {
+    i32::try_from(i64::from(price) * i64::from(percent) / 100).map_err(|_| TicketError::AmountTooLarge)
+}
+
+fn filter_clause(filter: TicketFilter) -> (&'static str, &'static str) {
+    match filter {
+        TicketFilter::All => ("", "t.start_date DESC, k.ticket_id DESC"),
+        TicketFilter::Upcoming => (
+            "AND k.status NOT IN ($3, $4) AND t.start_date > $2",
+            "t.start_date ASC, k.ticket_id ASC",
+        ),
+        TicketFilter::Past => (
+            "AND k.status NOT IN ($3, $4) AND t.start_date <= $2",
+            "t.start_date DESC, k.ticket_id DESC",
+        ),
+        TicketFilter::Cancelled => ("AND k.status IN ($3, $4)", "t.start_date DESC, k.ticket_id DESC"),
+    }
+}
+
+fn user_ticket(row: &QueryResult) -> Result<UserTicket, TicketError> {
+    Ok(UserTicket {
+        details: TicketDetails {
+            ticket: TicketModel::from_query_result(row, "")?,
+            seat_name: row.try_get("", "seat_name")?,
+        },
+        route_id: row.try_get("", "route_id")?,
+        route_from_location: row.try_get("", "route_from_location")?,
+        route_to_location: row.try_get("", "route_to_location")?,
+        departure: row.try_get("", "start_date")?,
+        arrival: row.try_get("", "end_date")?,
+        bus_id: row.try_get("", "bus_id")?,
+        bus_name: row.try_get("", "bus_name")?,
+        license_plate: row.try_get("", "license_plate")?,
+    })
+}
+
+/// Changes the status of a locked ticket and records the change.
+async fn apply_transition<C: ConnectionTrait>(
+    conn: &C,
+    ticket: TicketModel,
+    to: TicketStatus,
+    refund_amount: Option<i32>,
+    actor_id: Option<i32>,
+) -> Result<TicketDetails, TicketError> {
+    let from = ticket.status;
+    if !can_transition(from, to) {
+        return Err(TicketError::IllegalTransition { from, to });
+    }
+
+    let ticket_id = ticket.ticket_id;
+    let mut active: TicketActiveModel = ticket.into();
+    active.status = Set(to);
+    if refund_amount.is_some() {
+        active.refund_amount = Set(refund_amount);
+    }
+    let ticket = active.update(conn).await?;
+
+    record_transition(conn, ticket_id, Some(from), to, actor_id, Utc::now().naive_utc()).await?;
+    let seat_name = Seat::find_by_id(ticket.seat_id)
+        .one(conn)
+        .await?
+        .map(|seat| seat.name)
+        .unwrap_or_default();
+
+    Ok(TicketDetails { ticket, seat_name })
+}
+
+#[async_trait]
+impl super::TicketService for TicketServiceImpl {
+    async fn transition(
+        &self,
+        db: &DatabaseConnection,
+        ticket_id: i32,
+        to: TicketStatus,
+        actor_id: Option<i32>,
+    ) -> Result<TicketDetails, TicketError> {
+        if SEAT_RELEASING_STATUSES.contains(&to) {
+            return Err(TicketError::CancelRequired(to));
+        }
+
+        let txn = db.begin().await?;
+
+        let ticket = Ticket::find_by_id(ticket_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(TicketError::TicketNotFound)?;
+
+        let details = apply_transition(&txn, ticket, to, None, actor_id).await?;
+        txn.commit().await?;
+
+        Ok(details)
+    }
+
+    async fn cancel(
+        &self,
+        db: &DatabaseConnection,
+        ticket_id: i32,
+        actor_id: i32,
+        staff: bool,
+    ) -> Result<TicketDetails, TicketError> {
+        let txn = db.begin().await?;
+        let now = Utc::now().naive_utc();
+
+        let ticket = Ticket::find_by_id(ticket_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(TicketError::TicketNotFound)?;
+        if !staff && ticket.user_id != actor_id {
+            return Err(TicketError::NotTicketOwner);
+        }
+
+        let trip = Trip::find_by_id(ticket.trip_id)
+            .one(&txn)
+            .await?
+            .ok_or(TicketError::TicketNotFound)?;
+        if trip.start_date <= now {
+            return Err(TicketError::TripDeparted);
+        }
+
+        // Only a paid ticket has money to give back.
+        let refund_amount = if ticket.status == TicketStatus::Paid {
+            let tiers = refund_tiers(&txn, trip.route_id).await?;
+            refund_amount(ticket.price, refund_percent(&tiers, trip.start_date, now))?
+        } else {
+            0
+        };
+        let to = if refund_amount > 0 { TicketStatus::Refunded } else { TicketStatus::Cancelled };
+
+        let details = apply_transition(&txn, ticket, to, Some(refund_amount), Some(actor_id)).await?;
+        self.payment_service.refund_ticket(&txn, ticket_id, refund_amount).await?;
+        txn.commit().await?;
+
+        Ok(details)
+    }
+
+    async fn refund_policy(&self, db: &DatabaseConnection, route_id: i32) -> Result<Vec<RefundTier>, TicketError> {
+        Route::find_by_id(route_id)
+            .one(db)
+            .await?
+            .ok_or(TicketError::RouteNotFound)?;
+
+        refund_tiers(db, route_id).await
+    }
+
+    async fn set_refund_policy(
+        &self,
+        db: &DatabaseConnection,
+        route_id: i32,
+        tiers: &[RefundTier],
+    ) -> Result<Vec<RefundTier>, TicketError> {
+        let txn = db.begin().await?;
+
+        Route::find_by_id(route_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(TicketError::RouteNotFound)?;
+
+        RouteRefundTier::delete_many()
+            .filter(RouteRefundTierColumn::RouteId.eq(route_id))
+            .exec(&txn)
+            .await?;
+
+        if !tiers.is_empty() {
+            RouteRefundTier::insert_many(tiers.iter().map(|tier| RouteRefundTierActiveModel {
+                route_id: Set(route_id),
+                min_hours_before: Set(tier.min_hours_before),
+                refund_percent: Set(tier.refund_percent),
+            }))
+            .exec(&txn)
+            .await?;
+        }
+
+        let tiers = refund_tiers(&txn, route_id).await?;
+        txn.commit().await?;
+
+        Ok(tiers)
+    }
+
+    async fn list_for_user(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i32,
+        filter: TicketFilter,
+        page: u64,
+        page_size: u64,
+    ) -> Result<TicketPage, TicketError> {
+        let (condition, order) = filter_clause(filter);
+        let [released_a, released_b] = SEAT_RELEASING_STATUSES;
+        let values = |extra: Vec<sea_orm::Value>| {
+            let mut values = vec![
+                user_id.into(),
+                Utc::now().naive_utc().into(),
+                released_a.into_value().into(),
+                released_b.into_value().into(),
+            ];
+            values.extend(extra);
+            values
+        };
+
+        let total = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!("SELECT COUNT(*) AS total FROM ({USER_TICKETS_QUERY} {condition}) matches"),
+                values(Vec::new()),
+            ))
+            .await?
+            .map(|row| row.try_get::<i64>("", "total"))
+            .transpose()?
+            .unwrap_or(0);
+
+        let offset = (page - 1) * page_size;
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!("{USER_TICKETS_QUERY} {condition} ORDER BY {order} LIMIT $5 OFFSET $6"),
+                values(vec![(page_size as i64).into(), (offset as i64).into()]),
+            ))
+            .await?;
+
+        Ok(TicketPage {
+            tickets: rows.iter().map(user_ticket).collect::<Result<_, _>>()?,
+            total: total.max(0) as u64,
+        })
+    }
+
+    async fn history(
+        &self,
+        db: &DatabaseConnection,
+        ticket_id: i32,
+    ) -> Result<Vec<TicketStatusHistoryModel>, TicketError> {
+        Ticket::find_by_id(ticket_id)
+            .one(db)
+            .await?
+            .ok_or(TicketError::TicketNotFound)?;
+
+        Ok(TicketStatusHistory::find()
+            .filter(TicketStatusHistoryColumn::TicketId.eq(ticket_id))
+            .order_by_asc(TicketStatusHistoryColumn::TicketStatusHistoryId)
+            .all(db)
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use super::*;
+
+    fn departure() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 11, 1).unwrap().and_hms_opt(8, 0, 0).unwrap()
+    }
+
+    fn tiers() -> Vec<RefundTier> {
+        vec![
+            RefundTier { min_hours_before: 48, refund_percent: 100 },
+            RefundTier { min_hours_before: 24, refund_percent: 50 },
+            RefundTier { min_hours_before: 2, refund_percent: 20 },
+        ]
+    }
+
+    fn percent_with_hours_left(tiers: &[RefundTier], hours: i64) -> i32 {
+        refund_percent(tiers, departure(), departure() - Duration::hours(hours))
+    }
+
+    #[test]
+    fn picks_the_highest_tier_still_reached() {
+        assert_eq!(percent_with_hours_left(&tiers(), 72), 100);
+        assert_eq!(percent_with_hours_left(&tiers(), 30), 50);
+        assert_eq!(percent_with_hours_left(&tiers(), 3), 20);
+    }
+
+    #[test]
+    fn refund_amount_does_not_overflow_large_fares() {
+        assert_eq!(refund_amount(i32::MAX, 100).unwrap(), i32::MAX);
+        assert_eq!(refund_amount(i32::MAX, 50).unwrap(), i32::MAX / 2);
+        assert_eq!(refund_amount(1999, 50).unwrap(), 999);
+        assert!(matches!(refund_amount(i32::MAX, 101), Err(TicketError::AmountTooLarge)));
+    }
+
+    #[test]
+    fn tier_boundaries_are_inclusive() {
+        assert_eq!(percent_with_hours_left(&tiers(), 48), 100);
+        assert_eq!(percent_with_hours_left(&tiers(), 24), 50);
+        assert_eq!(
+            refund_percent(&tiers(), departure(), departure() - Duration::hours(24) + Duration::seconds(1)),
+            20
+        );
+    }
+
+    #[test]
+    fn nothing_is_refunded_below_the_lowest_tier_or_after_departure() {
+        assert_eq!(percent_with_hours_left(&tiers(), 1), 0);
+        assert_eq!(percent_with_hours_left(&tiers(), -5), 0);
+        assert_eq!(percent_with_hours_left(&[], 100), 0);
+    }
+
+    #[test]
+    fn tier_order_does_not_matter() {
+        let mut reversed = tiers();
+        reversed.reverse();
+
+        assert_eq!(percent_with_hours_left(&reversed, 30), 50);
+    }
+
+    #[test]
+    fn zero_hour_tier_applies_until_departure() {
+        let tiers = [RefundTier { min_hours_before: 0, refund_percent: 10 }];
+
+        assert_eq!(percent_with_hours_left(&tiers, 0), 10);
+        assert_eq!(refund_percent(&tiers, departure(), departure() + Duration::seconds(1)), 0);
+    }
+}
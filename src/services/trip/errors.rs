@@ -0,0 +1,41 @@
+use sea_orm::DbErr;
+use thiserror::Error;
+
+use crate::services::payment::PaymentError;
+
+#[derive(Debug, Error)]
+pub enum TripError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("trip not found")]
+    TripNotFound,
+    #[error("the trip does not run between those stops")]
+    InvalidSegment,
+    #[error("route not found")]
+    RouteNotFound,
+    #[error("bus not found")]
+    BusNotFound,
+    #[error("route is deleted")]
+    RouteDeleted,
+    #[error("bus is deleted")]
+    BusDeleted,
+    #[error("{}", schedule_conflict_message(.0))]
+    ScheduleConflict(Vec<i32>),
+    #[error("trip has already departed")]
+    TripDeparted,
+    #[error("trip is cancelled")]
+    TripCancelled,
+    #[error("route and bus cannot change while the trip has tickets or holds")]
+    TripHasTickets,
+    #[error(transparent)]
+    Payment(#[from] PaymentError),
+}
+
+fn schedule_conflict_message(trip_ids: &[i32]) -> String {
+    if trip_ids.is_empty() {
+        return "bus is already scheduled in that window".to_string();
+    }
+
+    let trip_ids: Vec<String> = trip_ids.iter().map(i32::to_string).collect();
+    format!("bus is already scheduled on overlapping trips: {}", trip_ids.join(", "))
+}
@@ -0,0 +1,152 @@
+mod errors;
+mod trip;
+
+pub use errors::TripError;
+pub use trip::{SEGMENT_SEATS, TripServiceImpl, contains_pattern};
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sea_orm::{DatabaseConnection, FromQueryResult};
+
+use crate::entities::trip::Model as TripModel;
+use crate::services::route::Segment;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TripSort {
+    Departure,
+    /// By the cheapest seat still for sale; sold-out trips come last.
+    Price,
+}
+
+#[derive(Debug, Clone)]
+pub struct TripSearch {
+    /// Matched against the route's stops ignoring case and accents; without
+    /// them the trip is offered from its origin or to its destination.
+    pub from_location: Option<String>,
+    pub to_location: Option<String>,
+    /// Departures from the boarding stop in `[departure_from, departure_to)`.
+    pub departure_from: NaiveDateTime,
+    pub departure_to: Option<NaiveDateTime>,
+    pub sort: TripSort,
+    pub descending: bool,
+    /// 1-based.
+    pub page: u64,
+    pub page_size: u64,
+}
+
+/// A bookable ride on a trip, between the stops `from_stop` and `to_stop`,
+/// with what is left on it. `start_date` and `end_date` are the times at
+/// those stops. Fares are the segment's share of `route.base_price` plus
+/// `seat.price` over the seats free for the whole segment, absent once sold
+/// out.
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct TripAvailability {
+    pub trip_id: i32,
+    pub route_id: i32,
+    pub bus_id: i32,
+    pub start_date: NaiveDateTime,
+    pub end_date: NaiveDateTime,
+    pub from_stop: i32,
+    pub to_stop: i32,
+    pub from_location: String,
+    pub to_location: String,
+    pub bus_name: Option<String>,
+    pub license_plate: String,
+    pub seat_count: i32,
+    pub available_seats: i32,
+    pub min_price: Option<i32>,
+    pub max_price: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeatStatus {
+    Available,
+    /// Reserved during someone's checkout.
+    Held,
+    Sold,
+}
+
+/// A seat of the trip's bus; `fare` is what a ticket for it costs on the
+/// segment, and `status` whether the seat is free for all of it.
+#[derive(Debug, Clone)]
+pub struct TripSeat {
+    pub seat_id: i32,
+    pub name: String,
+    pub price: i32,
+    pub row: Option<i32>,
+    pub column: Option<i32>,
+    pub deck: i32,
+    pub fare: i32,
+    pub status: SeatStatus,
+}
+
+/// A stop of a trip and when the bus is there.
+#[derive(Debug, Clone)]
+pub struct TripStop {
+    pub stop_order: i32,
+    pub location: String,
+    pub time: NaiveDateTime,
+}
+
+/// When and with what a trip runs; `end_date` must follow `start_date`.
+#[derive(Debug, Clone)]
+pub struct TripSchedule {
+    pub route_id: i32,
+    pub bus_id: i32,
+    pub start_date: NaiveDateTime,
+    pub end_date: NaiveDateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct CancelledTrip {
+    pub trip: TripModel,
+    /// Tickets cancelled or refunded along with the trip.
+    pub tickets_cancelled: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TripPage {
+    pub trips: Vec<TripAvailability>,
+    pub total: u64,
+}
+
+#[async_trait]
+pub trait TripService: Send + Sync {
+    /// Trips on active routes and buses matching `search`, one page at a time.
+    async fn search(&self, db: &DatabaseConnection, search: &TripSearch) -> Result<TripPage, TripError>;
+
+    /// Every non-deleted seat of the trip's bus, ordered by seat id, as seen
+    /// by a passenger riding `segment`.
+    async fn seat_map(
+        &self,
+        db: &DatabaseConnection,
+        trip_id: i32,
+        segment: Segment,
+    ) -> Result<Vec<TripSeat>, TripError>;
+
+    /// The stops of the trip's route in travel order.
+    async fn trip_stops(&self, db: &DatabaseConnection, trip_id: i32) -> Result<Vec<TripStop>, TripError>;
+
+    /// Schedules a trip on an active route and bus. Fails with
+    /// `ScheduleConflict` when the bus already runs a trip in the window.
+    async fn create_trip(&self, db: &DatabaseConnection, schedule: TripSchedule) -> Result<TripModel, TripError>;
+
+    /// Reschedules a trip that has neither departed nor been cancelled, with
+    /// the checks of `create_trip`. The route and bus only change while the
+    /// trip has no tickets or holds, since they point at its stops and seats.
+    async fn update_trip(
+        &self,
+        db: &DatabaseConnection,
+        trip_id: i32,
+        schedule: TripSchedule,
+    ) -> Result<TripModel, TripError>;
+
+    /// Cancels a trip that has not departed. Its live tickets are cancelled,
+    /// paid ones refunded in full, and its holds dropped.
+    async fn cancel_trip(
+        &self,
+        db: &DatabaseConnection,
+        trip_id: i32,
+        actor_id: i32,
+    ) -> Result<CancelledTrip, TripError>;
+}
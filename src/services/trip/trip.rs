@@ -0,0 +1,469 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr,
+    EntityTrait, FromQueryResult, QueryFilter, QueryOrder, QuerySelect, RuntimeErr, Statement, TransactionTrait, Value,
+};
+
+use crate::entities::bus::Entity as Bus;
+use crate::entities::route::Entity as Route;
+use crate::entities::seat_hold::{Column as SeatHoldColumn, Entity as SeatHold};
+use crate::entities::ticket::{ActiveModel as TicketActiveModel, Column as TicketColumn, Entity as Ticket};
+use crate::entities::trip::{ActiveModel as TripActiveModel, Column as TripColumn, Entity as Trip, Model as TripModel};
+use crate::entities::sea_orm_active_enums::TicketStatus;
+use crate::services::payment::PaymentService;
+use crate::services::route::{Segment, itinerary};
+use crate::services::ticket::{SEAT_RELEASING_STATUSES, can_transition, record_transition};
+
+use super::{
+    CancelledTrip, SeatStatus, TripAvailability, TripError, TripPage, TripSchedule, TripSearch, TripSeat, TripSort,
+    TripStop,
+};
+
+/// Counts the seats of trip `t`'s bus between stops `a` and `z` as `seats`:
+/// all of them, the free ones and the lowest and highest surcharge among the
+/// free ones. A seat is taken while a ticket that is neither cancelled nor
+/// refunded, or an unexpired hold, overlaps the segment. Trip search and the
+/// journey planner share it so both apply the same rules.
+///
+/// $5 cancelled status, $6 refunded status, $7 now.
+pub const SEGMENT_SEATS: &str = r#"
+    CROSS JOIN LATERAL (
+        SELECT COUNT(*)::INT AS seat_count,
+               (COUNT(*) FILTER (WHERE NOT s.taken))::INT AS available_seats,
+               MIN(s.price) FILTER (WHERE NOT s.taken) AS min_surcharge,
+               MAX(s.price) FILTER (WHERE NOT s.taken) AS max_surcharge
+        FROM (
+            SELECT s.price,
+                   EXISTS (
+                       SELECT 1 FROM ticket k
+                       WHERE k.trip_id = t.trip_id AND k.seat_id = s.seat_id AND k.status NOT IN ($5, $6)
+                         AND k.from_stop < z.stop_order AND k.to_stop > a.stop_order
+                   ) OR EXISTS (
+                       SELECT 1 FROM seat_hold h
+                       WHERE h.trip_id = t.trip_id AND h.seat_id = s.seat_id AND h.expires_at > $7
+                         AND h.from_stop < z.stop_order AND h.to_stop > a.stop_order
+                   ) AS taken
+            FROM seat s
+            WHERE s.bus_id = t.bus_id AND NOT s.deleted
+        ) s
+    ) seats
+"#;
+
+/// One row per matching trip and segment; without a from (to) pattern the
+/// segment starts at the origin (ends at the destination). Seats are counted
+/// by `SEGMENT_SEATS`.
+///
+/// $1 from, $2 to (LIKE patterns or NULL), $3 departure_from,
+/// $4 departure_to or NULL, $5 cancelled status, $6 refunded status, $7 now.
+fn availability_query() -> String {
+    format!(
+        r#"
+    SELECT t.trip_id, t.route_id, t.bus_id,
+           t.start_date + a.offset_minutes * INTERVAL '1 minute' AS start_date,
+           COALESCE(t.start_date + z.offset_minutes * INTERVAL '1 minute', t.end_date) AS end_date,
+           a.stop_order AS from_stop, z.stop_order AS to_stop,
+           a.location AS from_location, z.location AS to_location, b.name AS bus_name, b.license_plate,
+           seats.seat_count, seats.available_seats,
+           z.fare_from_origin - a.fare_from_origin + seats.min_surcharge AS min_price,
+           z.fare_from_origin - a.fare_from_origin + seats.max_surcharge AS max_price
+    FROM trip t
+    JOIN route r ON r.route_id = t.route_id AND NOT r.deleted
+    JOIN bus b ON b.bus_id = t.bus_id AND NOT b.deleted
+    JOIN route_itinerary a ON a.route_id = t.route_id
+    JOIN route_itinerary z ON z.route_id = t.route_id AND z.stop_order > a.stop_order
+    {SEGMENT_SEATS}
+    WHERE NOT t.cancelled
+      AND CASE WHEN $1::TEXT IS NULL THEN a.stop_order = 0 ELSE fold_location(a.location) LIKE fold_location($1) END
+      AND CASE WHEN $2::TEXT IS NULL THEN z.terminal ELSE fold_location(z.location) LIKE fold_location($2) END
+      AND t.start_date + a.offset_minutes * INTERVAL '1 minute' >= $3
+      AND ($4::TIMESTAMP IS NULL OR t.start_date + a.offset_minutes * INTERVAL '1 minute' < $4)
+"#
+    )
+}
+
+/// The seats of a trip's bus with whether a ticket that is neither cancelled
+/// nor refunded, or an unexpired hold, overlaps the stops `$5..$6`; held
+/// tickets count as holds. $1 trip_id, $2 cancelled status, $3 refunded
+/// status, $4 now, $5 from_stop, $6 to_stop, $7 held status.
+const SEAT_MAP_QUERY: &str = r#"
+    SELECT s.seat_id, s.name, s.price, s.seat_row, s.seat_column, s.deck,
+           EXISTS (
+               SELECT 1 FROM ticket k
+               WHERE k.trip_id = t.trip_id AND k.seat_id = s.seat_id AND k.status NOT IN ($2, $3, $7)
+                 AND k.from_stop < $6 AND k.to_stop > $5
+           ) AS sold,
+           EXISTS (
+               SELECT 1 FROM ticket k
+               WHERE k.trip_id = t.trip_id AND k.seat_id = s.seat_id AND k.status = $7
+                 AND k.from_stop < $6 AND k.to_stop > $5
+           ) OR EXISTS (
+               SELECT 1 FROM seat_hold h
+               WHERE h.trip_id = t.trip_id AND h.seat_id = s.seat_id AND h.expires_at > $4
+                 AND h.from_stop < $6 AND h.to_stop > $5
+           ) AS held
+    FROM trip t
+    JOIN seat s ON s.bus_id = t.bus_id AND NOT s.deleted
+    WHERE t.trip_id = $1
+    ORDER BY s.seat_id
+"#;
+
+#[derive(FromQueryResult)]
+struct SeatRow {
+    seat_id: i32,
+    name: String,
+    price: i32,
+    seat_row: Option<i32>,
+    seat_column: Option<i32>,
+    deck: i32,
+    sold: bool,
+    held: bool,
+}
+
+pub struct TripServiceImpl {
+    payment_service: Arc<dyn PaymentService>,
+}
+
+impl TripServiceImpl {
+    pub fn new(payment_service: Arc<dyn PaymentService>) -> Self {
+        Self { payment_service }
+    }
+}
+
+/// A `LIKE` pattern matching `text` anywhere; wildcards in the input match
+/// literally.
+pub fn contains_pattern(text: &Option<String>) -> Option<String> {
+    let text = text.as_deref().map(str::trim).filter(|text| !text.is_empty())?;
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+
+    Some(format!("%{escaped}%"))
+}
+
+/// SQLSTATE 23P01, raised by `trip_bus_no_overlap` when a concurrent request
+/// scheduled the bus first.
+fn is_exclusion_violation(err: &DbErr) -> bool {
+    match err {
+        DbErr::Exec(RuntimeErr::SqlxError(sea_orm::sqlx::Error::Database(e)))
+        | DbErr::Query(RuntimeErr::SqlxError(sea_orm::sqlx::Error::Database(e))) => {
+            e.code().as_deref() == Some("23P01")
+        }
+        _ => false,
+    }
+}
+
+fn schedule_error(err: DbErr) -> TripError {
+    if is_exclusion_violation(&err) {
+        TripError::ScheduleConflict(Vec::new())
+    } else {
+        TripError::Database(err)
+    }
+}
+
+/// Checks the route and bus are active and the bus is free in the window,
+/// ignoring `trip_id` itself and cancelled trips. Locks the bus row so
+/// schedules for one bus are checked one at a time.
+async fn check_schedule<C: ConnectionTrait>(
+    conn: &C,
+    schedule: &TripSchedule,
+    trip_id: Option<i32>,
+) -> Result<(), TripError> {
+    let route = Route::find_by_id(schedule.route_id)
+        .one(conn)
+        .await?
+        .ok_or(TripError::RouteNotFound)?;
+    if route.deleted {
+        return Err(TripError::RouteDeleted);
+    }
+
+    let bus = Bus::find_by_id(schedule.bus_id)
+        .lock_exclusive()
+        .one(conn)
+        .await?
+        .ok_or(TripError::BusNotFound)?;
+    if bus.deleted {
+        return Err(TripError::BusDeleted);
+    }
+
+    let mut query = Trip::find()
+        .select_only()
+        .column(TripColumn::TripId)
+        .filter(TripColumn::BusId.eq(schedule.bus_id))
+        .filter(TripColumn::Cancelled.eq(false))
+        .filter(TripColumn::StartDate.lt(schedule.end_date))
+        .filter(TripColumn::EndDate.gt(schedule.start_date));
+    if let Some(trip_id) = trip_id {
+        query = query.filter(TripColumn::TripId.ne(trip_id));
+    }
+
+    let conflicts: Vec<i32> = query
+        .order_by_asc(TripColumn::TripId)
+        .into_tuple()
+        .all(conn)
+        .await?;
+    if !conflicts.is_empty() {
+        return Err(TripError::ScheduleConflict(conflicts));
+    }
+
+    Ok(())
+}
+
+/// Locks a trip that can still be changed.
+async fn open_trip<C: ConnectionTrait>(conn: &C, trip_id: i32) -> Result<TripModel, TripError> {
+    let trip = Trip::find_by_id(trip_id)
+        .lock_exclusive()
+        .one(conn)
+        .await?
+        .ok_or(TripError::TripNotFound)?;
+    if trip.cancelled {
+        return Err(TripError::TripCancelled);
+    }
+    if trip.start_date <= Utc::now().naive_utc() {
+        return Err(TripError::TripDeparted);
+    }
+
+    Ok(trip)
+}
+
+fn order_by(sort: TripSort, descending: bool) -> &'static str {
+    match (sort, descending) {
+        (TripSort::Departure, false) => "start_date ASC, trip_id ASC",
+        (TripSort::Departure, true) => "start_date DESC, trip_id DESC",
+        (TripSort::Price, false) => "min_price ASC NULLS LAST, start_date ASC, trip_id ASC",
+        (TripSort::Price, true) => "min_price DESC NULLS LAST, start_date ASC, trip_id ASC",
+    }
+}
+
+#[async_trait]
+impl super::TripService for TripServiceImpl {
+    async fn search(&self, db: &DatabaseConnection, search: &TripSearch) -> Result<TripPage, TripError> {
+        let now = Utc::now().naive_utc();
+        let query = availability_query();
+        let filters = |extra: Vec<Value>| -> Vec<Value> {
+            let mut values: Vec<Value> = vec![
+                contains_pattern(&search.from_location).into(),
+                contains_pattern(&search.to_location).into(),
+                search.departure_from.into(),
+                search.departure_to.into(),
+                TicketStatus::Cancelled.into_value().into(),
+                TicketStatus::Refunded.into_value().into(),
+                now.into(),
+            ];
+            values.extend(extra);
+            values
+        };
+
+        let total = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!("SELECT COUNT(*) AS total FROM ({query}) matches"),
+                filters(Vec::new()),
+            ))
+            .await?
+            .map(|row| row.try_get::<i64>("", "total"))
+            .transpose()?
+            .unwrap_or(0);
+
+        let offset = (search.page - 1) * search.page_size;
+        let trips = TripAvailability::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!(
+                "SELECT * FROM ({query}) matches ORDER BY {} LIMIT $8 OFFSET $9",
+                order_by(search.sort, search.descending),
+            ),
+            filters(vec![(search.page_size as i64).into(), (offset as i64).into()]),
+        ))
+        .all(db)
+        .await?;
+
+        Ok(TripPage {
+            trips,
+            total: total.max(0) as u64,
+        })
+    }
+
+    async fn seat_map(
+        &self,
+        db: &DatabaseConnection,
+        trip_id: i32,
+        segment: Segment,
+    ) -> Result<Vec<TripSeat>, TripError> {
+        let trip = Trip::find_by_id(trip_id)
+            .one(db)
+            .await?
+            .ok_or(TripError::TripNotFound)?;
+        let stops = itinerary(db, trip.route_id).await?;
+        let (from, to) = segment.resolve(&stops).ok_or(TripError::InvalidSegment)?;
+        let fare = to.fare_from_origin - from.fare_from_origin;
+
+        let rows = SeatRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            SEAT_MAP_QUERY,
+            [
+                trip_id.into(),
+                TicketStatus::Cancelled.into_value().into(),
+                TicketStatus::Refunded.into_value().into(),
+                Utc::now().naive_utc().into(),
+                from.stop_order.into(),
+                to.stop_order.into(),
+                TicketStatus::Held.into_value().into(),
+            ],
+        ))
+        .all(db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TripSeat {
+                seat_id: row.seat_id,
+                name: row.name,
+                price: row.price,
+                row: row.seat_row,
+                column: row.seat_column,
+                deck: row.deck,
+                fare: fare + row.price,
+                status: if row.sold {
+                    SeatStatus::Sold
+                } else if row.held {
+                    SeatStatus::Held
+                } else {
+                    SeatStatus::Available
+                },
+            })
+            .collect())
+    }
+
+    async fn trip_stops(&self, db: &DatabaseConnection, trip_id: i32) -> Result<Vec<TripStop>, TripError> {
+        let trip = Trip::find_by_id(trip_id)
+            .one(db)
+            .await?
+            .ok_or(TripError::TripNotFound)?;
+
+        Ok(itinerary(db, trip.route_id)
+            .await?
+            .into_iter()
+            .map(|stop| TripStop {
+                stop_order: stop.stop_order,
+                location: stop.location,
+                time: stop
+                    .offset_minutes
+                    .map_or(trip.end_date, |offset| trip.start_date + Duration::minutes(offset.into())),
+            })
+            .collect())
+    }
+
+    async fn create_trip(&self, db: &DatabaseConnection, schedule: TripSchedule) -> Result<TripModel, TripError> {
+        let txn = db.begin().await?;
+
+        check_schedule(&txn, &schedule, None).await?;
+        let trip = TripActiveModel {
+            route_id: Set(schedule.route_id),
+            bus_id: Set(schedule.bus_id),
+            start_date: Set(schedule.start_date),
+            end_date: Set(schedule.end_date),
+            cancelled: Set(false),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .map_err(schedule_error)?;
+        txn.commit().await?;
+
+        Ok(trip)
+    }
+
+    async fn update_trip(
+        &self,
+        db: &DatabaseConnection,
+        trip_id: i32,
+        schedule: TripSchedule,
+    ) -> Result<TripModel, TripError> {
+        let txn = db.begin().await?;
+
+        let trip = open_trip(&txn, trip_id).await?;
+        if trip.bus_id != schedule.bus_id || trip.route_id != schedule.route_id {
+            let now = Utc::now().naive_utc();
+            let ticket = Ticket::find()
+                .filter(TicketColumn::TripId.eq(trip_id))
+                .filter(TicketColumn::Status.is_not_in(SEAT_RELEASING_STATUSES))
+                .one(&txn)
+                .await?;
+            let hold = SeatHold::find()
+                .filter(SeatHoldColumn::TripId.eq(trip_id))
+                .filter(SeatHoldColumn::ExpiresAt.gt(now))
+                .one(&txn)
+                .await?;
+            if ticket.is_some() || hold.is_some() {
+                return Err(TripError::TripHasTickets);
+            }
+        }
+
+        check_schedule(&txn, &schedule, Some(trip_id)).await?;
+        let mut active: TripActiveModel = trip.into();
+        active.route_id = Set(schedule.route_id);
+        active.bus_id = Set(schedule.bus_id);
+        active.start_date = Set(schedule.start_date);
+        active.end_date = Set(schedule.end_date);
+        let trip = active.update(&txn).await.map_err(schedule_error)?;
+        txn.commit().await?;
+
+        Ok(trip)
+    }
+
+    async fn cancel_trip(
+        &self,
+        db: &DatabaseConnection,
+        trip_id: i32,
+        actor_id: i32,
+    ) -> Result<CancelledTrip, TripError> {
+        let txn = db.begin().await?;
+        let now = Utc::now().naive_utc();
+
+        let trip = open_trip(&txn, trip_id).await?;
+        let tickets = Ticket::find()
+            .filter(TicketColumn::TripId.eq(trip_id))
+            .filter(TicketColumn::Status.is_not_in(SEAT_RELEASING_STATUSES))
+            .order_by_asc(TicketColumn::TicketId)
+            .lock_exclusive()
+            .all(&txn)
+            .await?;
+
+        let mut tickets_cancelled = 0;
+        for ticket in tickets {
+            // The trip is called off, so paid fares go back in full.
+            let (to, refund_amount) = match ticket.status {
+                TicketStatus::Paid => (TicketStatus::Refunded, ticket.price),
+                _ => (TicketStatus::Cancelled, 0),
+            };
+            let from = ticket.status;
+            if !can_transition(from, to) {
+                continue;
+            }
+
+            let ticket_id = ticket.ticket_id;
+            let mut active: TicketActiveModel = ticket.into();
+            active.status = Set(to);
+            active.refund_amount = Set(Some(refund_amount));
+            active.update(&txn).await?;
+            record_transition(&txn, ticket_id, Some(from), to, Some(actor_id), now).await?;
+            self.payment_service.refund_ticket(&txn, ticket_id, refund_amount).await?;
+            tickets_cancelled += 1;
+        }
+
+        SeatHold::delete_many()
+            .filter(SeatHoldColumn::TripId.eq(trip_id))
+            .exec(&txn)
+            .await?;
+
+        let mut active: TripActiveModel = trip.into();
+        active.cancelled = Set(true);
+        let trip = active.update(&txn).await?;
+        txn.commit().await?;
+
+        Ok(CancelledTrip { trip, tickets_cancelled })
+    }
+}